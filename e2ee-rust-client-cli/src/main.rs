@@ -7,7 +7,13 @@ use crossterm::{
 };
 use e2ee_rust_client_lib::Client;
 use e2ee_rust_common::{
-    crypto::{curve::curve25519::Curve25519, pqkem::crystalskyber512::CrystalsKyber512},
+    crypto::{
+        aead::enum_aead_types::AEADType, curve::enum_elliptic_curve_type::EllipticCurveType,
+        pqkem::enum_pqkem_type::PQKEMType,
+    },
+    errors::pqxdh::PQXDHError,
+    hash::enum_hash_types::HashType,
+    pqxdh::parameters::PqxdhParameters,
     storage::client::traits::ClientStorage,
 };
 use e2ee_rust_common::{
@@ -15,21 +21,28 @@ use e2ee_rust_common::{
     storage::storage_interface::StorageInterface,
 };
 use e2ee_rust_sqlite_storage::SQLiteStorage;
+use uuid::Uuid;
 
 // curve        A Montgomery curve for which XEdDSA is specified, at present this is one of curve25519 or curve448
-const CURVE_TYPE: Curve25519 = Curve25519 {};
+const CURVE_TYPE: EllipticCurveType = EllipticCurveType::CURVE25519;
 
 // hash         A 256 or 512-bit hash function (e.g. SHA-256 or SHA-512)
-// const HASH_TYPE: HashType = HashType::SHA256;
+const HASH_TYPE: HashType = HashType::SHA256;
 
-// info         An optional ASCII string with a maximum length of 255 bytesAn ASCII string identifying the application with a minimum length of 8 bytes
-// const INFO: &str = "PQXDHTestApplication";
+// info         An ASCII string identifying the application with a minimum length of 8 bytes
+const INFO: &str = "PQXDHTestApplication";
 
 // pqkem        A post-quantum key encapsulation mechanism that has IND-CCA post-quantum security (e.g. Crystals-Kyber-1024)
-const PQKEM_TYPE: CrystalsKyber512 = CrystalsKyber512 {};
+const PQKEM_TYPE: PQKEMType = PQKEMType::KYBER1024;
 
 // aead         A scheme for authenticated encryption with associated data that has IND-CPA and INT-CTXT post-quantum security
-// const AEAD_TYPE: AES256GCM = AES256GCM {};
+const AEAD_TYPE: AEADType = AEADType::AES256GCM;
+
+// File holding the z85 encoded CurveZMQ public key of the server, written by the server when libzmq supports CURVE
+const SERVER_PUBLIC_KEY_FILE: &str = "./server-curve.pub";
+
+// Without a server key, the client only connects in cleartext when started with this flag
+const ALLOW_INSECURE_TRANSPORT_FLAG: &str = "--allow-insecure-transport";
 
 fn clear_screen(out: &mut Stdout) {
     out.queue(Hide).unwrap();
@@ -39,6 +52,8 @@ fn clear_screen(out: &mut Stdout) {
 }
 
 fn main() -> Result<(), GeneralError> {
+    env_logger::init();
+
     // Stdout output
     let mut out = stdout();
 
@@ -50,17 +65,164 @@ fn main() -> Result<(), GeneralError> {
     client_storage.init_client().to_general_error()?;
     println!("Client storage initialized");
 
+    // Validate the PQXDH parameters
+    let parameters = PqxdhParameters::new(CURVE_TYPE, HASH_TYPE, INFO, PQKEM_TYPE, AEAD_TYPE)
+        .map_err(|e| GeneralError::PQXDH(PQXDHError::InvalidParameters(e)))?;
+
+    // Pin the server key when the server published one
+    let server_public_key = std::fs::read_to_string(SERVER_PUBLIC_KEY_FILE).ok();
+    let allow_insecure_transport = std::env::args().any(|arg| arg == ALLOW_INSECURE_TRANSPORT_FLAG);
+    if server_public_key.is_none() {
+        if allow_insecure_transport {
+            println!(
+                "No server key found in {}, the connection will not be encrypted",
+                SERVER_PUBLIC_KEY_FILE
+            );
+        } else {
+            println!(
+                "No server key found in {}, start with {} to connect in cleartext",
+                SERVER_PUBLIC_KEY_FILE, ALLOW_INSECURE_TRANSPORT_FLAG
+            );
+        }
+    }
+
     // Start the client
-    let _client = Client::new(client_storage, CURVE_TYPE, PQKEM_TYPE)?;
+    let mut client_builder = Client::builder(client_storage, parameters)
+        .allow_insecure_transport(allow_insecure_transport);
+    if let Some(server_public_key) = &server_public_key {
+        client_builder = client_builder.server_public_key(server_public_key);
+    }
+    let client = client_builder.build()?;
 
     // Print the greet message
     clear_screen(&mut out);
     println!("Client ready");
+    print_help();
 
-    // Wait for the user to press enter
-    println!("Press enter to exit...");
+    // Run the commands until the user exits
     let mut input = String::new();
-    std::io::stdin().read_line(&mut input).unwrap();
+    loop {
+        input.clear();
+        if std::io::stdin().read_line(&mut input).unwrap() == 0 {
+            break;
+        }
+
+        let args: Vec<&str> = input.split_whitespace().collect();
+        match args.as_slice() {
+            [] | ["exit"] => break,
+            ["send", peer, ..] => send_message(&client, peer, &input),
+            ["inbox"] => show_received_messages(&client),
+            ["safety-number", peer] => show_safety_number(&client, peer),
+            ["verify", peer, scanned] => verify_safety_number(&client, peer, scanned),
+            _ => print_help(),
+        }
+    }
 
     Ok(())
 }
+
+fn print_help() {
+    println!("Commands:");
+    println!("  send <peer uuid> <message>              Send a message to a peer");
+    println!(
+        "  inbox                                   Show the messages received since the last call"
+    );
+    println!(
+        "  safety-number <peer uuid>               Display the safety number shared with a peer"
+    );
+    println!("  verify <peer uuid> <scannable string>   Compare the peer's scannable safety number with ours");
+    println!("  exit                                    Exit (or press enter)");
+}
+
+fn send_message<S: ClientStorage + Send + Sync + 'static>(
+    client: &Client<S>,
+    peer: &str,
+    input: &str,
+) {
+    let peer_uuid = match Uuid::parse_str(peer) {
+        Ok(peer_uuid) => peer_uuid,
+        Err(_) => {
+            println!("Invalid peer uuid: {}", peer);
+            return;
+        }
+    };
+
+    // The message is the rest of the line, with its inner spacing
+    let message = input
+        .trim()
+        .splitn(3, char::is_whitespace)
+        .nth(2)
+        .unwrap_or("")
+        .trim();
+    if message.is_empty() {
+        print_help();
+        return;
+    }
+
+    match client.send_message(&peer_uuid, message.as_bytes()) {
+        Ok(()) => println!("Message sent"),
+        Err(e) => println!("Cannot send the message: {}", e),
+    }
+}
+
+fn show_received_messages<S: ClientStorage + Send + Sync + 'static>(client: &Client<S>) {
+    let messages = client.received_messages();
+    if messages.is_empty() {
+        println!("No new messages");
+    }
+    for message in messages {
+        println!(
+            "{}: {}",
+            message.sender_uuid,
+            String::from_utf8_lossy(&message.plaintext)
+        );
+    }
+}
+
+fn show_safety_number<S: ClientStorage + Send + Sync + 'static>(client: &Client<S>, peer: &str) {
+    let safety_number = match Uuid::parse_str(peer) {
+        Ok(peer_uuid) => client.safety_number(&peer_uuid),
+        Err(_) => {
+            println!("Invalid peer uuid: {}", peer);
+            return;
+        }
+    };
+
+    match safety_number {
+        Ok(safety_number) => {
+            // Print the digits in 3 rows of 4 groups of 5, as they are read out loud
+            let digits = safety_number.displayable();
+            println!("Safety number:");
+            for row in digits.as_bytes().chunks(20) {
+                let groups: Vec<&str> = row
+                    .chunks(5)
+                    .map(|group| std::str::from_utf8(group).unwrap())
+                    .collect();
+                println!("  {}", groups.join(" "));
+            }
+            println!("Scannable: {}", safety_number.scannable());
+        }
+        Err(e) => println!("Cannot compute the safety number: {}", e),
+    }
+}
+
+fn verify_safety_number<S: ClientStorage + Send + Sync + 'static>(
+    client: &Client<S>,
+    peer: &str,
+    scanned: &str,
+) {
+    let safety_number = match Uuid::parse_str(peer) {
+        Ok(peer_uuid) => client.safety_number(&peer_uuid),
+        Err(_) => {
+            println!("Invalid peer uuid: {}", peer);
+            return;
+        }
+    };
+
+    match safety_number.map(|safety_number| safety_number.compare_scannable(scanned)) {
+        Ok(Ok(true)) => println!("Safety numbers match"),
+        Ok(Ok(false)) => println!("Safety numbers DO NOT match"),
+        Ok(Err(e)) => println!("Cannot read the scannable safety number: {:?}", e),
+        Err(e) => println!("Cannot compute the safety number: {}", e),
+    }
+}
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use e2ee_rust_common::{
+    errors::{config::ConfigError, general::GeneralError},
+    pqxdh::parameters::PqxdhParameters,
+    storage::client::traits::ClientStorage,
+};
+
+use crate::Client;
+
+// Settings of a client, fixed when the client is built
+#[derive(Debug, Clone)]
+pub(crate) struct ClientConfig {
+    pub(crate) endpoint: String,
+    pub(crate) heartbeat_interval: Duration,
+    pub(crate) one_time_curve_prekeys: usize,
+    pub(crate) one_time_pqkem_prekeys: usize,
+    pub(crate) server_public_key: Option<String>,
+    pub(crate) allow_insecure_transport: bool,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "tcp://localhost:5555".to_string(),
+            heartbeat_interval: Duration::from_secs(1),
+            one_time_curve_prekeys: 10,
+            one_time_pqkem_prekeys: 10,
+            server_public_key: None,
+            allow_insecure_transport: false,
+        }
+    }
+}
+
+impl ClientConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        if !["tcp://", "ipc://"]
+            .iter()
+            .any(|transport| self.endpoint.starts_with(transport))
+        {
+            return Err(ConfigError::InvalidValue("endpoint", self.endpoint.clone()));
+        }
+
+        if self.heartbeat_interval.is_zero() {
+            return Err(ConfigError::InvalidValue(
+                "heartbeat_interval",
+                format!("{:?}", self.heartbeat_interval),
+            ));
+        }
+
+        // Every batch must hold at least one prekey, otherwise the server keeps asking for more
+        if self.one_time_curve_prekeys == 0 {
+            return Err(ConfigError::InvalidValue(
+                "one_time_curve_prekeys",
+                self.one_time_curve_prekeys.to_string(),
+            ));
+        }
+        if self.one_time_pqkem_prekeys == 0 {
+            return Err(ConfigError::InvalidValue(
+                "one_time_pqkem_prekeys",
+                self.one_time_pqkem_prekeys.to_string(),
+            ));
+        }
+
+        // The pinned key must be a z85 encoded CurveZMQ public key
+        if let Some(server_public_key) = &self.server_public_key {
+            if !zmq::z85_decode(server_public_key).is_ok_and(|key| key.len() == 32) {
+                return Err(ConfigError::InvalidValue(
+                    "server_public_key",
+                    server_public_key.clone(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Configures and starts a client
+// The storage and the PQXDH parameters are required, every other setting has a default
+pub struct ClientBuilder<S: ClientStorage + Send> {
+    client_storage: S,
+    parameters: PqxdhParameters,
+    config: ClientConfig,
+}
+
+impl<S> ClientBuilder<S>
+where
+    S: ClientStorage + Send + Sync + 'static,
+{
+    // The PQXDH parameters must be the same for every client talking to each other
+    pub fn new(client_storage: S, parameters: PqxdhParameters) -> Self {
+        Self {
+            client_storage,
+            parameters,
+            config: ClientConfig::default(),
+        }
+    }
+
+    // ZMQ endpoint of the server, tcp://localhost:5555 by default
+    pub fn endpoint(mut self, endpoint: &str) -> Self {
+        self.config.endpoint = endpoint.to_string();
+        self
+    }
+
+    // Time between two heartbeats, one second by default
+    pub fn heartbeat_interval(mut self, heartbeat_interval: Duration) -> Self {
+        self.config.heartbeat_interval = heartbeat_interval;
+        self
+    }
+
+    // Number of one time curve prekeys generated at registration and when the server runs low, 10 by default
+    pub fn one_time_curve_prekeys(mut self, one_time_curve_prekeys: usize) -> Self {
+        self.config.one_time_curve_prekeys = one_time_curve_prekeys;
+        self
+    }
+
+    // Number of one time PQKEM prekeys generated at registration and when the server runs low, 10 by default
+    pub fn one_time_pqkem_prekeys(mut self, one_time_pqkem_prekeys: usize) -> Self {
+        self.config.one_time_pqkem_prekeys = one_time_pqkem_prekeys;
+        self
+    }
+
+    // z85 encoded CurveZMQ public key of the server
+    // Without a pinned key, the client refuses to connect unless the insecure transport is allowed
+    pub fn server_public_key(mut self, server_public_key: &str) -> Self {
+        self.config.server_public_key = Some(server_public_key.trim().to_string());
+        self
+    }
+
+    // Lets the client connect in cleartext when no server key is pinned
+    // The server is then not authenticated and the metadata of the requests can be read on the network
+    pub fn allow_insecure_transport(mut self, allow_insecure_transport: bool) -> Self {
+        self.config.allow_insecure_transport = allow_insecure_transport;
+        self
+    }
+
+    // Validates the settings, then starts the client
+    pub fn build(self) -> Result<Client<S>, GeneralError> {
+        self.config.validate().map_err(GeneralError::Config)?;
+        Client::start(self.client_storage, self.parameters, self.config)
+    }
+}
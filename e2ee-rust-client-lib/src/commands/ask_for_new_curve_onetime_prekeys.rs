@@ -1,10 +1,7 @@
 use e2ee_rust_common::{
-    crypto::{
-        curve::{
-            keys::{IdentifiedEllipticCurveKeyPair, IdentifiedEllipticCurvePublicKey},
-            traits::EllipticCurveAlgorithm,
-        },
-        pqkem::traits::PQKEMAlgorithm,
+    crypto::curve::{
+        keys::{IdentifiedEllipticCurveKeyPair, IdentifiedEllipticCurvePublicKey},
+        traits::EllipticCurveAlgorithm,
     },
     errors::general::GeneralError,
     messages::client::{
@@ -17,21 +14,24 @@ use e2ee_rust_common::{
 use log::{debug, error};
 use rand::{CryptoRng, RngCore};
 
-use crate::{ClientData, ONE_TIME_CURVE_PREKEYS};
+use crate::ClientData;
 
 pub fn command_ask_for_new_curve_onetime_prekeys<
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
     S: ClientStorage + Send + Sync + 'static,
     R: RngCore + CryptoRng,
 >(
-    client: &ClientData<T, U, S>,
+    client: &ClientData<S>,
     rng: &mut R,
 ) -> Result<ClientMessage, GeneralError> {
     // Generate a new set of curve one time prekeys
-    let new_keys: Vec<IdentifiedEllipticCurveKeyPair> = (0..ONE_TIME_CURVE_PREKEYS)
+    let new_keys: Vec<IdentifiedEllipticCurveKeyPair> = (0..client.config.one_time_curve_prekeys)
         .into_iter()
-        .map(|_| client.curve_algorithm.generate_identified_key_pair(rng))
+        .map(|_| {
+            client
+                .parameters
+                .curve_type()
+                .generate_identified_key_pair(rng)
+        })
         .collect();
 
     // Store it in the private bundle
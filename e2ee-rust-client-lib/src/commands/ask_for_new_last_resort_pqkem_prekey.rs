@@ -17,16 +17,17 @@ use rand::{CryptoRng, RngCore};
 use crate::ClientData;
 
 pub fn command_ask_for_new_last_resort_pqkem_prekey<
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
     S: ClientStorage + Send + Sync + 'static,
     R: RngCore + CryptoRng,
 >(
-    client: &ClientData<T, U, S>,
+    client: &ClientData<S>,
     rng: &mut R,
 ) -> Result<ClientMessage, GeneralError> {
     // Generate a new signed pqkem prekey and the signature
-    let new_last_resort_prekey = client.pqkem_algorithm.generate_identified_key_pair(rng);
+    let new_last_resort_prekey = client
+        .parameters
+        .pqkem_type()
+        .generate_identified_key_pair(rng);
 
     // Sign the new last resort prekey
     let signature = {
@@ -35,7 +36,8 @@ pub fn command_ask_for_new_last_resort_pqkem_prekey<
 
         // Sign the new last resort prekey
         let signature = client
-            .curve_algorithm
+            .parameters
+            .curve_type()
             .xeddsa_sign(
                 &client_storage
                     .get_private_key_bundle()
@@ -20,35 +20,38 @@ use e2ee_rust_common::{
 use log::{debug, error};
 use rand::{CryptoRng, RngCore};
 
-use crate::{ClientData, ONE_TIME_PQKEM_PREKEYS};
+use crate::ClientData;
 
 pub fn command_ask_for_new_signed_pqkem_onetime_prekeys<
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
     S: ClientStorage + Send + Sync + 'static,
     R: RngCore + CryptoRng,
 >(
-    client: &ClientData<T, U, S>,
+    client: &ClientData<S>,
     rng: &mut R,
 ) -> Result<ClientMessage, GeneralError> {
     // Generate a new set of pqkem one time prekeys
-    let new_keys: Vec<IdentifiedPQKEMKeyPair> = (0..ONE_TIME_PQKEM_PREKEYS)
+    let new_keys: Vec<IdentifiedPQKEMKeyPair> = (0..client.config.one_time_pqkem_prekeys)
         .into_iter()
-        .map(|_| client.pqkem_algorithm.generate_identified_key_pair(rng))
+        .map(|_| {
+            client
+                .parameters
+                .pqkem_type()
+                .generate_identified_key_pair(rng)
+        })
         .collect();
 
     // Generate the signatures
-    let signatures: Vec<[u8; 64]> = {
+    let signatures: Vec<Vec<u8>> = {
         // Lock the client storage
         let client_storage = client.client_storage_mutex.lock().unwrap();
 
         // Sign the new keys
         let signatures = new_keys
-            .clone()
-            .into_iter()
+            .iter()
             .map(|k| {
                 client
-                    .curve_algorithm
+                    .parameters
+                    .curve_type()
                     .xeddsa_sign(
                         &client_storage
                             .get_private_key_bundle()
@@ -86,7 +89,7 @@ pub fn command_ask_for_new_signed_pqkem_onetime_prekeys<
                 .enumerate()
                 .map(|(i, k)| SignedPQKEMPrekey {
                     identified_public_key: IdentifiedPQKEMPublicKey::from_identified_key_pair(k),
-                    signature: signatures[i],
+                    signature: signatures[i].clone(),
                 })
                 .collect(),
         }),
@@ -1,10 +1,7 @@
 use e2ee_rust_common::{
-    crypto::{
-        curve::{
-            keys::{IdentifiedEllipticCurveKeyPair, IdentifiedEllipticCurvePublicKey},
-            traits::EllipticCurveAlgorithm,
-        },
-        pqkem::traits::PQKEMAlgorithm,
+    crypto::curve::{
+        keys::{IdentifiedEllipticCurveKeyPair, IdentifiedEllipticCurvePublicKey},
+        traits::EllipticCurveAlgorithm,
     },
     errors::general::{GeneralError, ToGeneralError},
     messages::client::{
@@ -20,17 +17,17 @@ use rand::{CryptoRng, RngCore};
 use crate::ClientData;
 
 pub fn command_ask_for_new_signed_prekey<
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
     S: ClientStorage + Send + Sync + 'static,
     R: RngCore + CryptoRng,
 >(
-    client: &ClientData<T, U, S>,
+    client: &ClientData<S>,
     rng: &mut R,
 ) -> Result<ClientMessage, GeneralError> {
     // Generate a new curve signed prekey and the signature
-    let new_signed_prekey: IdentifiedEllipticCurveKeyPair =
-        client.curve_algorithm.generate_identified_key_pair(rng);
+    let new_signed_prekey: IdentifiedEllipticCurveKeyPair = client
+        .parameters
+        .curve_type()
+        .generate_identified_key_pair(rng);
 
     // Create the signature for the new signed prekey
 
@@ -40,7 +37,8 @@ pub fn command_ask_for_new_signed_prekey<
 
         // Generate the signature
         let signature = client
-            .curve_algorithm
+            .parameters
+            .curve_type()
             .xeddsa_sign(
                 &client_storage
                     .get_private_key_bundle()
@@ -52,7 +50,8 @@ pub fn command_ask_for_new_signed_prekey<
             )
             .map_err(|e| GeneralError::XedDSA(e))?;
 
-        // Store it in the private bundle
+        // Store it in the private bundle, replacing the previous one: first messages still built
+        // against the previous signed prekey are rejected and the peers have to fetch a new bundle
         client_storage
             .update_curve_signed_prekey(&new_signed_prekey)
             .map_err(|e| {
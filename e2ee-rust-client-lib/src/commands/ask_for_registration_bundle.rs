@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use e2ee_rust_common::{
-    crypto::{curve::traits::EllipticCurveAlgorithm, pqkem::traits::PQKEMAlgorithm},
     errors::general::{GeneralError, ToGeneralError},
     messages::client::client_message::{ClientMessage, ClientMessageType},
     pqxdh::registration_bundle::RegistrationBundle,
@@ -12,12 +11,10 @@ use rand::{CryptoRng, RngCore};
 use crate::ClientData;
 
 pub fn command_ask_for_registration_bundle<
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
     S: ClientStorage + Send + Sync + 'static,
     R: RngCore + CryptoRng,
 >(
-    client: &Arc<ClientData<T, U, S>>,
+    client: &Arc<ClientData<S>>,
     rng: &mut R,
 ) -> Result<ClientMessage, GeneralError> {
     // Create the Client Message
@@ -33,7 +30,7 @@ pub fn command_ask_for_registration_bundle<
     // Create the registration bundle
     msg.registration_bundle = Some(RegistrationBundle::from_private_bundle(
         &private_bundle,
-        &client.curve_algorithm,
+        client.parameters.curve_type(),
         rng,
     )?);
 
@@ -1,7 +1,6 @@
 use std::sync::Arc;
 
 use e2ee_rust_common::{
-    crypto::{curve::traits::EllipticCurveAlgorithm, pqkem::traits::PQKEMAlgorithm},
     errors::general::GeneralError,
     messages::{client::client_message::ClientMessage, server::server_message::ServerCommand},
     storage::client::traits::ClientStorage,
@@ -19,13 +18,9 @@ use crate::{
     ClientData,
 };
 
-pub fn handle_server_command<
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
-    S: ClientStorage + Send + Sync + 'static,
->(
+pub fn handle_server_command<S: ClientStorage + Send + Sync + 'static>(
     server_command: &ServerCommand,
-    client: &Arc<ClientData<T, U, S>>,
+    client: &Arc<ClientData<S>>,
 ) -> Result<ClientMessage, GeneralError> {
     debug!("Handling server command: {:?}", server_command);
     let mut rng = rand::thread_rng();
@@ -0,0 +1,57 @@
+use e2ee_rust_common::{
+    crypto::curve::traits::EllipticCurveAlgorithm,
+    errors::general::{GeneralError, ToGeneralError},
+    messages::{
+        client::{
+            authentication_response::AuthenticationResponse,
+            client_message::{ClientMessage, ClientMessageType},
+        },
+        server::server_authentication_challenge::ServerAuthenticationChallenge,
+    },
+    storage::client::traits::ClientStorage,
+};
+use rand::{CryptoRng, RngCore};
+
+use crate::ClientData;
+
+// Proves to the server that we own the registered identity key by signing its challenge
+pub fn data_authentication_challenge<
+    S: ClientStorage + Send + Sync + 'static,
+    R: RngCore + CryptoRng,
+>(
+    challenge: &ServerAuthenticationChallenge,
+    client: &ClientData<S>,
+    rng: &mut R,
+) -> Result<ClientMessage, GeneralError> {
+    let signature = {
+        // Lock the client storage
+        let client_storage = client.client_storage_mutex.lock().unwrap();
+
+        // Sign the challenge with the identity key
+        client
+            .parameters
+            .curve_type()
+            .xeddsa_sign(
+                &client_storage
+                    .get_private_key_bundle()
+                    .to_general_error()?
+                    .identity_key
+                    .private_key,
+                &challenge.signed_message(client.client_uuid),
+                rng,
+            )
+            .map_err(GeneralError::XedDSA)?
+    };
+
+    // The requests of the session opened by this challenge carry its token
+    *client.session_token.lock().unwrap() = challenge.session_token.clone();
+
+    // Return the message
+    let mut msg = ClientMessage::new(
+        ClientMessageType::AuthenticationResponse,
+        client.client_uuid,
+    );
+    msg.authentication_response = Some(AuthenticationResponse { signature });
+
+    Ok(msg)
+}
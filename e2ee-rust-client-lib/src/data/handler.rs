@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use e2ee_rust_common::{
+    errors::general::GeneralError,
+    messages::{
+        client::client_message::ClientMessage,
+        server::server_message::{ServerDataType, ServerMessageData},
+    },
+    storage::client::traits::ClientStorage,
+};
+use log::{debug, error};
+
+use crate::{
+    data::{
+        authentication_challenge::data_authentication_challenge,
+        receive_envelope::data_receive_envelope,
+    },
+    ClientData,
+};
+
+pub fn handle_server_data<S: ClientStorage + Send + Sync + 'static>(
+    server_data: &ServerMessageData,
+    client: &Arc<ClientData<S>>,
+) -> Result<ClientMessage, GeneralError> {
+    debug!("Handling server data: {:?}", server_data.data_type);
+    let mut rng = rand::thread_rng();
+
+    match server_data.data_type {
+        ServerDataType::Envelope => {
+            debug!("Processing envelope from mailbox");
+            data_receive_envelope(server_data.envelope.as_ref().unwrap(), client, &mut rng)
+        }
+        ServerDataType::AuthenticationChallenge => {
+            debug!("Answering authentication challenge");
+            data_authentication_challenge(
+                server_data.authentication_challenge.as_ref().unwrap(),
+                client,
+                &mut rng,
+            )
+        }
+        ServerDataType::PeerBundle => {
+            error!("Received an unsolicited peer bundle");
+            Err(GeneralError::ClientError)
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod authentication_challenge;
+pub mod handler;
+mod receive_envelope;
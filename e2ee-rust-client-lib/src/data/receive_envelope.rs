@@ -0,0 +1,433 @@
+use e2ee_rust_common::{
+    errors::general::{GeneralError, ToGeneralError},
+    messages::{
+        client::{
+            ack_envelope::AckEnvelope,
+            client_message::{ClientMessage, ClientMessageType},
+        },
+        server::server_envelope::{ServerEnvelope, ServerEnvelopeType},
+    },
+    pqxdh::responder::respond,
+    protobuf::utils::{decode_first_message, decode_ratchet_message},
+    ratchet::session::RatchetSession,
+    storage::client::traits::ClientStorage,
+};
+use log::{debug, error, info, warn};
+use rand::{CryptoRng, RngCore};
+
+use crate::{ClientData, ReceivedMessage};
+
+pub fn data_receive_envelope<S: ClientStorage + Send + Sync + 'static, R: RngCore + CryptoRng>(
+    envelope: &ServerEnvelope,
+    client: &ClientData<S>,
+    rng: &mut R,
+) -> Result<ClientMessage, GeneralError> {
+    match envelope.envelope_type {
+        ServerEnvelopeType::FirstMessage => {
+            debug!("Received first message from {}", envelope.sender_uuid);
+            let first_message = match decode_first_message(&envelope.payload) {
+                Ok(first_message) => first_message,
+                Err(e) => {
+                    error!(
+                        "Dropping malformed first message from {}: {:?}",
+                        envelope.sender_uuid, e
+                    );
+                    return Ok(ack_envelope(envelope, client));
+                }
+            };
+
+            // Lock the client storage
+            let client_storage = client.client_storage_mutex.lock().unwrap();
+
+            // Run the responder side of the key agreement
+            let private_bundle = client_storage.get_private_key_bundle().to_general_error()?;
+            match respond(&first_message, &private_bundle, &client.parameters) {
+                Ok(mut output) => {
+                    info!(
+                        "Completed key agreement with {} ({} bytes of initial data)",
+                        envelope.sender_uuid,
+                        output.plaintext.len()
+                    );
+
+                    // Start the ratchet session from the shared key, replacing any previous session with the peer
+                    let session = RatchetSession::new_responder(
+                        &output.shared_key,
+                        &output.associated_data,
+                        &first_message.peer_identity_key,
+                        &private_bundle.curve_prekey.key_pair,
+                    );
+                    client_storage
+                        .store_session(&envelope.sender_uuid, &session)
+                        .to_general_error()?;
+
+                    // Delete the one-time prekeys that were used, the last resort prekey is kept
+                    // Only once the session is stored: until then the envelope is left in the mailbox, and
+                    // processing it again needs the prekeys
+                    debug!("Removing the used one-time prekeys");
+                    client_storage
+                        .remove_one_time_prekeys(
+                            output.used_one_time_curve_prekey_id.as_ref(),
+                            output.used_one_time_pqkem_prekey_id.as_ref(),
+                        )
+                        .to_general_error()?;
+
+                    // The initial ciphertext is usually empty, the messages following in the ratchet
+                    if !output.plaintext.is_empty() {
+                        deliver(envelope, std::mem::take(&mut output.plaintext), client);
+                    }
+                }
+                // A first message that fails the key agreement never will succeed, so it is still acknowledged
+                Err(e) => error!(
+                    "Failed to process first message from {}: {:?}",
+                    envelope.sender_uuid, e
+                ),
+            }
+        }
+        ServerEnvelopeType::Message => {
+            debug!("Received message from {}", envelope.sender_uuid);
+            let ratchet_message = match decode_ratchet_message(&envelope.payload) {
+                Ok(ratchet_message) => ratchet_message,
+                Err(e) => {
+                    error!(
+                        "Dropping malformed message from {}: {:?}",
+                        envelope.sender_uuid, e
+                    );
+                    return Ok(ack_envelope(envelope, client));
+                }
+            };
+
+            // Lock the client storage
+            let client_storage = client.client_storage_mutex.lock().unwrap();
+
+            // Load the session established with the sender
+            let mut session = match client_storage
+                .load_session(&envelope.sender_uuid)
+                .to_general_error()?
+            {
+                Some(session) => session,
+                None => {
+                    warn!(
+                        "Dropping message from {}: no session established",
+                        envelope.sender_uuid
+                    );
+                    return Ok(ack_envelope(envelope, client));
+                }
+            };
+
+            match session.decrypt(
+                &ratchet_message,
+                client.parameters.curve_type(),
+                client.parameters.aead_type(),
+                client.parameters.hash_type(),
+                rng,
+            ) {
+                Ok(plaintext) => {
+                    // Store the advanced session before acknowledging, so that the message key is never reused
+                    client_storage
+                        .store_session(&envelope.sender_uuid, &session)
+                        .to_general_error()?;
+                    info!(
+                        "Received {} bytes from {}",
+                        plaintext.len(),
+                        envelope.sender_uuid
+                    );
+                    deliver(envelope, plaintext, client);
+                }
+                // The session is unchanged, and the message can never be decrypted later
+                Err(e) => error!(
+                    "Failed to decrypt message from {}: {:?}",
+                    envelope.sender_uuid, e
+                ),
+            }
+        }
+    }
+
+    Ok(ack_envelope(envelope, client))
+}
+
+// Hands a decrypted message to the application, once the session is stored
+fn deliver<S: ClientStorage + Send + Sync + 'static>(
+    envelope: &ServerEnvelope,
+    plaintext: Vec<u8>,
+    client: &ClientData<S>,
+) {
+    let message = ReceivedMessage {
+        sender_uuid: envelope.sender_uuid,
+        plaintext,
+    };
+    if client.inbox_sender.send(message).is_err() {
+        warn!(
+            "Dropping message from {}: the client was closed",
+            envelope.sender_uuid
+        );
+    }
+}
+
+// Acknowledges the envelope so that the server removes it from the mailbox
+fn ack_envelope<S: ClientStorage + Send + Sync + 'static>(
+    envelope: &ServerEnvelope,
+    client: &ClientData<S>,
+) -> ClientMessage {
+    let mut msg = ClientMessage::new(ClientMessageType::AckEnvelope, client.client_uuid);
+    msg.ack_envelope = Some(AckEnvelope {
+        envelope_id: envelope.id,
+    });
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Mutex,
+    };
+
+    use e2ee_rust_common::{
+        crypto::{
+            aead::enum_aead_types::AEADType,
+            curve::{
+                enum_elliptic_curve_type::EllipticCurveType, keys::IdentifiedEllipticCurveKeyPair,
+            },
+            pqkem::{enum_pqkem_type::PQKEMType, keys::IdentifiedPQKEMKeyPair},
+        },
+        hash::enum_hash_types::HashType,
+        messages::{
+            client::client_message::ClientMessageType,
+            server::server_envelope::{ServerEnvelope, ServerEnvelopeType},
+        },
+        pqxdh::{
+            initiator::initiate, parameters::PqxdhParameters, prekey_bundle::PrekeyBundle,
+            private_bundle::PrivateBundle, registration_bundle::RegistrationBundle,
+        },
+        protobuf::{server::PbServerEnvelope, utils::create_first_message},
+        ratchet::session::RatchetSession,
+        storage::{
+            client::traits::ClientStorage, errors::StorageInterfaceError,
+            storage_interface::StorageInterface,
+        },
+    };
+    use e2ee_rust_sqlite_storage::SQLiteStorage;
+    use uuid::Uuid;
+
+    use crate::{builder::ClientConfig, ClientData};
+
+    use super::data_receive_envelope;
+
+    // SQLite client storage in the temporary directory, which can be told to fail storing sessions
+    struct FailingStorage {
+        storage: SQLiteStorage,
+        name: String,
+        fail_store_session: AtomicBool,
+    }
+
+    impl FailingStorage {
+        fn new() -> Self {
+            let name = format!("test-receive-{}", Uuid::new_v4());
+            let storage =
+                SQLiteStorage::new(&name, std::env::temp_dir().to_str().unwrap()).unwrap();
+            storage.init_client().unwrap();
+            FailingStorage {
+                storage,
+                name,
+                fail_store_session: AtomicBool::new(false),
+            }
+        }
+    }
+
+    impl Drop for FailingStorage {
+        fn drop(&mut self) {
+            let _ =
+                std::fs::remove_file(std::env::temp_dir().join(format!("db_{}.sqlite", self.name)));
+        }
+    }
+
+    impl ClientStorage for FailingStorage {
+        fn init_client(&self) -> Result<(), StorageInterfaceError> {
+            self.storage.init_client()
+        }
+
+        fn contains_client(&self) -> Result<Option<i32>, StorageInterfaceError> {
+            self.storage.contains_client()
+        }
+
+        fn create_client(
+            &self,
+            client_id: &Uuid,
+            private_key_bundle: &PrivateBundle,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage.create_client(client_id, private_key_bundle)
+        }
+
+        fn get_client_uuid(&self) -> Result<Uuid, StorageInterfaceError> {
+            self.storage.get_client_uuid()
+        }
+
+        fn get_private_key_bundle(&self) -> Result<PrivateBundle, StorageInterfaceError> {
+            self.storage.get_private_key_bundle()
+        }
+
+        fn update_curve_signed_prekey(
+            &self,
+            new_signed_prekey: &IdentifiedEllipticCurveKeyPair,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage.update_curve_signed_prekey(new_signed_prekey)
+        }
+
+        fn update_last_resort_pqkem_prekey(
+            &self,
+            new_last_resort_prekey: &IdentifiedPQKEMKeyPair,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage
+                .update_last_resort_pqkem_prekey(new_last_resort_prekey)
+        }
+
+        fn add_curve_one_time_prekeys(
+            &self,
+            new_one_time_prekeys: &Vec<IdentifiedEllipticCurveKeyPair>,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage
+                .add_curve_one_time_prekeys(new_one_time_prekeys)
+        }
+
+        fn add_signed_pqkem_prekeys(
+            &self,
+            new_signed_pqkem_prekeys: &Vec<IdentifiedPQKEMKeyPair>,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage
+                .add_signed_pqkem_prekeys(new_signed_pqkem_prekeys)
+        }
+
+        fn remove_curve_one_time_prekey(
+            &self,
+            prekey_id: &Uuid,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage.remove_curve_one_time_prekey(prekey_id)
+        }
+
+        fn remove_pqkem_one_time_prekey(
+            &self,
+            prekey_id: &Uuid,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage.remove_pqkem_one_time_prekey(prekey_id)
+        }
+
+        fn remove_one_time_prekeys(
+            &self,
+            curve_prekey_id: Option<&Uuid>,
+            pqkem_prekey_id: Option<&Uuid>,
+        ) -> Result<(), StorageInterfaceError> {
+            self.storage
+                .remove_one_time_prekeys(curve_prekey_id, pqkem_prekey_id)
+        }
+
+        fn load_session(
+            &self,
+            peer_id: &Uuid,
+        ) -> Result<Option<RatchetSession>, StorageInterfaceError> {
+            self.storage.load_session(peer_id)
+        }
+
+        fn store_session(
+            &self,
+            peer_id: &Uuid,
+            session: &RatchetSession,
+        ) -> Result<(), StorageInterfaceError> {
+            if self.fail_store_session.load(Ordering::SeqCst) {
+                return Err(StorageInterfaceError::CustomError(
+                    "session storage unavailable".to_string(),
+                ));
+            }
+            self.storage.store_session(peer_id, session)
+        }
+
+        fn delete_session(&self, peer_id: &Uuid) -> Result<(), StorageInterfaceError> {
+            self.storage.delete_session(peer_id)
+        }
+    }
+
+    #[test]
+    fn first_message_is_processed_again_when_the_session_cannot_be_stored() {
+        let mut rng = rand::thread_rng();
+        let parameters = PqxdhParameters::new(
+            EllipticCurveType::CURVE25519,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap();
+
+        // Bob is the receiving client, with one prekey of each kind
+        let bob = PrivateBundle::new(&parameters, 1, 1, &mut rng);
+        let storage = FailingStorage::new();
+        let bob_uuid = Uuid::new_v4();
+        storage.create_client(&bob_uuid, &bob).unwrap();
+        let (inbox_sender, inbox) = mpsc::channel();
+        let client = ClientData {
+            client_uuid: bob_uuid,
+            client_storage_mutex: Mutex::new(storage),
+            socket_mutex: Mutex::new(zmq::Context::new().socket(zmq::DEALER).unwrap()),
+            parameters: parameters.clone(),
+            config: ClientConfig::default(),
+            inbox_sender,
+            session_token: Mutex::new(Vec::new()),
+        };
+
+        // Alice initiates the key agreement with Bob's bundle
+        let alice = PrivateBundle::new(&parameters, 0, 0, &mut rng);
+        let mut registration_bundle =
+            RegistrationBundle::from_private_bundle(&bob, parameters.curve_type(), &mut rng)
+                .unwrap();
+        let bundle = PrekeyBundle::from_registration_bundle(&mut registration_bundle);
+        let initiator_output =
+            initiate(&bundle, &alice, b"hello bob", &parameters, &mut rng).unwrap();
+        let alice_uuid = Uuid::new_v4();
+        let envelope = ServerEnvelope::from_protobuf(&PbServerEnvelope {
+            uuid: Uuid::new_v4().as_bytes().to_vec(),
+            sender_uuid: alice_uuid.to_string(),
+            envelope_type: ServerEnvelopeType::FirstMessage.to_protobuf().into(),
+            payload: create_first_message(&initiator_output.first_message),
+            timestamp: 0,
+        })
+        .unwrap();
+
+        // The session cannot be stored: the envelope is not acknowledged and the prekeys are kept
+        client
+            .client_storage_mutex
+            .lock()
+            .unwrap()
+            .fail_store_session
+            .store(true, Ordering::SeqCst);
+        assert!(data_receive_envelope(&envelope, &client, &mut rng).is_err());
+        {
+            let storage = client.client_storage_mutex.lock().unwrap();
+            assert!(storage.load_session(&alice_uuid).unwrap().is_none());
+            let private_bundle = storage.get_private_key_bundle().unwrap();
+            assert_eq!(private_bundle.one_time_curve_prekeys.len(), 1);
+            assert_eq!(private_bundle.one_time_pqkem_prekeys.len(), 1);
+        }
+        assert!(inbox.try_recv().is_err());
+
+        // The envelope is delivered again once the storage is back
+        client
+            .client_storage_mutex
+            .lock()
+            .unwrap()
+            .fail_store_session
+            .store(false, Ordering::SeqCst);
+        let ack = data_receive_envelope(&envelope, &client, &mut rng).unwrap();
+        assert!(matches!(ack.message_type, ClientMessageType::AckEnvelope));
+        assert_eq!(ack.ack_envelope.unwrap().envelope_id, envelope.id);
+        {
+            let storage = client.client_storage_mutex.lock().unwrap();
+            let session = storage.load_session(&alice_uuid).unwrap().unwrap();
+            assert_eq!(session.associated_data, initiator_output.associated_data);
+            let private_bundle = storage.get_private_key_bundle().unwrap();
+            assert!(private_bundle.one_time_curve_prekeys.is_empty());
+            assert!(private_bundle.one_time_pqkem_prekeys.is_empty());
+        }
+        let message = inbox.try_recv().unwrap();
+        assert_eq!(message.sender_uuid, alice_uuid);
+        assert_eq!(message.plaintext, b"hello bob");
+    }
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use e2ee_rust_common::storage::client::traits::ClientStorage;
+use uuid::Uuid;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::Client;
+
+// Message decrypted from the mailbox, waiting to be read by the application
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct ReceivedMessage {
+    #[zeroize(skip)]
+    pub sender_uuid: Uuid,
+    pub plaintext: Vec<u8>,
+}
+
+impl<S> Client<S>
+where
+    S: ClientStorage + Send + Sync + 'static,
+{
+    // Returns the messages received since the last call, oldest first, without waiting
+    pub fn received_messages(&self) -> Vec<ReceivedMessage> {
+        self.inbox.lock().unwrap().try_iter().collect()
+    }
+
+    // Waits for the next received message, for at most the given duration
+    pub fn wait_for_message(&self, timeout: Duration) -> Option<ReceivedMessage> {
+        self.inbox.lock().unwrap().recv_timeout(timeout).ok()
+    }
+}
@@ -1,17 +1,26 @@
+mod builder;
 mod commands;
+mod data;
+mod inbox;
+mod messaging;
+mod safety_number;
+
+pub use builder::ClientBuilder;
+pub use inbox::ReceivedMessage;
 
 use std::{
-    sync::{Arc, Mutex},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
     thread::{self, JoinHandle},
     time::Duration,
 };
 
+use builder::ClientConfig;
 use commands::handler::handle_server_command;
+use data::handler::handle_server_data;
 use e2ee_rust_common::{
-    crypto::{
-        curve::{curve25519::Curve25519, traits::EllipticCurveAlgorithm},
-        pqkem::{crystalskyber512::CrystalsKyber512, traits::PQKEMAlgorithm},
-    },
     errors::{
         general::{GeneralError, ToGeneralError},
         zmq::ZMQError,
@@ -21,81 +30,75 @@ use e2ee_rust_common::{
             client_hello::ClientHello,
             client_message::{ClientMessage, ClientMessageType},
         },
-        server::server_message::ServerMessageType,
+        server::server_message::{ServerError, ServerMessage, ServerMessageType},
     },
-    pqxdh::private_bundle::PrivateBundle,
+    pqxdh::{parameters::PqxdhParameters, private_bundle::PrivateBundle},
     protobuf::utils::{create_client_message, decode_server_message},
     storage::client::traits::ClientStorage,
 };
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use uuid::Uuid;
 use zmq::Socket;
 
-pub struct ClientData<
-    T: EllipticCurveAlgorithm + Send,
-    U: PQKEMAlgorithm + Send,
-    S: ClientStorage + Send,
-> {
+pub struct ClientData<S: ClientStorage + Send> {
     client_uuid: Uuid,
     client_storage_mutex: Mutex<S>,
     socket_mutex: Mutex<Socket>,
-    curve_algorithm: T,
-    pqkem_algorithm: U,
+    parameters: PqxdhParameters,
+    config: ClientConfig,
+    inbox_sender: Sender<ReceivedMessage>,
+    // Token of the session opened by the last authentication challenge, sent with every request
+    session_token: Mutex<Vec<u8>>,
 }
 
-pub struct Client<
-    T: EllipticCurveAlgorithm + Send,
-    U: PQKEMAlgorithm + Send,
-    S: ClientStorage + Send,
-> {
-    client_data: Arc<ClientData<T, U, S>>,
+pub struct Client<S: ClientStorage + Send> {
+    client_data: Arc<ClientData<S>>,
     heartbeat_thread: Option<JoinHandle<()>>,
+    inbox: Mutex<Receiver<ReceivedMessage>>,
 }
 
-// TODO: parameterize
-const CURVE_TYPE: Curve25519 = Curve25519 {};
-const PQKEM_TYPE: CrystalsKyber512 = CrystalsKyber512 {};
-
-const ONE_TIME_CURVE_PREKEYS: usize = 10;
-const ONE_TIME_PQKEM_PREKEYS: usize = 10;
-
-// TODO: parameterize
-const ENDPOINT: &str = "tcp://localhost:5555";
+const CURVE_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
 
-impl<T, U, S> Client<T, U, S>
+impl<S> Client<S>
 where
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
     S: ClientStorage + Send + Sync + 'static,
 {
+    pub fn builder(client_storage: S, parameters: PqxdhParameters) -> ClientBuilder<S> {
+        ClientBuilder::new(client_storage, parameters)
+    }
+
     // Starts the client in a separate thread and returns the client handle when the client is ready (connected to the server and registered and heartbeat is running)
-    pub fn new(
+    // When a z85 encoded server public key is pinned, the connection is encrypted with CurveZMQ and only accepted by the server owning that key
+    fn start(
         client_storage: S,
-        curve_algorithm: T,
-        pqkem_algorithm: U,
+        parameters: PqxdhParameters,
+        config: ClientConfig,
     ) -> Result<Self, GeneralError> {
-        env_logger::init();
-
         debug!("Starting client");
 
         // Get the UUID from the storage initialization
-        let client_uuid = initialize_client_storage(&client_storage)?;
+        let client_uuid = initialize_client_storage(&client_storage, &parameters, &config)?;
         debug!("Client UUID: {}", client_uuid);
 
         // Connect to the server
-        let socket = connect_to_server(client_uuid)?;
+        let socket = connect_to_server(client_uuid, &config)?;
         debug!("Connected to server");
 
         let socket_mutex = Mutex::new(socket);
         let client_storage_mutex = Mutex::new(client_storage);
 
+        // Decrypted messages are handed from the heartbeat thread to the application
+        let (inbox_sender, inbox) = mpsc::channel();
+
         // Create the client
         let client = ClientData {
             client_uuid,
             client_storage_mutex,
             socket_mutex,
-            curve_algorithm,
-            pqkem_algorithm,
+            parameters,
+            config,
+            inbox_sender,
+            session_token: Mutex::new(Vec::new()),
         };
 
         // Start the heartbeat thread
@@ -109,19 +112,24 @@ where
                 break;
             };
 
-            // Sleep for 1 second between heartbeats
-            thread::sleep(Duration::from_secs(1));
+            // Sleep between heartbeats
+            thread::sleep(client_arc_clone.config.heartbeat_interval);
         });
 
         // Return the client
         Ok(Client {
             client_data: Arc::clone(&client_arc),
             heartbeat_thread: Some(thread_handle),
+            inbox: Mutex::new(inbox),
         })
     }
 }
 
-fn initialize_client_storage<S: ClientStorage>(client_storage: &S) -> Result<Uuid, GeneralError> {
+fn initialize_client_storage<S: ClientStorage>(
+    client_storage: &S,
+    parameters: &PqxdhParameters,
+    config: &ClientConfig,
+) -> Result<Uuid, GeneralError> {
     let client_uuid: Uuid;
 
     // Check if the client is already registered
@@ -138,10 +146,9 @@ fn initialize_client_storage<S: ClientStorage>(client_storage: &S) -> Result<Uui
 
         // Generate the private bundle
         let private_key_bundle = PrivateBundle::new(
-            &CURVE_TYPE,
-            &PQKEM_TYPE,
-            ONE_TIME_CURVE_PREKEYS,
-            ONE_TIME_PQKEM_PREKEYS,
+            parameters,
+            config.one_time_curve_prekeys,
+            config.one_time_pqkem_prekeys,
             &mut rng,
         );
         debug!("Generated private bundle");
@@ -158,7 +165,7 @@ fn initialize_client_storage<S: ClientStorage>(client_storage: &S) -> Result<Uui
     Ok(client_uuid)
 }
 
-fn connect_to_server(client_uuid: Uuid) -> Result<Socket, GeneralError> {
+fn connect_to_server(client_uuid: Uuid, config: &ClientConfig) -> Result<Socket, GeneralError> {
     // Start a request socket
     info!("Starting client with identity {}...", client_uuid);
     let ctx = zmq::Context::new();
@@ -167,21 +174,106 @@ fn connect_to_server(client_uuid: Uuid) -> Result<Socket, GeneralError> {
         .set_identity(client_uuid.as_bytes())
         .map_err(|_| GeneralError::ZMQ(ZMQError::SetIdentityError))?;
 
+    // Without a pinned server key, the connection is not encrypted, which must have been explicitly allowed
+    let server_public_key = match &config.server_public_key {
+        Some(server_public_key) => server_public_key,
+        None if !config.allow_insecure_transport => {
+            error!("No server key pinned and the insecure transport is not allowed");
+            return Err(GeneralError::ZMQ(ZMQError::InsecureTransportNotAllowed));
+        }
+        None => {
+            warn!("No server key pinned, the connection will not be encrypted");
+            info!("Connecting to server...");
+            socket
+                .connect(&config.endpoint)
+                .map_err(|_| GeneralError::ZMQ(ZMQError::ConnectError))?;
+            return Ok(socket);
+        }
+    };
+
+    // Make sure that libzmq can encrypt the connection
+    if zmq::has("curve") != Some(true) {
+        error!("libzmq was built without CURVE support");
+        return Err(GeneralError::ZMQ(ZMQError::CurveNotSupported));
+    }
+
+    // Decode the pinned server key
+    let server_public_key: [u8; 32] = zmq::z85_decode(server_public_key)
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or(GeneralError::ZMQ(ZMQError::InvalidCurveKey))?;
+
+    // Encrypt the connection to the server key, with a fresh transport key pair for the client
+    // The client itself is authenticated later on with its identity key
+    let client_key_pair =
+        zmq::CurveKeyPair::new().map_err(|_| GeneralError::ZMQ(ZMQError::CurveSetupError))?;
+    socket
+        .set_curve_serverkey(&server_public_key)
+        .and_then(|_| socket.set_curve_publickey(&client_key_pair.public_key))
+        .and_then(|_| socket.set_curve_secretkey(&client_key_pair.secret_key))
+        .map_err(|_| GeneralError::ZMQ(ZMQError::CurveSetupError))?;
+
+    // Watch the connection events to know how the handshake ends
+    let monitor_endpoint = format!("inproc://monitor-{}", client_uuid);
+    socket
+        .monitor(
+            &monitor_endpoint,
+            (zmq::SocketEvent::HANDSHAKE_SUCCEEDED as i32)
+                | (zmq::SocketEvent::HANDSHAKE_FAILED_NO_DETAIL as i32)
+                | (zmq::SocketEvent::HANDSHAKE_FAILED_PROTOCOL as i32)
+                | (zmq::SocketEvent::HANDSHAKE_FAILED_AUTH as i32)
+                | (zmq::SocketEvent::DISCONNECTED as i32),
+        )
+        .map_err(|_| GeneralError::ZMQ(ZMQError::MonitorError))?;
+    let monitor_socket = ctx.socket(zmq::PAIR).unwrap();
+    monitor_socket
+        .connect(&monitor_endpoint)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::MonitorError))?;
+
     // Connect to the server
     info!("Connecting to server...");
     socket
-        .connect(ENDPOINT)
+        .connect(&config.endpoint)
         .map_err(|_| GeneralError::ZMQ(ZMQError::ConnectError))?;
 
+    wait_for_curve_handshake(&monitor_socket)?;
+    info!("Encrypted connection to the server established");
+
     Ok(socket)
 }
 
-fn server_heartbeat<
-    T: EllipticCurveAlgorithm + Send + Sync + 'static,
-    U: PQKEMAlgorithm + Send + Sync + 'static,
-    S: ClientStorage + Send + Sync + 'static,
->(
-    client: &Arc<ClientData<T, U, S>>,
+// Waits for the end of the CurveZMQ handshake with the server
+// A server that does not own the pinned key cannot read our hello and drops the connection, so a disconnection means that the handshake failed
+fn wait_for_curve_handshake(monitor_socket: &Socket) -> Result<(), GeneralError> {
+    // Wait for the first handshake event
+    let ready = monitor_socket
+        .poll(zmq::POLLIN, CURVE_HANDSHAKE_TIMEOUT.as_millis() as i64)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::MonitorError))?;
+    if ready == 0 {
+        error!("Timed out during the CurveZMQ handshake");
+        return Err(GeneralError::ZMQ(ZMQError::CurveHandshakeTimeout));
+    }
+
+    // Get the event ID, the address part is not needed
+    let event_msg = monitor_socket
+        .recv_msg(0)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::MonitorError))?;
+    let event = u16::from_ne_bytes([event_msg[0], event_msg[1]]);
+
+    match zmq::SocketEvent::from_raw(event) {
+        zmq::SocketEvent::HANDSHAKE_SUCCEEDED => Ok(()),
+        zmq_event => {
+            error!(
+                "CurveZMQ handshake failed ({:?}), is the pinned server key correct?",
+                zmq_event
+            );
+            Err(GeneralError::ZMQ(ZMQError::CurveHandshakeFailed))
+        }
+    }
+}
+
+fn server_heartbeat<S: ClientStorage + Send + Sync + 'static>(
+    client: &Arc<ClientData<S>>,
 ) -> Result<(), GeneralError> {
     // Get the socket
     let socket = client.socket_mutex.lock().unwrap();
@@ -190,13 +282,7 @@ fn server_heartbeat<
     let mut message: ClientMessage =
         ClientMessage::new(ClientMessageType::ClientHello, client.client_uuid);
     message.client_hello = Some(ClientHello {});
-    socket
-        .send("", zmq::SNDMORE)
-        .map_err(|_| GeneralError::ZMQ(ZMQError::SendError))?;
-    debug!("Sent envelope delimiter");
-    socket
-        .send(create_client_message(&message), 0)
-        .map_err(|_| GeneralError::ZMQ(ZMQError::SendError))?;
+    send_to_server(&socket, client, &mut message)?;
     debug!("Sent client hello message");
 
     // Answer commands until we get an OK or an error
@@ -234,18 +320,90 @@ fn server_heartbeat<
                     );
                     return Err(GeneralError::ClientError);
                 }
-                socket
-                    .send("", zmq::SNDMORE)
-                    .map_err(|_| GeneralError::ZMQ(ZMQError::SendError))?;
-                debug!("Sent envelope delimiter");
-                socket
-                    .send(create_client_message(&client_response.unwrap()), 0)
-                    .map_err(|_| GeneralError::ZMQ(ZMQError::SendError))?;
+                send_to_server(&socket, client, &mut client_response.unwrap())?;
+                debug!("Sent client response");
+            }
+            ServerMessageType::Data => {
+                let client_response = handle_server_data(&server_message.data.unwrap(), client);
+                if let Err(GeneralError::StorageError(e)) = &client_response {
+                    // The data is not acknowledged, so the server sends it again on the next heartbeat
+                    warn!(
+                        "Storage error handling server data, retrying later: {:?}",
+                        e
+                    );
+                    break;
+                }
+                if client_response.is_err() {
+                    error!(
+                        "Error handling server data: {:?}",
+                        client_response.err().unwrap()
+                    );
+                    return Err(GeneralError::ClientError);
+                }
+                send_to_server(&socket, client, &mut client_response.unwrap())?;
                 debug!("Sent client response");
             }
-            ServerMessageType::Data => todo!(),
         }
     }
 
     Ok(())
 }
+
+// Sends a request to the server and returns its answer
+// The server only accepts requests from authenticated clients, so the request is sent again after a heartbeat when the authentication expired
+fn server_request<S: ClientStorage + Send + Sync + 'static>(
+    client: &Arc<ClientData<S>>,
+    message: &mut ClientMessage,
+) -> Result<ServerMessage, GeneralError> {
+    let server_message = exchange_with_server(client, message)?;
+    if !matches!(server_message.error, Some(ServerError::NotAuthenticated)) {
+        return Ok(server_message);
+    }
+
+    debug!("Not authenticated, sending a heartbeat before retrying");
+    server_heartbeat(client)?;
+    exchange_with_server(client, message)
+}
+
+fn exchange_with_server<S: ClientStorage + Send + Sync + 'static>(
+    client: &Arc<ClientData<S>>,
+    message: &mut ClientMessage,
+) -> Result<ServerMessage, GeneralError> {
+    // Get the socket, the server answers each request with a single message
+    let socket = client.socket_mutex.lock().unwrap();
+
+    send_to_server(&socket, client, message)?;
+    debug!("Sent client request");
+
+    // Wait for envelope delimiter
+    socket
+        .recv_bytes(0)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::RecvError))?;
+
+    // Wait for server message
+    let server_response = socket
+        .recv_bytes(0)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::RecvError))?;
+    let server_message = decode_server_message(&server_response).map_err(GeneralError::Protobuf)?;
+    debug!("Received server message: {:?}", server_message);
+
+    Ok(server_message)
+}
+
+// Sends a message to the server with the token of the current session, which may have changed since the message was created
+fn send_to_server<S: ClientStorage + Send>(
+    socket: &Socket,
+    client: &ClientData<S>,
+    message: &mut ClientMessage,
+) -> Result<(), GeneralError> {
+    message.session_token = client.session_token.lock().unwrap().clone();
+    socket
+        .send("", zmq::SNDMORE)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::SendError))?;
+    debug!("Sent envelope delimiter");
+    socket
+        .send(create_client_message(message), 0)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::SendError))?;
+
+    Ok(())
+}
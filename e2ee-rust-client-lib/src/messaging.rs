@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use e2ee_rust_common::{
+    errors::general::{GeneralError, ToGeneralError},
+    messages::{
+        client::{
+            client_message::{ClientMessage, ClientMessageType},
+            post_envelope::PostEnvelope,
+            request_peer_bundle::RequestPeerBundle,
+            send_first_message::SendFirstMessage,
+        },
+        server::server_message::{ServerDataType, ServerMessage, ServerMessageType},
+    },
+    pqxdh::{initiator::initiate, prekey_bundle::PrekeyBundle},
+    protobuf::utils::create_ratchet_message,
+    ratchet::session::RatchetSession,
+    storage::client::traits::ClientStorage,
+};
+use log::{debug, error, info};
+use rand::{CryptoRng, RngCore};
+use uuid::Uuid;
+
+use crate::{server_request, Client, ClientData};
+
+impl<S> Client<S>
+where
+    S: ClientStorage + Send + Sync + 'static,
+{
+    // Sends a message to a peer through the server mailbox
+    // Without a session with the peer, a new one is first established with a PQXDH key agreement
+    pub fn send_message(&self, peer_uuid: &Uuid, plaintext: &[u8]) -> Result<(), GeneralError> {
+        let client = &self.client_data;
+        let mut rng = rand::thread_rng();
+
+        // Load the session established with the peer, or start one
+        let client_storage = client.client_storage_mutex.lock().unwrap();
+        let session = client_storage.load_session(peer_uuid).to_general_error()?;
+        drop(client_storage);
+        let mut session = match session {
+            Some(session) => session,
+            None => establish_session(client, peer_uuid, &mut rng)?,
+        };
+
+        // Encrypt with the session
+        let ratchet_message = session
+            .encrypt(
+                plaintext,
+                client.parameters.aead_type(),
+                client.parameters.hash_type(),
+                &mut rng,
+            )
+            .map_err(GeneralError::Ratchet)?;
+
+        // Store the advanced session before sending, so that the message key is never reused
+        client
+            .client_storage_mutex
+            .lock()
+            .unwrap()
+            .store_session(peer_uuid, &session)
+            .to_general_error()?;
+
+        let mut msg = ClientMessage::new(ClientMessageType::PostEnvelope, client.client_uuid);
+        msg.post_envelope = Some(PostEnvelope {
+            recipient_uuid: *peer_uuid,
+            payload: create_ratchet_message(&ratchet_message),
+        });
+        expect_ok(&server_request(client, &mut msg)?)?;
+        debug!("Sent message to {}", peer_uuid);
+
+        Ok(())
+    }
+}
+
+// Runs the initiator side of the key agreement with a peer, and sends it the first message
+// The initial ciphertext is left empty: messages are sent through the ratchet, which gives the peer our ratchet key so that it can reply
+fn establish_session<S: ClientStorage + Send + Sync + 'static, R: RngCore + CryptoRng>(
+    client: &Arc<ClientData<S>>,
+    peer_uuid: &Uuid,
+    rng: &mut R,
+) -> Result<RatchetSession, GeneralError> {
+    // Fetch the peer prekey bundle, without holding the storage lock while waiting for the server
+    info!("No session with {}, starting a key agreement", peer_uuid);
+    let prekey_bundle = request_peer_bundle(client, peer_uuid)?;
+
+    // Run the initiator side of the key agreement, the bundle signatures are verified there
+    let client_storage = client.client_storage_mutex.lock().unwrap();
+    let private_bundle = client_storage.get_private_key_bundle().to_general_error()?;
+    let output = initiate(
+        &prekey_bundle,
+        &private_bundle,
+        &[],
+        &client.parameters,
+        rng,
+    )
+    .map_err(GeneralError::PQXDH)?;
+
+    // Start the ratchet session from the shared key, the peer signed prekey is its first ratchet key
+    let session = RatchetSession::new_initiator(
+        &output.shared_key,
+        &output.associated_data,
+        &prekey_bundle.identity_key,
+        &prekey_bundle
+            .signed_curve_prekey
+            .identified_public_key
+            .public_key,
+        client.parameters.curve_type(),
+        client.parameters.hash_type(),
+        rng,
+    )
+    .map_err(GeneralError::Ratchet)?;
+    client_storage
+        .store_session(peer_uuid, &session)
+        .to_general_error()?;
+    drop(client_storage);
+
+    // Send the first message, which the mailbox delivers before the messages that follow
+    let mut msg = ClientMessage::new(ClientMessageType::SendFirstMessage, client.client_uuid);
+    msg.send_first_message = Some(SendFirstMessage {
+        peer_uuid: *peer_uuid,
+        first_message: output.first_message,
+    });
+    expect_ok(&server_request(client, &mut msg)?)?;
+    info!("Sent first message to {}", peer_uuid);
+
+    Ok(session)
+}
+
+// Asks the server for one of the peer prekey bundles
+fn request_peer_bundle<S: ClientStorage + Send + Sync + 'static>(
+    client: &Arc<ClientData<S>>,
+    peer_uuid: &Uuid,
+) -> Result<PrekeyBundle, GeneralError> {
+    let mut msg = ClientMessage::new(ClientMessageType::RequestPeerBundle, client.client_uuid);
+    msg.request_peer_bundle = Some(RequestPeerBundle {
+        peer_uuid: *peer_uuid,
+    });
+    let server_message = server_request(client, &mut msg)?;
+
+    match server_message.data {
+        Some(data) if matches!(data.data_type, ServerDataType::PeerBundle) => {
+            let prekey_bundle = data.peer_bundle.ok_or(GeneralError::ServerError)?.bundle;
+            prekey_bundle.print();
+            Ok(prekey_bundle)
+        }
+        _ => {
+            error!(
+                "Cannot get the prekey bundle of {}: {:?}",
+                peer_uuid, server_message.error
+            );
+            Err(GeneralError::ServerError)
+        }
+    }
+}
+
+fn expect_ok(server_message: &ServerMessage) -> Result<(), GeneralError> {
+    match server_message.message_type {
+        ServerMessageType::Ok => Ok(()),
+        _ => {
+            error!("Server error: {:?}", server_message.error);
+            Err(GeneralError::ServerError)
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use e2ee_rust_common::{
+    errors::{
+        fingerprint::FingerprintError,
+        general::{GeneralError, ToGeneralError},
+    },
+    fingerprint::safety_number::SafetyNumber,
+    storage::client::traits::ClientStorage,
+};
+use uuid::Uuid;
+
+use crate::Client;
+
+impl<S> Client<S>
+where
+    S: ClientStorage + Send + Sync + 'static,
+{
+    // Derives the safety number of the conversation with a peer, which needs an established session
+    pub fn safety_number(&self, peer_uuid: &Uuid) -> Result<SafetyNumber, GeneralError> {
+        let client_storage = self.client_data.client_storage_mutex.lock().unwrap();
+
+        let private_bundle = client_storage.get_private_key_bundle().to_general_error()?;
+
+        // The peer identity key is stored with the session by both the initiator and the responder
+        let session = client_storage
+            .load_session(peer_uuid)
+            .to_general_error()?
+            .ok_or(GeneralError::Fingerprint(FingerprintError::UnknownPeer(
+                *peer_uuid,
+            )))?;
+
+        Ok(SafetyNumber::new(
+            &self.client_data.client_uuid,
+            &private_bundle.identity_key.public_key,
+            peer_uuid,
+            &session.peer_identity_key,
+        ))
+    }
+}
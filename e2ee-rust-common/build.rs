@@ -7,16 +7,24 @@ fn main() {
             "src/protobuf/crypto/curve/keys.proto",
             "src/protobuf/crypto/pqkem/types.proto",
             "src/protobuf/crypto/pqkem/keys.proto",
+            "src/protobuf/crypto/aead/types.proto",
             "src/protobuf/pqxdh/pb_signed_curve_prekey.proto",
             "src/protobuf/pqxdh/pb_signed_pqkem_prekey.proto",
             "src/protobuf/pqxdh/pb_one_time_curve_prekey_set.proto",
             "src/protobuf/pqxdh/pb_signed_one_time_pqkem_prekey_set.proto",
             "src/protobuf/pqxdh/pb_registration_bundle.proto",
+            "src/protobuf/pqxdh/pb_first_message.proto",
+            "src/protobuf/ratchet/pb_ratchet_message.proto",
             "src/protobuf/client/pb_client_message.proto",
             "src/protobuf/client/pb_client_hello.proto",
             "src/protobuf/client/pb_new_keys.proto",
             "src/protobuf/client/pb_request_peer_bundle.proto",
+            "src/protobuf/client/pb_send_first_message.proto",
+            "src/protobuf/client/pb_post_envelope.proto",
+            "src/protobuf/client/pb_ack_envelope.proto",
+            "src/protobuf/client/pb_authentication_response.proto",
             "src/protobuf/server/pb_server_message.proto",
+            "src/protobuf/fingerprint/pb_fingerprint.proto",
         ],
         &["src/protobuf/"],
     )
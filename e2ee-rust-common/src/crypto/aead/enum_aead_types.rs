@@ -1,3 +1,127 @@
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{errors::aead::AEADError, protobuf::crypto::aead::PbaeadType};
+
+use super::{
+    aes256gcm::AES256GCM, chacha20poly1305::CHACHA20POLY1305, traits::AEADScheme,
+    xchacha20poly1305::XCHACHA20POLY1305,
+};
+
+#[derive(Zeroize, ZeroizeOnDrop, PartialEq, Debug, Clone)]
 pub enum AEADType {
     AES256GCM,
+    CHACHA20POLY1305,
+    XCHACHA20POLY1305,
+}
+
+impl AEADType {
+    pub fn id(&self) -> u8 {
+        match self {
+            AEADType::AES256GCM => 0,
+            AEADType::CHACHA20POLY1305 => 1,
+            AEADType::XCHACHA20POLY1305 => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<AEADType> {
+        match id {
+            0 => Some(AEADType::AES256GCM),
+            1 => Some(AEADType::CHACHA20POLY1305),
+            2 => Some(AEADType::XCHACHA20POLY1305),
+            _ => None,
+        }
+    }
+
+    pub fn nonce_length(&self) -> usize {
+        match self {
+            AEADType::AES256GCM => 12,
+            AEADType::CHACHA20POLY1305 => 12,
+            AEADType::XCHACHA20POLY1305 => 24,
+        }
+    }
+
+    // Length of the authentication tag appended to the ciphertexts
+    pub fn tag_length(&self) -> usize {
+        match self {
+            AEADType::AES256GCM => 16,
+            AEADType::CHACHA20POLY1305 => 16,
+            AEADType::XCHACHA20POLY1305 => 16,
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            AEADType::AES256GCM => "AES-256-GCM",
+            AEADType::CHACHA20POLY1305 => "CHACHA20-POLY1305",
+            AEADType::XCHACHA20POLY1305 => "XCHACHA20-POLY1305",
+        }
+    }
+
+    pub fn to_protobuf(&self) -> PbaeadType {
+        match self {
+            AEADType::AES256GCM => PbaeadType::Aes256gcm,
+            AEADType::CHACHA20POLY1305 => PbaeadType::Chacha20poly1305,
+            AEADType::XCHACHA20POLY1305 => PbaeadType::Xchacha20poly1305,
+        }
+    }
+}
+
+// Converts a nonce to the fixed size expected by the AEAD scheme
+fn nonce_from_slice<const N: usize>(nonce: &[u8]) -> Result<[u8; N], AEADError> {
+    nonce.try_into().map_err(|_| AEADError::InvalidNonceSize)
+}
+
+// Resolves the AEAD implementation at runtime, so that the AEAD can be chosen from a value instead of a type parameter
+// The nonce size depends on the scheme, so nonces are passed as byte vectors
+impl AEADScheme<[u8; 32], Vec<u8>> for AEADType {
+    fn encrypt<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        key: &[u8; 32],
+        plaintext: &[u8],
+        associated_data: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), AEADError> {
+        let (ciphertext, nonce) = match self {
+            AEADType::AES256GCM => AES256GCM {}
+                .encrypt(key, plaintext, associated_data, rng)
+                .map(|(c, n)| (c, n.to_vec()))?,
+            AEADType::CHACHA20POLY1305 => CHACHA20POLY1305 {}
+                .encrypt(key, plaintext, associated_data, rng)
+                .map(|(c, n)| (c, n.to_vec()))?,
+            AEADType::XCHACHA20POLY1305 => XCHACHA20POLY1305 {}
+                .encrypt(key, plaintext, associated_data, rng)
+                .map(|(c, n)| (c, n.to_vec()))?,
+        };
+        Ok((ciphertext, nonce))
+    }
+
+    fn decrypt(
+        &self,
+        key: &[u8; 32],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+        nonce: &Vec<u8>,
+    ) -> Result<Vec<u8>, AEADError> {
+        match self {
+            AEADType::AES256GCM => {
+                AES256GCM {}.decrypt(key, ciphertext, associated_data, &nonce_from_slice(nonce)?)
+            }
+            AEADType::CHACHA20POLY1305 => CHACHA20POLY1305 {}.decrypt(
+                key,
+                ciphertext,
+                associated_data,
+                &nonce_from_slice(nonce)?,
+            ),
+            AEADType::XCHACHA20POLY1305 => XCHACHA20POLY1305 {}.decrypt(
+                key,
+                ciphertext,
+                associated_data,
+                &nonce_from_slice(nonce)?,
+            ),
+        }
+    }
+
+    fn get_type(&self) -> AEADType {
+        self.clone()
+    }
 }
@@ -1,3 +1,5 @@
 pub mod aes256gcm;
+pub mod chacha20poly1305;
 pub mod enum_aead_types;
 pub mod traits;
+pub mod xchacha20poly1305;
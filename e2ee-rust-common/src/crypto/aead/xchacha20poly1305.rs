@@ -0,0 +1,53 @@
+use chacha20poly1305::{aead::AeadMutInPlace, AeadCore, Key, KeyInit, XChaCha20Poly1305, XNonce};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::errors::aead::AEADError;
+
+use super::{enum_aead_types::AEADType, traits::AEADScheme};
+
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct XCHACHA20POLY1305 {}
+
+impl AEADScheme<[u8; 32], [u8; 24]> for XCHACHA20POLY1305 {
+    fn encrypt<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        key: &[u8; 32],
+        plaintext: &[u8],
+        associated_data: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, [u8; 24]), AEADError> {
+        let aead_key = Key::from_slice(key);
+
+        let mut cipher = XChaCha20Poly1305::new(aead_key);
+        let nonce = XChaCha20Poly1305::generate_nonce(rng);
+        let nonce_bytes: [u8; 24] = nonce.into();
+
+        let mut buffer = plaintext.to_vec();
+        cipher
+            .encrypt_in_place(&nonce, associated_data, &mut buffer)
+            .map_err(|_| AEADError::NotEnoughMemory)?;
+
+        Ok((buffer, nonce_bytes))
+    }
+
+    fn decrypt(
+        &self,
+        key: &[u8; 32],
+        ciphertext: &[u8],
+        associated_data: &[u8],
+        nonce: &[u8; 24],
+    ) -> Result<Vec<u8>, AEADError> {
+        let mut buffer = ciphertext.to_vec();
+        let aead_key = Key::from_slice(key);
+        let mut cipher = XChaCha20Poly1305::new(aead_key);
+
+        match cipher.decrypt_in_place(XNonce::from_slice(nonce), associated_data, &mut buffer) {
+            Ok(()) => Ok(buffer),
+            Err(_) => Err(AEADError::AuthenticationFailed),
+        }
+    }
+
+    fn get_type(&self) -> super::enum_aead_types::AEADType {
+        AEADType::XCHACHA20POLY1305
+    }
+}
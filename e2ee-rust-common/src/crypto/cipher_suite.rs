@@ -0,0 +1,35 @@
+use crate::hash::enum_hash_types::HashType;
+
+use super::{
+    aead::enum_aead_types::AEADType, curve::enum_elliptic_curve_type::EllipticCurveType,
+    pqkem::enum_pqkem_type::PQKEMType,
+};
+
+// Set of algorithms used by a PQXDH session (see https://signal.org/docs/specifications/pqxdh/#pqxdh-parameters)
+// The type enums implement their algorithm traits, so the implementations are resolved at runtime from the suite
+#[derive(PartialEq, Debug, Clone)]
+pub struct CipherSuite {
+    pub curve_type: EllipticCurveType,
+    pub hash_type: HashType,
+    pub pqkem_type: PQKEMType,
+    pub aead_type: AEADType,
+    pub info: String,
+}
+
+impl CipherSuite {
+    pub fn new(
+        curve_type: EllipticCurveType,
+        hash_type: HashType,
+        pqkem_type: PQKEMType,
+        aead_type: AEADType,
+        info: &str,
+    ) -> Self {
+        CipherSuite {
+            curve_type,
+            hash_type,
+            pqkem_type,
+            aead_type,
+            info: info.to_string(),
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use rand::{CryptoRng, RngCore};
 use xeddsa::{Sign, Verify};
 
-use crate::errors::{diffie_hellman::DiffieHellmanError, xeddsa::XedDSAError};
+use crate::{
+    crypto::secret::SecretBytes,
+    errors::{diffie_hellman::DiffieHellmanError, xeddsa::XedDSAError},
+};
 
 use super::{
     enum_elliptic_curve_type::EllipticCurveType,
@@ -12,6 +15,63 @@ use super::{
     traits::EllipticCurveAlgorithm,
 };
 
+// Encodings of the points of small order on curve25519 and its twist, as listed by libsodium
+// X25519 ignores the top bit of the u-coordinate, so it is masked before comparing
+const LOW_ORDER_POINTS: [[u8; 32]; 7] = [
+    // 0 (order 4)
+    [0; 32],
+    // 1 (order 1)
+    [
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+    // 325606250916557431795983626356110631294008115727848805560023387167927233504 (order 8)
+    [
+        0xe0, 0xeb, 0x7a, 0x7c, 0x3b, 0x41, 0xb8, 0xae, 0x16, 0x56, 0xe3, 0xfa, 0xf1, 0x9f, 0xc4,
+        0x6a, 0xda, 0x09, 0x8d, 0xeb, 0x9c, 0x32, 0xb1, 0xfd, 0x86, 0x62, 0x05, 0x16, 0x5f, 0x49,
+        0xb8, 0x00,
+    ],
+    // 39382357235489614581723060781553021112529911719440698176882885853963445705823 (order 8)
+    [
+        0x5f, 0x9c, 0x95, 0xbc, 0xa3, 0x50, 0x8c, 0x24, 0xb1, 0xd0, 0xb1, 0x55, 0x9c, 0x83, 0xef,
+        0x5b, 0x04, 0x44, 0x5c, 0xc4, 0x58, 0x1c, 0x8e, 0x86, 0xd8, 0x22, 0x4e, 0xdd, 0xd0, 0x9f,
+        0x11, 0x57,
+    ],
+    // p - 1 (order 2)
+    [
+        0xec, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0x7f,
+    ],
+    // p, non-canonical encoding of 0
+    [
+        0xed, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0x7f,
+    ],
+    // p + 1, non-canonical encoding of 1
+    [
+        0xee, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0x7f,
+    ],
+];
+
+// Computes X25519, rejecting the all-zero output that any remaining small order point would produce (see https://www.rfc-editor.org/rfc/rfc7748#section-6.1)
+fn contributory_x25519(
+    private_key: [u8; 32],
+    public_key: [u8; 32],
+) -> Result<Vec<u8>, DiffieHellmanError> {
+    let shared_secret = x25519_dalek::StaticSecret::from(private_key)
+        .diffie_hellman(&x25519_dalek::PublicKey::from(public_key));
+    if !shared_secret.was_contributory() {
+        return Err(DiffieHellmanError::NonContributoryOutput);
+    }
+
+    Ok(shared_secret.as_bytes().to_vec())
+}
+
 pub struct Curve25519 {}
 
 impl EllipticCurveAlgorithm for Curve25519 {
@@ -26,7 +86,7 @@ impl EllipticCurveAlgorithm for Curve25519 {
             key_type: EllipticCurveType::CURVE25519,
             private_key: EllipticCurvePrivateKey {
                 key_type: EllipticCurveType::CURVE25519,
-                bytes: private_key.to_bytes().to_vec(),
+                bytes: SecretBytes::new(private_key.to_bytes().to_vec()),
             },
             public_key: EllipticCurvePublicKey {
                 key_type: EllipticCurveType::CURVE25519,
@@ -50,21 +110,48 @@ impl EllipticCurveAlgorithm for Curve25519 {
         EllipticCurveType::CURVE25519
     }
 
+    fn validate_public_key(
+        &self,
+        public_key: &EllipticCurvePublicKey,
+    ) -> Result<(), DiffieHellmanError> {
+        if public_key.key_type != EllipticCurveType::CURVE25519 {
+            return Err(DiffieHellmanError::WrongKeyType);
+        }
+        let mut pub_bytes: [u8; 32] = public_key
+            .bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| DiffieHellmanError::InvalidPublicKeyLength(public_key.bytes.len()))?;
+
+        pub_bytes[31] &= 0x7f;
+        if LOW_ORDER_POINTS.contains(&pub_bytes) {
+            return Err(DiffieHellmanError::LowOrderPublicKey);
+        }
+
+        Ok(())
+    }
+
     fn dh(
         &self,
         private_key: &EllipticCurvePrivateKey,
         public_key: &EllipticCurvePublicKey,
-    ) -> Result<[u8; 32], DiffieHellmanError> {
-        if public_key.key_type != EllipticCurveType::CURVE25519
-            || private_key.key_type != EllipticCurveType::CURVE25519
-        {
+    ) -> Result<Vec<u8>, DiffieHellmanError> {
+        if private_key.key_type != EllipticCurveType::CURVE25519 {
             return Err(DiffieHellmanError::WrongKeyType);
         }
+        self.validate_public_key(public_key)?;
 
-        let priv_bytes: [u8; 32] = private_key.bytes.as_slice().try_into().unwrap();
-        let pub_bytes: [u8; 32] = public_key.bytes.as_slice().try_into().unwrap();
+        let priv_bytes: [u8; 32] =
+            private_key.bytes.expose_secret().try_into().map_err(|_| {
+                DiffieHellmanError::InvalidPrivateKeyLength(private_key.bytes.len())
+            })?;
+        let pub_bytes: [u8; 32] = public_key
+            .bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| DiffieHellmanError::InvalidPublicKeyLength(public_key.bytes.len()))?;
 
-        Ok(x25519_dalek::x25519(priv_bytes, pub_bytes))
+        contributory_x25519(priv_bytes, pub_bytes)
     }
 
     fn xeddsa_sign<R: RngCore + CryptoRng>(
@@ -72,28 +159,39 @@ impl EllipticCurveAlgorithm for Curve25519 {
         private_key: &EllipticCurvePrivateKey,
         message: &[u8],
         rng: &mut R,
-    ) -> Result<[u8; 64], XedDSAError> {
+    ) -> Result<Vec<u8>, XedDSAError> {
         if private_key.key_type != EllipticCurveType::CURVE25519 {
             return Err(XedDSAError::WrongKeyType);
         }
-        let private_key_bytes: [u8; 32] = private_key.bytes.as_slice().try_into().unwrap();
+        let private_key_bytes: [u8; 32] = private_key
+            .bytes
+            .expose_secret()
+            .try_into()
+            .map_err(|_| XedDSAError::InvalidPrivateKeyLength(private_key.bytes.len()))?;
 
         let key = xeddsa::xed25519::PrivateKey::from(&private_key_bytes);
         let sign: [u8; 64] = key.sign(message, rng);
 
-        Ok(sign)
+        Ok(sign.to_vec())
     }
 
     fn xeddsa_verify(
         &self,
         public_key: &EllipticCurvePublicKey,
         message: &[u8],
-        signature: &[u8; 64],
+        signature: &[u8],
     ) -> Result<bool, XedDSAError> {
         if public_key.key_type != EllipticCurveType::CURVE25519 {
             return Err(XedDSAError::WrongKeyType);
         }
-        let public_key_bytes: [u8; 32] = public_key.bytes.as_slice().try_into().unwrap();
+        let signature: &[u8; 64] = signature
+            .try_into()
+            .map_err(|_| XedDSAError::InvalidSignatureLength)?;
+        let public_key_bytes: [u8; 32] = public_key
+            .bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| XedDSAError::InvalidPublicKeyLength(public_key.bytes.len()))?;
 
         let key =
             xeddsa::xed25519::PublicKey::from(&x25519_dalek::PublicKey::from(public_key_bytes));
@@ -103,3 +201,136 @@ impl EllipticCurveAlgorithm for Curve25519 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::{
+            curve::{
+                enum_elliptic_curve_type::EllipticCurveType,
+                keys::{EllipticCurvePrivateKey, EllipticCurvePublicKey},
+                traits::EllipticCurveAlgorithm,
+            },
+            secret::SecretBytes,
+        },
+        errors::{diffie_hellman::DiffieHellmanError, xeddsa::XedDSAError},
+    };
+
+    use super::{contributory_x25519, Curve25519, LOW_ORDER_POINTS};
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn private_key(bytes: Vec<u8>) -> EllipticCurvePrivateKey {
+        EllipticCurvePrivateKey {
+            key_type: EllipticCurveType::CURVE25519,
+            bytes: SecretBytes::new(bytes),
+        }
+    }
+
+    fn public_key(bytes: Vec<u8>) -> EllipticCurvePublicKey {
+        EllipticCurvePublicKey {
+            key_type: EllipticCurveType::CURVE25519,
+            bytes,
+        }
+    }
+
+    const ALICE_PRIVATE_KEY: &str =
+        "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a";
+    const BOB_PUBLIC_KEY: &str = "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f";
+
+    // Test vector from https://www.rfc-editor.org/rfc/rfc7748#section-6.1
+    #[test]
+    fn x25519_rfc7748_key_agreement() {
+        let curve = Curve25519 {};
+        assert_eq!(
+            curve
+                .dh(
+                    &private_key(from_hex(ALICE_PRIVATE_KEY)),
+                    &public_key(from_hex(BOB_PUBLIC_KEY))
+                )
+                .unwrap(),
+            from_hex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742")
+        );
+    }
+
+    #[test]
+    fn low_order_points_are_rejected() {
+        let curve = Curve25519 {};
+        let key_pair = curve.generate_key_pair(&mut rand::thread_rng());
+
+        for point in LOW_ORDER_POINTS {
+            // The top bit is ignored by X25519, so both encodings must be rejected
+            let mut high_bit_point = point;
+            high_bit_point[31] |= 0x80;
+
+            for encoding in [point, high_bit_point] {
+                assert!(matches!(
+                    curve.validate_public_key(&public_key(encoding.to_vec())),
+                    Err(DiffieHellmanError::LowOrderPublicKey)
+                ));
+                assert!(matches!(
+                    curve.dh(&key_pair.private_key, &public_key(encoding.to_vec())),
+                    Err(DiffieHellmanError::LowOrderPublicKey)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn low_order_points_give_non_contributory_output() {
+        // The list only holds points of small order, which all produce the all-zero output caught by the second check
+        let private_key_bytes: [u8; 32] = from_hex(ALICE_PRIVATE_KEY).try_into().unwrap();
+        for point in LOW_ORDER_POINTS {
+            assert!(matches!(
+                contributory_x25519(private_key_bytes, point),
+                Err(DiffieHellmanError::NonContributoryOutput)
+            ));
+        }
+
+        let bob_public_key: [u8; 32] = from_hex(BOB_PUBLIC_KEY).try_into().unwrap();
+        assert!(contributory_x25519(private_key_bytes, bob_public_key).is_ok());
+    }
+
+    #[test]
+    fn invalid_public_keys_are_rejected() {
+        let curve = Curve25519 {};
+
+        curve
+            .validate_public_key(&public_key(from_hex(BOB_PUBLIC_KEY)))
+            .unwrap();
+        assert!(matches!(
+            curve.validate_public_key(&public_key(vec![9u8; 31])),
+            Err(DiffieHellmanError::InvalidPublicKeyLength(31))
+        ));
+        assert!(matches!(
+            curve.validate_public_key(&EllipticCurvePublicKey {
+                key_type: EllipticCurveType::CURVE448,
+                bytes: from_hex(BOB_PUBLIC_KEY),
+            }),
+            Err(DiffieHellmanError::WrongKeyType)
+        ));
+    }
+
+    #[test]
+    fn xeddsa_rejects_wrong_key_length() {
+        let curve = Curve25519 {};
+
+        assert!(matches!(
+            curve.xeddsa_sign(
+                &private_key(vec![1u8; 31]),
+                b"hello",
+                &mut rand::thread_rng()
+            ),
+            Err(XedDSAError::InvalidPrivateKeyLength(31))
+        ));
+        assert!(matches!(
+            curve.xeddsa_verify(&public_key(vec![9u8; 33]), b"hello", &[0u8; 64]),
+            Err(XedDSAError::InvalidPublicKeyLength(33))
+        ));
+    }
+}
@@ -0,0 +1,427 @@
+use crypto_bigint::subtle::ConstantTimeEq;
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+use crate::{
+    crypto::secret::SecretBytes,
+    errors::{diffie_hellman::DiffieHellmanError, xeddsa::XedDSAError},
+};
+
+use super::{
+    enum_elliptic_curve_type::EllipticCurveType,
+    keys::{
+        EllipticCurveKeyPair, EllipticCurvePrivateKey, EllipticCurvePublicKey,
+        IdentifiedEllipticCurveKeyPair,
+    },
+    traits::EllipticCurveAlgorithm,
+    x448::{is_low_order, x448, BASE_POINT_U},
+    xed448,
+};
+
+pub struct Curve448 {}
+
+impl EllipticCurveAlgorithm for Curve448 {
+    fn generate_key_pair<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> EllipticCurveKeyPair {
+        let mut private_key = [0u8; 56];
+        rng.fill_bytes(&mut private_key);
+        let public_key = x448(&private_key, &BASE_POINT_U);
+
+        let key_pair = EllipticCurveKeyPair {
+            key_type: EllipticCurveType::CURVE448,
+            private_key: EllipticCurvePrivateKey {
+                key_type: EllipticCurveType::CURVE448,
+                bytes: SecretBytes::new(private_key.to_vec()),
+            },
+            public_key: EllipticCurvePublicKey {
+                key_type: EllipticCurveType::CURVE448,
+                bytes: public_key.to_vec(),
+            },
+        };
+        private_key.zeroize();
+        key_pair
+    }
+
+    fn generate_identified_key_pair<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> IdentifiedEllipticCurveKeyPair {
+        IdentifiedEllipticCurveKeyPair::generate_from_keypair(self.generate_key_pair(rng), rng)
+    }
+
+    fn generate_kdf_ikm_prepad(&self) -> &[u8] {
+        &[0xff; 57]
+    }
+
+    fn get_type(&self) -> EllipticCurveType {
+        EllipticCurveType::CURVE448
+    }
+
+    fn validate_public_key(
+        &self,
+        public_key: &EllipticCurvePublicKey,
+    ) -> Result<(), DiffieHellmanError> {
+        if public_key.key_type != EllipticCurveType::CURVE448 {
+            return Err(DiffieHellmanError::WrongKeyType);
+        }
+        let pub_bytes: &[u8; 56] = public_key
+            .bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| DiffieHellmanError::InvalidPublicKeyLength(public_key.bytes.len()))?;
+
+        if is_low_order(pub_bytes) {
+            return Err(DiffieHellmanError::LowOrderPublicKey);
+        }
+
+        Ok(())
+    }
+
+    fn dh(
+        &self,
+        private_key: &EllipticCurvePrivateKey,
+        public_key: &EllipticCurvePublicKey,
+    ) -> Result<Vec<u8>, DiffieHellmanError> {
+        if private_key.key_type != EllipticCurveType::CURVE448 {
+            return Err(DiffieHellmanError::WrongKeyType);
+        }
+        self.validate_public_key(public_key)?;
+
+        let priv_bytes: &[u8; 56] =
+            private_key.bytes.expose_secret().try_into().map_err(|_| {
+                DiffieHellmanError::InvalidPrivateKeyLength(private_key.bytes.len())
+            })?;
+        let pub_bytes: &[u8; 56] = public_key.bytes.as_slice().try_into().unwrap();
+
+        // Reject the all-zero output that any remaining small order point would produce (see https://www.rfc-editor.org/rfc/rfc7748#section-6.2)
+        let mut shared_secret = x448(priv_bytes, pub_bytes);
+        if bool::from(shared_secret.ct_eq(&[0u8; 56])) {
+            shared_secret.zeroize();
+            return Err(DiffieHellmanError::NonContributoryOutput);
+        }
+
+        Ok(shared_secret.to_vec())
+    }
+
+    fn xeddsa_sign<R: RngCore + CryptoRng>(
+        &self,
+        private_key: &EllipticCurvePrivateKey,
+        message: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<u8>, XedDSAError> {
+        if private_key.key_type != EllipticCurveType::CURVE448 {
+            return Err(XedDSAError::WrongKeyType);
+        }
+        let private_key_bytes: &[u8; 56] = private_key
+            .bytes
+            .expose_secret()
+            .try_into()
+            .map_err(|_| XedDSAError::InvalidPrivateKeyLength(private_key.bytes.len()))?;
+
+        Ok(xed448::sign(private_key_bytes, message, rng).to_vec())
+    }
+
+    fn xeddsa_verify(
+        &self,
+        public_key: &EllipticCurvePublicKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, XedDSAError> {
+        if public_key.key_type != EllipticCurveType::CURVE448 {
+            return Err(XedDSAError::WrongKeyType);
+        }
+        let public_key_bytes: &[u8; 56] = public_key
+            .bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| XedDSAError::InvalidPublicKeyLength(public_key.bytes.len()))?;
+        let signature: &[u8; xed448::SIGNATURE_LENGTH] = signature
+            .try_into()
+            .map_err(|_| XedDSAError::InvalidSignatureLength)?;
+
+        Ok(xed448::verify(public_key_bytes, message, signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{CryptoRng, RngCore};
+
+    use crate::{
+        crypto::{
+            curve::{
+                enum_elliptic_curve_type::EllipticCurveType,
+                keys::{EllipticCurvePrivateKey, EllipticCurvePublicKey},
+                traits::EllipticCurveAlgorithm,
+                x448::{x448, BASE_POINT_U},
+            },
+            secret::SecretBytes,
+        },
+        errors::{diffie_hellman::DiffieHellmanError, xeddsa::XedDSAError},
+    };
+
+    use super::Curve448;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn private_key(hex: &str) -> EllipticCurvePrivateKey {
+        EllipticCurvePrivateKey {
+            key_type: EllipticCurveType::CURVE448,
+            bytes: SecretBytes::new(from_hex(hex)),
+        }
+    }
+
+    fn public_key(hex: &str) -> EllipticCurvePublicKey {
+        EllipticCurvePublicKey {
+            key_type: EllipticCurveType::CURVE448,
+            bytes: from_hex(hex),
+        }
+    }
+
+    // Deterministic generator returning the given bytes, used to reproduce known signatures
+    struct FixedRng(Vec<u8>);
+
+    impl RngCore for FixedRng {
+        fn next_u32(&mut self) -> u32 {
+            unimplemented!()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            unimplemented!()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.copy_from_slice(&self.0[..dest.len()]);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for FixedRng {}
+
+    const ALICE_PRIVATE_KEY: &str = "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b";
+    const ALICE_PUBLIC_KEY: &str = "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0";
+    const BOB_PRIVATE_KEY: &str = "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d";
+    const BOB_PUBLIC_KEY: &str = "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609";
+    const SHARED_SECRET: &str = "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d";
+
+    // Test vectors from https://www.rfc-editor.org/rfc/rfc7748#section-5.2
+    #[test]
+    fn x448_rfc7748_vectors() {
+        let vectors = [
+            (
+                "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+                "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086",
+                "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f",
+            ),
+            (
+                "203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f",
+                "0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db",
+                "884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d",
+            ),
+        ];
+
+        for (scalar, u, expected) in vectors {
+            let scalar: [u8; 56] = from_hex(scalar).try_into().unwrap();
+            let u: [u8; 56] = from_hex(u).try_into().unwrap();
+            assert_eq!(x448(&scalar, &u).to_vec(), from_hex(expected));
+        }
+    }
+
+    // Iterated test vectors from https://www.rfc-editor.org/rfc/rfc7748#section-5.2, the 1,000,000 iterations are left out
+    #[test]
+    fn x448_rfc7748_iterations() {
+        let mut k = BASE_POINT_U;
+        let mut u = BASE_POINT_U;
+        for iteration in 1..=1000 {
+            let result = x448(&k, &u);
+            u = k;
+            k = result;
+
+            match iteration {
+                1 => assert_eq!(k.to_vec(), from_hex("3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113")),
+                1000 => assert_eq!(k.to_vec(), from_hex("aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38")),
+                _ => {}
+            }
+        }
+    }
+
+    // Test vector from https://www.rfc-editor.org/rfc/rfc7748#section-6.2
+    #[test]
+    fn x448_rfc7748_key_agreement() {
+        let alice_private_key: [u8; 56] = from_hex(ALICE_PRIVATE_KEY).try_into().unwrap();
+        assert_eq!(
+            x448(&alice_private_key, &BASE_POINT_U).to_vec(),
+            from_hex(ALICE_PUBLIC_KEY)
+        );
+
+        let curve = Curve448 {};
+        assert_eq!(
+            curve
+                .dh(&private_key(ALICE_PRIVATE_KEY), &public_key(BOB_PUBLIC_KEY))
+                .unwrap(),
+            from_hex(SHARED_SECRET)
+        );
+        assert_eq!(
+            curve
+                .dh(&private_key(BOB_PRIVATE_KEY), &public_key(ALICE_PUBLIC_KEY))
+                .unwrap(),
+            from_hex(SHARED_SECRET)
+        );
+    }
+
+    #[test]
+    fn dh_round_trip() {
+        let mut rng = rand::thread_rng();
+        let curve = Curve448 {};
+        let alice = curve.generate_key_pair(&mut rng);
+        let bob = curve.generate_key_pair(&mut rng);
+
+        assert_eq!(alice.public_key.bytes.len(), 56);
+        assert_eq!(
+            curve.dh(&alice.private_key, &bob.public_key).unwrap(),
+            curve.dh(&bob.private_key, &alice.public_key).unwrap()
+        );
+    }
+
+    #[test]
+    fn dh_rejects_low_order_points() {
+        let mut rng = rand::thread_rng();
+        let curve = Curve448 {};
+        let key_pair = curve.generate_key_pair(&mut rng);
+
+        // 0, 1 and p - 1, then p as a non-canonical encoding of 0
+        let mut low_order_points = vec![[0u8; 56], [0u8; 56], [0xffu8; 56], [0xffu8; 56]];
+        low_order_points[1][0] = 1;
+        low_order_points[2][0] = 0xfe;
+        low_order_points[2][28] = 0xfe;
+        low_order_points[3][28] = 0xfe;
+
+        for point in low_order_points {
+            let public_key = EllipticCurvePublicKey {
+                key_type: EllipticCurveType::CURVE448,
+                bytes: point.to_vec(),
+            };
+            assert!(matches!(
+                curve.dh(&key_pair.private_key, &public_key),
+                Err(DiffieHellmanError::LowOrderPublicKey)
+            ));
+        }
+
+        let truncated_key = EllipticCurvePublicKey {
+            key_type: EllipticCurveType::CURVE448,
+            bytes: vec![5u8; 55],
+        };
+        assert!(matches!(
+            curve.dh(&key_pair.private_key, &truncated_key),
+            Err(DiffieHellmanError::InvalidPublicKeyLength(55))
+        ));
+    }
+
+    #[test]
+    fn xeddsa_round_trip() {
+        let mut rng = rand::thread_rng();
+        let curve = Curve448 {};
+        let key_pair = curve.generate_key_pair(&mut rng);
+        let message = b"signed prekey";
+
+        let signature = curve
+            .xeddsa_sign(&key_pair.private_key, message, &mut rng)
+            .unwrap();
+        assert_eq!(
+            signature.len(),
+            EllipticCurveType::CURVE448.signature_length()
+        );
+        assert!(curve
+            .xeddsa_verify(&key_pair.public_key, message, &signature)
+            .unwrap());
+
+        // Tampering with the message or the signature must be detected
+        assert!(!curve
+            .xeddsa_verify(&key_pair.public_key, b"other prekey", &signature)
+            .unwrap());
+        let mut tampered_signature = signature.clone();
+        tampered_signature[10] ^= 1;
+        assert!(!curve
+            .xeddsa_verify(&key_pair.public_key, message, &tampered_signature)
+            .unwrap());
+
+        // The signature must not verify with another key
+        let other_key_pair = curve.generate_key_pair(&mut rng);
+        assert!(!curve
+            .xeddsa_verify(&other_key_pair.public_key, message, &signature)
+            .unwrap());
+    }
+
+    // The XEdDSA specification has no curve448 test vectors, these signatures are printed by xed448_vectors.py, an
+    // independent implementation in affine coordinates whose Montgomery ladder is checked against RFC 7748
+    // Bob's Edwards public key has an odd x, so the second vector covers the negated private scalar
+    #[test]
+    fn xeddsa_known_signatures() {
+        let curve = Curve448 {};
+        let vectors = [
+            (
+                ALICE_PRIVATE_KEY,
+                ALICE_PUBLIC_KEY,
+                b"hello".to_vec(),
+                vec![0u8; 64],
+                "9e273a25a47367d72325e579ea1f6fab0704e2473b09dd09272c093b40a6458975213f41bbcf7a3886a5404565274b83bcb310a4c743763c80051aa5e1aaa093068432e0a0cd44872b514eef366e671133214f2e6868f2318efcd55437529675bcaf1b4724bac25480dbbf45ec0bfbd20100",
+            ),
+            (
+                BOB_PRIVATE_KEY,
+                BOB_PUBLIC_KEY,
+                (0..200).collect(),
+                (0..64).collect(),
+                "7d140f2dd394bcf5da295f54f29a5533c2c806a16bca476e5499e55e12f533dd2def6c4ab5dfa025cfb5e2d96a7a34eda6be64af709be01a807929a7c839f7e2cfdfc2edfe3e7f75ad8d04f1543b8b40c642725b52b3b4d5a6bd0d84b440082327e54eed06048e0bc0e2a9307c0507151e00",
+            ),
+        ];
+
+        for (private_key_hex, public_key_hex, message, z, expected_signature) in vectors {
+            let signature = curve
+                .xeddsa_sign(&private_key(private_key_hex), &message, &mut FixedRng(z))
+                .unwrap();
+            assert_eq!(signature, from_hex(expected_signature));
+            assert!(curve
+                .xeddsa_verify(&public_key(public_key_hex), &message, &signature)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn xeddsa_rejects_wrong_key_length() {
+        let curve = Curve448 {};
+
+        assert!(matches!(
+            curve.xeddsa_sign(
+                &private_key(&ALICE_PRIVATE_KEY[2..]),
+                b"hello",
+                &mut rand::thread_rng()
+            ),
+            Err(XedDSAError::InvalidPrivateKeyLength(55))
+        ));
+        assert!(matches!(
+            curve.xeddsa_verify(&public_key(&ALICE_PUBLIC_KEY[2..]), b"hello", &[0u8; 114]),
+            Err(XedDSAError::InvalidPublicKeyLength(55))
+        ));
+    }
+
+    #[test]
+    fn xeddsa_rejects_wrong_signature_length() {
+        let curve = Curve448 {};
+
+        assert!(matches!(
+            curve.xeddsa_verify(&public_key(ALICE_PUBLIC_KEY), b"hello", &[0u8; 64]),
+            Err(XedDSAError::InvalidSignatureLength)
+        ));
+    }
+}
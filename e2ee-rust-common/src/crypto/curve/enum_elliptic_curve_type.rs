@@ -1,6 +1,20 @@
+use rand::{CryptoRng, RngCore};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::protobuf::crypto::curve::PbEllipticCurveType;
+use crate::{
+    errors::{diffie_hellman::DiffieHellmanError, xeddsa::XedDSAError},
+    protobuf::crypto::curve::PbEllipticCurveType,
+};
+
+use super::{
+    curve25519::Curve25519,
+    curve448::Curve448,
+    keys::{
+        EllipticCurveKeyPair, EllipticCurvePrivateKey, EllipticCurvePublicKey,
+        IdentifiedEllipticCurveKeyPair,
+    },
+    traits::EllipticCurveAlgorithm,
+};
 
 #[derive(Zeroize, ZeroizeOnDrop, PartialEq, Debug, Clone)]
 pub enum EllipticCurveType {
@@ -45,6 +59,13 @@ impl EllipticCurveType {
         }
     }
 
+    pub fn signature_length(&self) -> usize {
+        match self {
+            EllipticCurveType::CURVE25519 => 64,
+            EllipticCurveType::CURVE448 => 114,
+        }
+    }
+
     pub fn to_protobuf(&self) -> PbEllipticCurveType {
         match self {
             EllipticCurveType::CURVE25519 => PbEllipticCurveType::Curve25519,
@@ -52,3 +73,86 @@ impl EllipticCurveType {
         }
     }
 }
+
+// Resolves the curve implementation at runtime, so that the curve can be chosen from a value instead of a type parameter
+impl EllipticCurveAlgorithm for EllipticCurveType {
+    fn generate_key_pair<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> EllipticCurveKeyPair {
+        match self {
+            EllipticCurveType::CURVE25519 => Curve25519 {}.generate_key_pair(rng),
+            EllipticCurveType::CURVE448 => Curve448 {}.generate_key_pair(rng),
+        }
+    }
+
+    fn generate_identified_key_pair<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> IdentifiedEllipticCurveKeyPair {
+        match self {
+            EllipticCurveType::CURVE25519 => Curve25519 {}.generate_identified_key_pair(rng),
+            EllipticCurveType::CURVE448 => Curve448 {}.generate_identified_key_pair(rng),
+        }
+    }
+
+    fn generate_kdf_ikm_prepad(&self) -> &[u8] {
+        match self {
+            EllipticCurveType::CURVE25519 => Curve25519 {}.generate_kdf_ikm_prepad(),
+            EllipticCurveType::CURVE448 => Curve448 {}.generate_kdf_ikm_prepad(),
+        }
+    }
+
+    fn get_type(&self) -> EllipticCurveType {
+        self.clone()
+    }
+
+    fn validate_public_key(
+        &self,
+        public_key: &EllipticCurvePublicKey,
+    ) -> Result<(), DiffieHellmanError> {
+        match self {
+            EllipticCurveType::CURVE25519 => Curve25519 {}.validate_public_key(public_key),
+            EllipticCurveType::CURVE448 => Curve448 {}.validate_public_key(public_key),
+        }
+    }
+
+    fn dh(
+        &self,
+        private_key: &EllipticCurvePrivateKey,
+        public_key: &EllipticCurvePublicKey,
+    ) -> Result<Vec<u8>, DiffieHellmanError> {
+        match self {
+            EllipticCurveType::CURVE25519 => Curve25519 {}.dh(private_key, public_key),
+            EllipticCurveType::CURVE448 => Curve448 {}.dh(private_key, public_key),
+        }
+    }
+
+    fn xeddsa_sign<R: RngCore + CryptoRng>(
+        &self,
+        private_key: &EllipticCurvePrivateKey,
+        message: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<u8>, XedDSAError> {
+        match self {
+            EllipticCurveType::CURVE25519 => Curve25519 {}.xeddsa_sign(private_key, message, rng),
+            EllipticCurveType::CURVE448 => Curve448 {}.xeddsa_sign(private_key, message, rng),
+        }
+    }
+
+    fn xeddsa_verify(
+        &self,
+        public_key: &EllipticCurvePublicKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, XedDSAError> {
+        match self {
+            EllipticCurveType::CURVE25519 => {
+                Curve25519 {}.xeddsa_verify(public_key, message, signature)
+            }
+            EllipticCurveType::CURVE448 => {
+                Curve448 {}.xeddsa_verify(public_key, message, signature)
+            }
+        }
+    }
+}
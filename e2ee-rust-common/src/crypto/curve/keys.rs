@@ -3,7 +3,7 @@ use uuid::Uuid;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{
-    crypto::traits::PrintableKey,
+    crypto::{secret::SecretBytes, traits::PrintableKey},
     errors::{encoding::EncodingError, protobuf::ProtobufError},
     protobuf::crypto::curve::{PbEllipticCurvePublicKey, PbIdentifiedEllipticCurvePublicKey},
     protobuf::utils::uuid_from_bytes,
@@ -17,21 +17,22 @@ pub struct EllipticCurvePublicKey {
     pub bytes: Vec<u8>,
 }
 
-#[derive(Zeroize, ZeroizeOnDrop, Debug, Clone)]
+#[derive(Zeroize, ZeroizeOnDrop, Debug, PartialEq)]
 pub struct EllipticCurvePrivateKey {
     pub key_type: EllipticCurveType,
-    pub bytes: Vec<u8>,
+    pub bytes: SecretBytes,
 }
 
-#[derive(Zeroize, ZeroizeOnDrop, Debug, Clone)]
+#[derive(Zeroize, ZeroizeOnDrop, Debug)]
 pub struct EllipticCurveKeyPair {
     pub key_type: EllipticCurveType,
     pub private_key: EllipticCurvePrivateKey,
     pub public_key: EllipticCurvePublicKey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Zeroize, ZeroizeOnDrop, Debug)]
 pub struct IdentifiedEllipticCurveKeyPair {
+    #[zeroize(skip)]
     pub id: Uuid,
     pub key_pair: EllipticCurveKeyPair,
 }
@@ -60,7 +61,7 @@ impl EllipticCurveKeyPair {
             key_type: key_type_enum.clone(),
             private_key: EllipticCurvePrivateKey {
                 key_type: key_type_enum.clone(),
-                bytes: private_key_bytes,
+                bytes: SecretBytes::new(private_key_bytes),
             },
             public_key: EllipticCurvePublicKey {
                 key_type: key_type_enum,
@@ -70,6 +71,20 @@ impl EllipticCurveKeyPair {
     }
 }
 
+impl EllipticCurveKeyPair {
+    // Private keys do not implement Clone, so that every copy of the secret is explicit
+    pub fn duplicate(&self) -> Self {
+        Self {
+            key_type: self.key_type.clone(),
+            private_key: EllipticCurvePrivateKey {
+                key_type: self.private_key.key_type.clone(),
+                bytes: SecretBytes::from_slice(self.private_key.bytes.expose_secret()),
+            },
+            public_key: self.public_key.clone(),
+        }
+    }
+}
+
 impl EllipticCurvePublicKey {
     pub fn from_bytes(key_type: u8, bytes: Vec<u8>) -> Result<Self, EncodingError> {
         let key_type_enum =
@@ -200,18 +215,6 @@ impl EllipticCurvePublicKey {
     }
 }
 
-impl PrintableKey for EllipticCurvePrivateKey {
-    fn print_key(&self) -> String {
-        format!(
-            "0x{}",
-            self.bytes
-                .iter()
-                .map(|b| format!("{:02x}", b))
-                .collect::<String>()
-        )
-    }
-}
-
 impl PrintableKey for EllipticCurvePublicKey {
     fn print_key(&self) -> String {
         format!(
@@ -1,4 +1,7 @@
 pub mod curve25519;
+pub mod curve448;
 pub mod enum_elliptic_curve_type;
 pub mod keys;
 pub mod traits;
+mod x448;
+mod xed448;
@@ -29,12 +29,19 @@ pub trait EllipticCurveAlgorithm {
     // Get the type of the elliptic curve algorithm
     fn get_type(&self) -> EllipticCurveType;
 
+    // Check that a peer public key has the expected length and is not a point of small order
+    fn validate_public_key(
+        &self,
+        public_key: &EllipticCurvePublicKey,
+    ) -> Result<(), DiffieHellmanError>;
+
     // Perform the Diffie-Hellman key exchange
+    // Fails on malformed keys and on non-contributory outputs, so that a peer cannot force a known shared secret
     fn dh(
         &self,
         private_key: &EllipticCurvePrivateKey,
         public_key: &EllipticCurvePublicKey,
-    ) -> Result<[u8; 32], DiffieHellmanError>;
+    ) -> Result<Vec<u8>, DiffieHellmanError>;
 
     // Perform an XEdDSA signature
     fn xeddsa_sign<R: RngCore + CryptoRng>(
@@ -42,13 +49,13 @@ pub trait EllipticCurveAlgorithm {
         private_key: &EllipticCurvePrivateKey,
         message: &[u8],
         rng: &mut R,
-    ) -> Result<[u8; 64], XedDSAError>;
+    ) -> Result<Vec<u8>, XedDSAError>;
 
     // Verify an XEdDSA signature
     fn xeddsa_verify(
         &self,
         public_key: &EllipticCurvePublicKey,
         message: &[u8],
-        signature: &[u8; 64],
+        signature: &[u8],
     ) -> Result<bool, XedDSAError>;
 }
@@ -0,0 +1,100 @@
+use crypto_bigint::{
+    impl_modulus,
+    modular::constant_mod::Residue,
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq},
+    Encoding, U448,
+};
+
+// Field of curve448, modulo p = 2^448 - 2^224 - 1
+const P_HEX: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+const P: U448 = U448::from_be_hex(P_HEX);
+impl_modulus!(FieldModulus, U448, P_HEX);
+
+pub type FieldElement = Residue<FieldModulus, { U448::LIMBS }>;
+
+pub const FIELD_ZERO: FieldElement = FieldElement::new(&U448::ZERO);
+pub const FIELD_ONE: FieldElement = FieldElement::new(&U448::ONE);
+
+// p - 2, used to compute inverses (0 is mapped to 0)
+const P_MINUS_2: U448 = U448::from_be_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffffffffffffffffffffffffffffffffffffffffffffffffffffd");
+
+// (A - 2) / 4 for the curve448 Montgomery curve
+const A24: FieldElement = FieldElement::new(&U448::from_u64(39081));
+
+// u-coordinate of the curve448 base point
+pub const BASE_POINT_U: [u8; 56] = {
+    let mut u = [0u8; 56];
+    u[0] = 5;
+    u
+};
+
+// Decodes a little-endian field element, reducing it modulo p
+pub fn decode_field_element(bytes: &[u8; 56]) -> FieldElement {
+    FieldElement::new(&U448::from_le_slice(bytes))
+}
+
+// Checks that a little-endian encoded integer is lower than p
+pub fn is_canonical(bytes: &[u8; 56]) -> bool {
+    U448::from_le_slice(bytes) < P
+}
+
+// Checks whether a u-coordinate is a point of small order on curve448 or its twist, i.e. 0, 1 or -1 modulo p
+pub fn is_low_order(bytes: &[u8; 56]) -> bool {
+    let u = decode_field_element(bytes);
+    bool::from(u.ct_eq(&FIELD_ZERO) | u.ct_eq(&FIELD_ONE) | u.ct_eq(&-FIELD_ONE))
+}
+
+// Encodes a field element in little-endian
+pub fn encode_field_element(element: &FieldElement) -> [u8; 56] {
+    element.retrieve().to_le_bytes()
+}
+
+// Computes the inverse of a field element
+pub fn invert(element: &FieldElement) -> FieldElement {
+    element.pow(&P_MINUS_2)
+}
+
+// Clamps a private key into an X448 scalar (see https://www.rfc-editor.org/rfc/rfc7748#section-5)
+pub fn clamp(scalar: &[u8; 56]) -> [u8; 56] {
+    let mut clamped = *scalar;
+    clamped[0] &= 252;
+    clamped[55] |= 128;
+    clamped
+}
+
+// Computes the X448 function with the Montgomery ladder (see https://www.rfc-editor.org/rfc/rfc7748#section-5)
+pub fn x448(scalar: &[u8; 56], u: &[u8; 56]) -> [u8; 56] {
+    let k = clamp(scalar);
+    let x_1 = decode_field_element(u);
+    let mut x_2 = FIELD_ONE;
+    let mut z_2 = FIELD_ZERO;
+    let mut x_3 = x_1;
+    let mut z_3 = FIELD_ONE;
+    let mut swap = Choice::from(0);
+
+    for t in (0..448).rev() {
+        let k_t = Choice::from((k[t / 8] >> (t % 8)) & 1);
+        swap ^= k_t;
+        FieldElement::conditional_swap(&mut x_2, &mut x_3, swap);
+        FieldElement::conditional_swap(&mut z_2, &mut z_3, swap);
+        swap = k_t;
+
+        let a = x_2 + z_2;
+        let aa = a.square();
+        let b = x_2 - z_2;
+        let bb = b.square();
+        let e = aa - bb;
+        let c = x_3 + z_3;
+        let d = x_3 - z_3;
+        let da = d * a;
+        let cb = c * b;
+        x_3 = (da + cb).square();
+        z_3 = x_1 * (da - cb).square();
+        x_2 = aa * bb;
+        z_2 = e * (aa + A24 * e);
+    }
+    FieldElement::conditional_swap(&mut x_2, &mut x_3, swap);
+    FieldElement::conditional_swap(&mut z_2, &mut z_3, swap);
+
+    encode_field_element(&(x_2 * invert(&z_2)))
+}
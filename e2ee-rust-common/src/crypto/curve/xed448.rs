@@ -0,0 +1,252 @@
+use crypto_bigint::{
+    impl_modulus,
+    modular::constant_mod::Residue,
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq},
+    Encoding, NonZero, U448, U512,
+};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use zeroize::Zeroize;
+
+use super::x448::{
+    clamp, decode_field_element, encode_field_element, invert, is_canonical, FieldElement,
+    FIELD_ONE, FIELD_ZERO,
+};
+
+// XEdDSA over curve448 (see https://signal.org/docs/specifications/xeddsa/#xeddsa)
+// Points live on the twisted Edwards curve -x^2 + y^2 = 1 + d * x^2 * y^2 that is birationally equivalent to curve448 with y = (u - 1) / (u + 1)
+// No vetted curve448 XEdDSA implementation is available, signatures are checked against xed448_vectors.py in the curve448 tests
+
+pub const SIGNATURE_LENGTH: usize = 114;
+const POINT_LENGTH: usize = 57;
+
+// Order of the base point, q = 2^446 - 13818066809895115352007386748515426880336692474882178609894547503885
+impl_modulus!(
+    ScalarModulus,
+    U448,
+    "3fffffffffffffffffffffffffffffffffffffffffffffffffffffff7cca23e9c44edb49aed63690216cc2728dc58f552378c292ab5844f3"
+);
+
+type Scalar = Residue<ScalarModulus, { U448::LIMBS }>;
+
+const Q_WIDE: U512 = U512::from_be_hex("00000000000000003fffffffffffffffffffffffffffffffffffffffffffffffffffffff7cca23e9c44edb49aed63690216cc2728dc58f552378c292ab5844f3");
+
+// d = -39081 / 39082
+const EDWARDS_D: FieldElement = FieldElement::new(&U448::from_be_hex("934c1a0b4af2e995b384b25380ec70d2a89b01cb7762e59a27ccc00dad9a784a4e93e80d4f6de6c4a4129a5a1b3921c95ec884c9cd7528c0"));
+
+// (p + 1) / 4, used to compute square roots since p = 3 mod 4
+const P_PLUS_1_DIV_4: U448 = U448::from_be_hex("3fffffffffffffffffffffffffffffffffffffffffffffffffffffffc0000000000000000000000000000000000000000000000000000000");
+
+// Edwards equivalent of the curve448 base point u = 5, with an even x
+const BASE_POINT: EdwardsPoint = EdwardsPoint::from_affine(
+    FieldElement::new(&U448::from_be_hex("0f6bf2e2a5abbada4db36ea4e2fc9b5b486bd4a1d6ea58dc9b6848564356cd00c82760ad911114f1a5cbe3ff74913ff149dd858f3ec81e8c")),
+    FieldElement::new(&U448::from_be_hex("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa00000000000000000000000000000000000000000000000000000000")),
+);
+
+// Point in extended twisted Edwards coordinates (see https://eprint.iacr.org/2008/522)
+#[derive(Clone, Copy)]
+struct EdwardsPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl EdwardsPoint {
+    const IDENTITY: Self = Self {
+        x: FIELD_ZERO,
+        y: FIELD_ONE,
+        z: FIELD_ONE,
+        t: FIELD_ZERO,
+    };
+
+    const fn from_affine(x: FieldElement, y: FieldElement) -> Self {
+        Self {
+            x,
+            y,
+            z: FIELD_ONE,
+            t: x.mul(&y),
+        }
+    }
+
+    // Unified addition for a = -1
+    fn add(&self, other: &Self) -> Self {
+        let a = (self.y - self.x) * (other.y - other.x);
+        let b = (self.y + self.x) * (other.y + other.x);
+        let c = self.t * (EDWARDS_D + EDWARDS_D) * other.t;
+        let d = self.z * (other.z + other.z);
+        let e = b - a;
+        let f = d - c;
+        let g = d + c;
+        let h = b + a;
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    fn neg(&self) -> Self {
+        Self {
+            x: -self.x,
+            y: self.y,
+            z: self.z,
+            t: -self.t,
+        }
+    }
+
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: FieldElement::conditional_select(&a.x, &b.x, choice),
+            y: FieldElement::conditional_select(&a.y, &b.y, choice),
+            z: FieldElement::conditional_select(&a.z, &b.z, choice),
+            t: FieldElement::conditional_select(&a.t, &b.t, choice),
+        }
+    }
+
+    // Multiplies the point by a little-endian scalar in constant time
+    fn mul(&self, scalar: &[u8]) -> Self {
+        let mut result = Self::IDENTITY;
+        for i in (0..scalar.len() * 8).rev() {
+            result = result.add(&result);
+            let sum = result.add(self);
+            result = Self::conditional_select(
+                &result,
+                &sum,
+                Choice::from((scalar[i / 8] >> (i % 8)) & 1),
+            );
+        }
+        result
+    }
+
+    // Encodes y in little-endian, with the sign of x in the top bit
+    fn encode(&self) -> [u8; POINT_LENGTH] {
+        let z_inv = invert(&self.z);
+        let x = encode_field_element(&(self.x * z_inv));
+        let y = encode_field_element(&(self.y * z_inv));
+
+        let mut encoded = [0u8; POINT_LENGTH];
+        encoded[..56].copy_from_slice(&y);
+        encoded[56] = (x[0] & 1) << 7;
+        encoded
+    }
+
+    // Converts a Montgomery u-coordinate into the Edwards point with an even x
+    fn from_montgomery(u: &[u8; 56]) -> Option<Self> {
+        let u = decode_field_element(u);
+        let y = (u - FIELD_ONE) * invert(&(u + FIELD_ONE));
+
+        // Recover x from -x^2 + y^2 = 1 + d * x^2 * y^2
+        let y2 = y.square();
+        let x2 = (y2 - FIELD_ONE) * invert(&(EDWARDS_D * y2 + FIELD_ONE));
+        let mut x = x2.pow(&P_PLUS_1_DIV_4);
+        if !bool::from(x.square().ct_eq(&x2)) {
+            return None;
+        }
+        if encode_field_element(&x)[0] & 1 == 1 {
+            x = -x;
+        }
+
+        Some(Self::from_affine(x, y))
+    }
+}
+
+// Reduces a 512-bit little-endian hash modulo q
+fn scalar_from_hash(hash: &[u8]) -> Scalar {
+    let wide = U512::from_le_slice(hash).rem(&NonZero::new(Q_WIDE).unwrap());
+    Scalar::new(&wide.resize())
+}
+
+fn encode_scalar(scalar: &Scalar) -> [u8; POINT_LENGTH] {
+    let mut encoded = [0u8; POINT_LENGTH];
+    encoded[..56].copy_from_slice(&scalar.retrieve().to_le_bytes());
+    encoded
+}
+
+// Computes the Edwards public key with a sign bit of 0, and the matching private scalar
+fn calculate_key_pair(private_key: &[u8; 56]) -> ([u8; POINT_LENGTH], Scalar) {
+    let mut k = clamp(private_key);
+    let mut public_key = BASE_POINT.mul(&k).encode();
+    let a = Scalar::new(&U448::from_le_slice(&k));
+    k.zeroize();
+
+    let sign = Choice::from(public_key[56] >> 7);
+    public_key[56] &= 0x7f;
+    (public_key, Scalar::conditional_select(&a, &-a, sign))
+}
+
+pub fn sign<R: RngCore + CryptoRng>(
+    private_key: &[u8; 56],
+    message: &[u8],
+    rng: &mut R,
+) -> [u8; SIGNATURE_LENGTH] {
+    let (public_key, a) = calculate_key_pair(private_key);
+    let mut a_bytes = encode_scalar(&a);
+    let mut z = [0u8; 64];
+    rng.fill_bytes(&mut z);
+
+    // r = hash1(a || M || Z) (mod q)
+    let mut prefix = [0xffu8; POINT_LENGTH];
+    prefix[0] = 0xfe;
+    let mut hash = Sha512::new()
+        .chain_update(prefix)
+        .chain_update(a_bytes)
+        .chain_update(message)
+        .chain_update(z)
+        .finalize();
+    let r = scalar_from_hash(&hash);
+    hash.zeroize();
+    a_bytes.zeroize();
+    z.zeroize();
+
+    // R = rB, h = hash(R || A || M) (mod q), s = r + ha (mod q)
+    let mut r_bytes = encode_scalar(&r);
+    let r_point = BASE_POINT.mul(&r_bytes).encode();
+    r_bytes.zeroize();
+    let h = scalar_from_hash(
+        &Sha512::new()
+            .chain_update(r_point)
+            .chain_update(public_key)
+            .chain_update(message)
+            .finalize(),
+    );
+    let s = r + h * a;
+
+    let mut signature = [0u8; SIGNATURE_LENGTH];
+    signature[..POINT_LENGTH].copy_from_slice(&r_point);
+    signature[POINT_LENGTH..].copy_from_slice(&encode_scalar(&s));
+    signature
+}
+
+pub fn verify(public_key: &[u8; 56], message: &[u8], signature: &[u8; SIGNATURE_LENGTH]) -> bool {
+    let (r_point, s) = signature.split_at(POINT_LENGTH);
+
+    // Reject non-canonical inputs: u >= p, R.y >= 2^448 and s >= 2^446
+    if !is_canonical(public_key) {
+        return false;
+    }
+    if r_point[56] & 0x7f != 0 || s[56] != 0 || s[55] & 0xc0 != 0 {
+        return false;
+    }
+
+    let a_point = match EdwardsPoint::from_montgomery(public_key) {
+        Some(point) => point,
+        None => return false,
+    };
+
+    // Rcheck = sB - hA
+    let h = scalar_from_hash(
+        &Sha512::new()
+            .chain_update(r_point)
+            .chain_update(a_point.encode())
+            .chain_update(message)
+            .finalize(),
+    );
+    let r_check = BASE_POINT
+        .mul(s)
+        .add(&a_point.mul(&encode_scalar(&h)).neg())
+        .encode();
+
+    bool::from(r_check.as_slice().ct_eq(r_point))
+}
@@ -0,0 +1,162 @@
+# Generates the curve448 XEdDSA signatures checked by xeddsa_known_signatures in curve448.rs
+# Independent implementation of https://signal.org/docs/specifications/xeddsa/ with affine Edwards coordinates
+# and Python integers, sharing no code with x448.rs and xed448.rs
+# Its Montgomery ladder is first checked against the RFC 7748 vectors
+#
+# Usage: python3 xed448_vectors.py
+import hashlib
+
+p = 2**448 - 2**224 - 1
+q = 2**446 - 13818066809895115352007386748515426880336692474882178609894547503885
+
+
+def inv(x):
+    return pow(x, p - 2, p)
+
+
+def sqrt(x):
+    r = pow(x, (p + 1) // 4, p)
+    return r if r * r % p == x % p else None
+
+
+# Montgomery curve448: v^2 = u^3 + A u^2 + u, birationally equivalent to the twisted Edwards curve
+# -x^2 + y^2 = 1 + d x^2 y^2 used by XEdDSA
+A = 156326
+d = (-(A - 2) * inv(A + 2)) % p
+
+
+def edwards_add(P, Q):
+    (x1, y1), (x2, y2) = P, Q
+    t = d * x1 * x2 * y1 * y2 % p
+    return ((x1 * y2 + y1 * x2) * inv(1 + t) % p, (y1 * y2 + x1 * x2) * inv(1 - t) % p)
+
+
+def edwards_mul(k, P):
+    R = (0, 1)
+    while k:
+        if k & 1:
+            R = edwards_add(R, P)
+        P = edwards_add(P, P)
+        k >>= 1
+    return R
+
+
+# Edwards point of the u-coordinate, with an even x
+def from_montgomery(u):
+    y = (u - 1) * inv(u + 1) % p
+    x = sqrt((y * y - 1) * inv(d * y * y + 1) % p)
+    assert x is not None
+    return (p - x if x & 1 else x, y)
+
+
+def encode_point(P):
+    x, y = P
+    return (y | ((x & 1) << 455)).to_bytes(57, "little")
+
+
+def clamp(k):
+    k = bytearray(k)
+    k[0] &= 252
+    k[55] |= 128
+    return int.from_bytes(k, "little")
+
+
+# X448 from https://www.rfc-editor.org/rfc/rfc7748#section-5
+def x448(k, u):
+    k = clamp(k)
+    x1, x2, z2, x3, z3, swap = int.from_bytes(u, "little") % p, 1, 0, int.from_bytes(u, "little") % p, 1, 0
+    for t in reversed(range(448)):
+        kt = (k >> t) & 1
+        swap ^= kt
+        if swap:
+            x2, x3, z2, z3 = x3, x2, z3, z2
+        swap = kt
+        a = x2 + z2
+        aa = a * a
+        b = x2 - z2
+        bb = b * b
+        e = aa - bb
+        c = x3 + z3
+        dd = x3 - z3
+        da = dd * a
+        cb = c * b
+        x3 = (da + cb) ** 2 % p
+        z3 = x1 * (da - cb) ** 2 % p
+        x2 = aa * bb % p
+        z2 = e * (aa + 39081 * e) % p
+    if swap:
+        x2, x3, z2, z3 = x3, x2, z3, z2
+    return (x2 * inv(z2) % p).to_bytes(56, "little")
+
+
+B = from_montgomery(5)
+assert edwards_mul(q, B) == (0, 1)
+
+
+# calculate_key_pair: the Edwards public key always has a sign bit of 0
+def calculate_key_pair(k):
+    a = clamp(k)
+    E = edwards_mul(a, B)
+    if E[0] & 1:
+        return encode_point((p - E[0], E[1])), (-a) % q
+    return encode_point(E), a % q
+
+
+def hash_to_scalar(data):
+    return int.from_bytes(hashlib.sha512(data).digest(), "little") % q
+
+
+def sign(k, message, Z):
+    public_key, a = calculate_key_pair(k)
+    r = hash_to_scalar(bytes([0xFE]) + b"\xff" * 56 + a.to_bytes(57, "little") + message + Z)
+    R = encode_point(edwards_mul(r, B))
+    h = hash_to_scalar(R + public_key + message)
+    return R + ((r + h * a) % q).to_bytes(57, "little")
+
+
+def verify(u, message, signature):
+    u = int.from_bytes(u, "little")
+    if u >= p:
+        return False
+    R, s = signature[:57], int.from_bytes(signature[57:], "little")
+    if s >= 2**446 or int.from_bytes(R, "little") & ((1 << 455) - 1) >= p:
+        return False
+    public_key = from_montgomery(u)
+    h = hash_to_scalar(R + encode_point(public_key) + message)
+    return encode_point(edwards_add(edwards_mul(s, B), edwards_mul(h, (p - public_key[0], public_key[1])))) == R
+
+
+h = bytes.fromhex
+
+# https://www.rfc-editor.org/rfc/rfc7748#section-5.2
+assert x448(
+    h("3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3"),
+    h("06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086"),
+) == h("ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f")
+assert x448(
+    h("203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f"),
+    h("0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db"),
+) == h("884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d")
+
+# https://www.rfc-editor.org/rfc/rfc7748#section-6.2
+alice = h("9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b")
+alice_public = h("9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0")
+bob = h("1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d")
+bob_public = h("3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609")
+base_point = (5).to_bytes(56, "little")
+assert x448(alice, base_point) == alice_public
+assert x448(bob, base_point) == bob_public
+assert x448(alice, bob_public) == x448(bob, alice_public)
+
+# The Edwards public key matches the Montgomery public key
+assert calculate_key_pair(alice)[0] == encode_point(from_montgomery(int.from_bytes(alice_public, "little")))
+assert calculate_key_pair(bob)[0] == encode_point(from_montgomery(int.from_bytes(bob_public, "little")))
+
+# Bob's Edwards public key has an odd x, so his vector covers the negated private scalar
+for name, private_key, public_key, message, Z in [
+    ("alice", alice, alice_public, b"hello", bytes(64)),
+    ("bob", bob, bob_public, bytes(range(200)), bytes(range(64))),
+]:
+    signature = sign(private_key, message, Z)
+    assert verify(public_key, message, signature)
+    print(name, signature.hex())
@@ -1,4 +1,6 @@
 pub mod aead;
+pub mod cipher_suite;
 pub mod curve;
 pub mod pqkem;
+pub mod secret;
 pub mod traits;
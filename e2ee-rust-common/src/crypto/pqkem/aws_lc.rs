@@ -0,0 +1,615 @@
+use std::ptr::{null, null_mut};
+
+use aws_lc_sys::{
+    EVP_PKEY_CTX_free, EVP_PKEY_CTX_kem_set_params, EVP_PKEY_CTX_new, EVP_PKEY_CTX_new_id,
+    EVP_PKEY_decapsulate, EVP_PKEY_encapsulate_deterministic, EVP_PKEY_free,
+    EVP_PKEY_get_raw_private_key, EVP_PKEY_get_raw_public_key, EVP_PKEY_kem_new_raw_public_key,
+    EVP_PKEY_kem_new_raw_secret_key, EVP_PKEY_keygen_deterministic, EVP_PKEY_keygen_init, EVP_PKEY,
+    EVP_PKEY_CTX, EVP_PKEY_KEM, NID_KYBER1024_R3, NID_KYBER512_R3, NID_KYBER768_R3, NID_MLKEM1024,
+    NID_MLKEM512, NID_MLKEM768,
+};
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+use crate::errors::pqkem::PQKEMError;
+
+// CRYSTALS-Kyber round 3 (see https://pq-crystals.org/kyber/data/kyber-specification-round3-20210804.pdf)
+// and ML-KEM (see https://csrc.nist.gov/pubs/fips/203/final) through the KEM API of AWS-LC, which implements every
+// parameter set
+// aws-lc-sys is pinned to 0.23.1: later releases dropped the round 3 parameter sets
+// The randomness is drawn from the caller's generator and handed to the deterministic functions, like the rest of the crate
+
+const SHARED_SECRET_LENGTH: usize = 32;
+const KEYGEN_SEED_LENGTH: usize = 64;
+const ENCAPSULATION_SEED_LENGTH: usize = 32;
+const Q: u16 = 3329;
+
+pub struct KemParameters {
+    nid: i32,
+    checks_public_key_modulus: bool,
+}
+
+pub const KYBER512: KemParameters = KemParameters {
+    nid: NID_KYBER512_R3,
+    checks_public_key_modulus: false,
+};
+
+pub const KYBER768: KemParameters = KemParameters {
+    nid: NID_KYBER768_R3,
+    checks_public_key_modulus: false,
+};
+
+pub const KYBER1024: KemParameters = KemParameters {
+    nid: NID_KYBER1024_R3,
+    checks_public_key_modulus: false,
+};
+
+pub const MLKEM512: KemParameters = KemParameters {
+    nid: NID_MLKEM512,
+    checks_public_key_modulus: true,
+};
+
+pub const MLKEM768: KemParameters = KemParameters {
+    nid: NID_MLKEM768,
+    checks_public_key_modulus: true,
+};
+
+pub const MLKEM1024: KemParameters = KemParameters {
+    nid: NID_MLKEM1024,
+    checks_public_key_modulus: true,
+};
+
+// Owned AWS-LC objects, freed when dropped
+struct PKey(*mut EVP_PKEY);
+
+impl Drop for PKey {
+    fn drop(&mut self) {
+        // SAFETY: the key was allocated by AWS-LC and is only freed here, EVP_PKEY_free accepts null
+        unsafe { EVP_PKEY_free(self.0) }
+    }
+}
+
+struct PKeyContext(*mut EVP_PKEY_CTX);
+
+impl Drop for PKeyContext {
+    fn drop(&mut self) {
+        // SAFETY: the context was allocated by AWS-LC and is only freed here, EVP_PKEY_CTX_free accepts null
+        unsafe { EVP_PKEY_CTX_free(self.0) }
+    }
+}
+
+// ML-KEM requires every 12-bit coefficient encoded in the public key to be reduced modulo q (FIPS 203, section 7.2),
+// which this release of AWS-LC does not check
+fn is_public_key_reduced(public_key: &[u8]) -> bool {
+    let encoded_polynomials = &public_key[..public_key.len().saturating_sub(32)];
+    encoded_polynomials.chunks_exact(3).all(|bytes| {
+        let (b0, b1, b2) = (bytes[0] as u16, bytes[1] as u16, bytes[2] as u16);
+        (b0 | ((b1 & 0x0f) << 8)) < Q && ((b1 >> 4) | (b2 << 4)) < Q
+    })
+}
+
+// Copies a raw key out of an AWS-LC key, with the getter for the public or the private part
+fn raw_key(
+    key: &PKey,
+    get: unsafe extern "C" fn(*const EVP_PKEY, *mut u8, *mut usize) -> i32,
+) -> Option<Vec<u8>> {
+    let mut length = 0;
+    // SAFETY: a null output only queries the length of the key
+    if unsafe { get(key.0, null_mut(), &mut length) } != 1 {
+        return None;
+    }
+    let mut bytes = vec![0u8; length];
+    // SAFETY: the buffer holds the queried length
+    if unsafe { get(key.0, bytes.as_mut_ptr(), &mut length) } != 1 || length != bytes.len() {
+        bytes.zeroize();
+        return None;
+    }
+    Some(bytes)
+}
+
+// Returns (public_key, private_key)
+pub fn generate_key_pair<R: RngCore + CryptoRng>(
+    params: &KemParameters,
+    rng: &mut R,
+) -> (Vec<u8>, Vec<u8>) {
+    let mut seed = [0u8; KEYGEN_SEED_LENGTH];
+    rng.fill_bytes(&mut seed);
+
+    // SAFETY: every pointer is either checked for null or points to a live buffer of the given length
+    let key_pair = unsafe {
+        let context = PKeyContext(EVP_PKEY_CTX_new_id(EVP_PKEY_KEM, null_mut()));
+        let mut key = PKey(null_mut());
+        let mut seed_length = seed.len();
+        if context.0.is_null()
+            || EVP_PKEY_CTX_kem_set_params(context.0, params.nid) != 1
+            || EVP_PKEY_keygen_init(context.0) != 1
+            || EVP_PKEY_keygen_deterministic(context.0, &mut key.0, seed.as_ptr(), &mut seed_length)
+                != 1
+        {
+            None
+        } else {
+            raw_key(&key, EVP_PKEY_get_raw_public_key)
+                .zip(raw_key(&key, EVP_PKEY_get_raw_private_key))
+        }
+    };
+    seed.zeroize();
+
+    // AWS-LC only fails to generate a key when it runs out of memory
+    key_pair.expect("AWS-LC failed to generate a PQKEM key pair")
+}
+
+// Returns (ciphertext, shared_secret)
+pub fn encapsulate<R: RngCore + CryptoRng>(
+    params: &KemParameters,
+    public_key: &[u8],
+    rng: &mut R,
+) -> Result<(Vec<u8>, Vec<u8>), PQKEMError> {
+    if params.checks_public_key_modulus && !is_public_key_reduced(public_key) {
+        return Err(PQKEMError::EncapsulationError);
+    }
+
+    let mut seed = [0u8; ENCAPSULATION_SEED_LENGTH];
+    rng.fill_bytes(&mut seed);
+
+    // SAFETY: every pointer is either checked for null or points to a live buffer of the given length
+    let output = unsafe {
+        // The public key length is checked by AWS-LC
+        let key = PKey(EVP_PKEY_kem_new_raw_public_key(
+            params.nid,
+            public_key.as_ptr(),
+            public_key.len(),
+        ));
+        let context = PKeyContext(if key.0.is_null() {
+            null_mut()
+        } else {
+            EVP_PKEY_CTX_new(key.0, null_mut())
+        });
+
+        let mut ciphertext_length = 0;
+        let mut shared_secret_length = 0;
+        let mut seed_length = 0;
+        if context.0.is_null()
+            || EVP_PKEY_encapsulate_deterministic(
+                context.0,
+                null_mut(),
+                &mut ciphertext_length,
+                null_mut(),
+                &mut shared_secret_length,
+                null(),
+                &mut seed_length,
+            ) != 1
+        {
+            None
+        } else {
+            let mut ciphertext = vec![0u8; ciphertext_length];
+            let mut shared_secret = vec![0u8; shared_secret_length];
+            seed_length = seed.len();
+            if EVP_PKEY_encapsulate_deterministic(
+                context.0,
+                ciphertext.as_mut_ptr(),
+                &mut ciphertext_length,
+                shared_secret.as_mut_ptr(),
+                &mut shared_secret_length,
+                seed.as_ptr(),
+                &mut seed_length,
+            ) == 1
+            {
+                Some((ciphertext, shared_secret))
+            } else {
+                shared_secret.zeroize();
+                None
+            }
+        }
+    };
+    seed.zeroize();
+
+    output.ok_or(PQKEMError::EncapsulationError)
+}
+
+pub fn decapsulate(
+    params: &KemParameters,
+    private_key: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, PQKEMError> {
+    let mut shared_secret = vec![0u8; SHARED_SECRET_LENGTH];
+
+    // SAFETY: every pointer is either checked for null or points to a live buffer of the given length
+    let decapsulated = unsafe {
+        // The private key and ciphertext lengths are checked by AWS-LC
+        let key = PKey(EVP_PKEY_kem_new_raw_secret_key(
+            params.nid,
+            private_key.as_ptr(),
+            private_key.len(),
+        ));
+        let context = PKeyContext(if key.0.is_null() {
+            null_mut()
+        } else {
+            EVP_PKEY_CTX_new(key.0, null_mut())
+        });
+
+        let mut shared_secret_length = shared_secret.len();
+        !context.0.is_null()
+            && EVP_PKEY_decapsulate(
+                context.0,
+                shared_secret.as_mut_ptr(),
+                &mut shared_secret_length,
+                ciphertext.as_ptr(),
+                ciphertext.len(),
+            ) == 1
+            && shared_secret_length == SHARED_SECRET_LENGTH
+    };
+
+    // A ciphertext that does not match the key yields the implicit rejection secret instead of an error,
+    // ML-KEM also rejects private keys whose embedded public key hash is wrong
+    if decapsulated {
+        Ok(shared_secret)
+    } else {
+        shared_secret.zeroize();
+        Err(PQKEMError::DecapsulationError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{CryptoRng, RngCore};
+    use sha2::{Digest, Sha256};
+    use sha3::Sha3_256;
+
+    use crate::errors::pqkem::PQKEMError;
+
+    use super::{
+        decapsulate, encapsulate, generate_key_pair, KemParameters, KYBER1024, KYBER512, KYBER768,
+        MLKEM1024, MLKEM512, MLKEM768,
+    };
+
+    // Deterministic generator of the Kyber reference test_vectors program, based on SURF (see https://cr.yp.to/papers.html#surf)
+    // It also records the generated bytes in hexadecimal, as printed by the reference program
+    struct SurfRng {
+        input: [u32; 12],
+        out: [u32; 8],
+        out_left: usize,
+        log: String,
+    }
+
+    const SURF_SEED: [u32; 32] = [
+        3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4, 6, 2, 6, 4, 3, 3, 8, 3, 2, 7,
+        9, 5,
+    ];
+
+    impl SurfRng {
+        fn new() -> Self {
+            SurfRng {
+                input: [0; 12],
+                out: [0; 8],
+                out_left: 0,
+                log: String::new(),
+            }
+        }
+
+        fn surf(&mut self) {
+            let mut t = [0u32; 12];
+            for (i, t_i) in t.iter_mut().enumerate() {
+                *t_i = self.input[i] ^ SURF_SEED[12 + i];
+            }
+            self.out.copy_from_slice(&SURF_SEED[24..]);
+
+            let mut x = t[11];
+            let mut sum = 0u32;
+            for _ in 0..2 {
+                for _ in 0..16 {
+                    sum = sum.wrapping_add(0x9e3779b9);
+                    for i in 0..12 {
+                        t[i] = t[i].wrapping_add(
+                            (x ^ SURF_SEED[i]).wrapping_add(sum)
+                                ^ x.rotate_left([5, 7, 9, 13][i % 4]),
+                        );
+                        x = t[i];
+                    }
+                }
+                for i in 0..8 {
+                    self.out[i] ^= t[i + 4];
+                }
+            }
+        }
+    }
+
+    impl RngCore for SurfRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest.iter_mut() {
+                if self.out_left == 0 {
+                    self.input[0] = self.input[0].wrapping_add(1);
+                    if self.input[0] == 0 {
+                        self.input[1] = self.input[1].wrapping_add(1);
+                    }
+                    self.surf();
+                    self.out_left = 8;
+                }
+                self.out_left -= 1;
+                *byte = self.out[self.out_left] as u8;
+                self.log.push_str(&format!("{:02x}", byte));
+            }
+            self.log.push('\n');
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for SurfRng {}
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // Runs the reference test_vectors program and returns the SHA-256 of its output
+    // The key generation seed d || z is drawn in one call where the reference draws d then z, which prints the
+    // same bytes on two lines, so the log of each key generation is split after d
+    fn test_vectors(params: &KemParameters, count: usize) -> String {
+        let mut rng = SurfRng::new();
+        let mut output = Sha256::new();
+        for _ in 0..count {
+            let (public_key, private_key) = generate_key_pair(params, &mut rng);
+            rng.log.insert(64, '\n');
+            output.update(&rng.log);
+            rng.log.clear();
+            output.update(format!("Public Key: {}\n", to_hex(&public_key)));
+            output.update(format!("Secret Key: {}\n", to_hex(&private_key)));
+
+            let (ciphertext, shared_secret) = encapsulate(params, &public_key, &mut rng).unwrap();
+            output.update(&rng.log);
+            rng.log.clear();
+            output.update(format!("Ciphertext: {}\n", to_hex(&ciphertext)));
+            output.update(format!("Shared Secret B: {}\n", to_hex(&shared_secret)));
+
+            let decapsulated_secret = decapsulate(params, &private_key, &ciphertext).unwrap();
+            output.update(format!(
+                "Shared Secret A: {}\n",
+                to_hex(&decapsulated_secret)
+            ));
+        }
+        to_hex(&output.finalize())
+    }
+
+    // SHA-256 of the 10000 test vectors printed by the round 3 reference implementation, as listed in its SHA256SUMS
+    // file (see https://github.com/pq-crystals/kyber)
+    #[test]
+    fn kyber512_reference_test_vectors() {
+        assert_eq!(
+            test_vectors(&KYBER512, 10000),
+            "6730bb552c22d9d2176ffb5568e48eb30952cf1f065073ec5f9724f6a3c6ea85"
+        );
+    }
+
+    #[test]
+    fn kyber768_reference_test_vectors() {
+        assert_eq!(
+            test_vectors(&KYBER768, 10000),
+            "667c8ca2ca93729c0df6ff24588460bad1bbdbfb64ece0fe8563852a7ff348c6"
+        );
+    }
+
+    #[test]
+    fn kyber1024_reference_test_vectors() {
+        assert_eq!(
+            test_vectors(&KYBER1024, 10000),
+            "ff1a854b9b6761a70c65ccae85246fe0596a949e72eae0866a8a2a2d4ea54b10"
+        );
+    }
+
+    #[test]
+    fn modified_ciphertext_is_implicitly_rejected() {
+        let mut rng = rand::thread_rng();
+        for params in [&KYBER512, &KYBER768, &KYBER1024] {
+            let (public_key, private_key) = generate_key_pair(params, &mut rng);
+            let (mut ciphertext, shared_secret) =
+                encapsulate(params, &public_key, &mut rng).unwrap();
+
+            *ciphertext.last_mut().unwrap() ^= 1;
+            let rejected_secret = decapsulate(params, &private_key, &ciphertext).unwrap();
+            assert_eq!(rejected_secret.len(), shared_secret.len());
+            assert_ne!(rejected_secret, shared_secret);
+        }
+    }
+
+    #[test]
+    fn wrong_lengths_are_rejected() {
+        let mut rng = rand::thread_rng();
+        let (public_key, private_key) = generate_key_pair(&KYBER768, &mut rng);
+        let (ciphertext, _) = encapsulate(&KYBER768, &public_key, &mut rng).unwrap();
+
+        assert!(encapsulate(&KYBER768, &public_key[1..], &mut rng).is_err());
+        assert!(encapsulate(&KYBER512, &public_key, &mut rng).is_err());
+        assert!(decapsulate(&KYBER768, &private_key[1..], &ciphertext).is_err());
+        assert!(decapsulate(&KYBER768, &private_key, &ciphertext[1..]).is_err());
+        assert!(decapsulate(&KYBER1024, &private_key, &ciphertext).is_err());
+    }
+
+    // Deterministic generator returning the given bytes in order
+    struct FixedRng(Vec<u8>);
+
+    impl RngCore for FixedRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            let rest = self.0.split_off(dest.len());
+            dest.copy_from_slice(&self.0);
+            self.0 = rest;
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for FixedRng {}
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // ML-KEM vector: the key generation seed d || z, the encapsulation randomness m, the SHA3-256 of the keys and of the
+    // ciphertext, the shared secret, and the implicit rejection secret of the ciphertext with its last bit flipped
+    struct MlKemVector {
+        params: &'static KemParameters,
+        seed: &'static str,
+        m: &'static str,
+        public_key: &'static str,
+        private_key: &'static str,
+        ciphertext: &'static str,
+        shared_secret: &'static str,
+        rejected_secret: &'static str,
+    }
+
+    // Computed with the FIPS 203 implementation of OpenSSL 3.5 (openssl genpkey -pkeyopt hexseed, then openssl pkeyutl
+    // -encap -pkeyopt hexikme and -decap)
+    const MLKEM_VECTORS: [MlKemVector; 6] = [
+        MlKemVector {
+            params: &MLKEM512,
+            seed: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+            m: "404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f",
+            public_key: "82f101ff648063b376e2bb6c5b7455f655a50c2feadade150efa0e0e6f365aea",
+            private_key: "0bd3f5df01098ac9c29d687c7f1bd0588a5573feeef8f1e3b4573fa7f6ab57c8",
+            ciphertext: "e3fdddb90255869185c07cdf1c1880b2efe08b6f04da4997b693c0dea61503bd",
+            shared_secret: "14cace3e48771b316676afad2cfcfe8488daaa4fad954e57236caa3f24a42cf7",
+            rejected_secret: "eed71bd178318ef2846b91a3fee1248840bd46f26a90ae1d82b919a6472443c9",
+        },
+        MlKemVector {
+            params: &MLKEM512,
+            seed: "a6aae2e27c31e807b446147f469816ce4c3081ecba19ea753ec4b69f91c7fd76684e5f255d306874d74da644e257da576f05832ac2fddd247aa9a4ae404b9be7",
+            m: "94b895caefc37ac9a8df8835cbf8bd474f77270694f91902e252e4b32217a718",
+            public_key: "c83147a1f1c39df8176881b4924a3e59d91c978354486dbeeefebbb55e85ed40",
+            private_key: "6c985e8ae572db1a7ff160e3630736aa39805fd6e0ad89e1b853e1a2aa55aeac",
+            ciphertext: "adc88e5f3e12ea0484363024f53105e8d1e94b96f2894f043fa5697deb07b362",
+            shared_secret: "ff6186c0dc9747c46680581af05f0657a0c264ba9f69aa5e26f1ee7cff0adc78",
+            rejected_secret: "a9f72674b6d7425b84957a13bbdadf9ddce4cc6801fe5b67016d88f20959d085",
+        },
+        MlKemVector {
+            params: &MLKEM768,
+            seed: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+            m: "404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f",
+            public_key: "a24e16d8f8f9383a95b77050f4d9fd2f5733eec1d63ef3c23ebf9918173669a7",
+            private_key: "1149f17c3c4ac6ab1e3e2d9d8bd0171355ac0fa31bb8855c48ceade874c0864b",
+            ciphertext: "b4cfbd24cef67afd3764276c6980e0f88f8e9ca57f59b7f12fe1a9c1e72f4710",
+            shared_secret: "9cddd089ffe70e3996e76f7c8d06746df34d07e8657bc0fcf2bb0e1c3084aea1",
+            rejected_secret: "1f39ae51991196b33dbc7c6031f9f35fd3347d577ebb4dea93028bcd9ab5dabe",
+        },
+        MlKemVector {
+            params: &MLKEM768,
+            seed: "b2b778ac6f8c565b77806aa11d4ff10def872754630b9c8aa2b7a67aff96c39c420d125fb56186b0172a9a3ac15fcbcca2aadc24ab0be2a0932f4395c6443496",
+            m: "d327dd02c2a85fa11961c1201581b20ee0c49533067e9ffb144298d1e80626dc",
+            public_key: "77be26779d3e96c3c78d8000fee370624f3c4b13537b5f794bd1f9b0b2358c9c",
+            private_key: "66b0a66f7ae6ce40559a120f6ce12faeb5de3f5c6438b2ed6f873d4afc5efc96",
+            ciphertext: "6326e29a568e1f0057d1ce01071b9cd3e186e804411694b402c0a6ff501b8502",
+            shared_secret: "9f93384ebed7cc64418ce4ca6c7708dde3d48146be794858e273da59939b3ccd",
+            rejected_secret: "11fa03f8f65e8518f8cb74705e92ff3acd16e826f232825cbe1fd2a3b8d1e651",
+        },
+        MlKemVector {
+            params: &MLKEM1024,
+            seed: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+            m: "404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f",
+            public_key: "61349e5c131a7e116a0463861d7d18663c5627c38c7147ddaadfd48acd7a4535",
+            private_key: "f0db5d938027fcd9bad87847d52c14cf0c4abcf0703b749793f212111ffb303b",
+            ciphertext: "c1579fa02c614f3762b2a799b51e41cebb8f820f34fa736af02c56de2460ce3c",
+            shared_secret: "0ad8d1ea1b8dd788979b4379581218df9321bdce5567eca42ae6be7d395f1a54",
+            rejected_secret: "9d20ec8bd82507657af2e7573571c146ea7c0c9281182f016c4774944172285a",
+        },
+        MlKemVector {
+            params: &MLKEM1024,
+            seed: "3ee6461d965ecabf7b0c36c7effc6ad006814c870554b3c2bc0434c408b13ae7c6c6d0707a9479d513faf0be9c59726a933e200620949d8a955f1e46a17120a6",
+            m: "19a128f4506041ff48db64ca0518ec163d936715aba30b55ecdc181703418616",
+            public_key: "399d726a80e25511fdd85e2e2e0d787ea798ea65e4fe0b71e80242df29a678b1",
+            private_key: "e06d2a996e375a9b21b860e4bce464380ab24c6122ade3295863fa8c7cc64264",
+            ciphertext: "d557f07e79a39c5767ebe372c2ef62dd7f1f537b98d704b813af4233d9855749",
+            shared_secret: "83a9876c44c9421674faf70f6506e27fee47bd92b332afe53872229af2fca646",
+            rejected_secret: "9238b4778b533fd7171e395509e150f95378804bb6daec4546b00271dba11260",
+        },
+    ];
+
+    #[test]
+    fn mlkem_test_vectors() {
+        for vector in MLKEM_VECTORS {
+            let params = vector.params;
+            let (public_key, private_key) =
+                generate_key_pair(params, &mut FixedRng(from_hex(vector.seed)));
+            assert_eq!(to_hex(&Sha3_256::digest(&public_key)), vector.public_key);
+            assert_eq!(to_hex(&Sha3_256::digest(&private_key)), vector.private_key);
+
+            let (ciphertext, shared_secret) =
+                encapsulate(params, &public_key, &mut FixedRng(from_hex(vector.m))).unwrap();
+            assert_eq!(to_hex(&Sha3_256::digest(&ciphertext)), vector.ciphertext);
+            assert_eq!(to_hex(&shared_secret), vector.shared_secret);
+            assert_eq!(
+                to_hex(&decapsulate(params, &private_key, &ciphertext).unwrap()),
+                vector.shared_secret
+            );
+
+            // A modified ciphertext yields the implicit rejection secret J(z || c) instead of an error
+            let mut modified_ciphertext = ciphertext;
+            *modified_ciphertext.last_mut().unwrap() ^= 1;
+            assert_eq!(
+                to_hex(&decapsulate(params, &private_key, &modified_ciphertext).unwrap()),
+                vector.rejected_secret
+            );
+        }
+    }
+
+    #[test]
+    fn mlkem_rejects_unreduced_public_key() {
+        let mut rng = rand::thread_rng();
+        for params in [&MLKEM512, &MLKEM768, &MLKEM1024] {
+            let (mut public_key, _) = generate_key_pair(params, &mut rng);
+            encapsulate(params, &public_key, &mut rng).unwrap();
+
+            // Encode q = 3329 as the first coefficient, which is only valid modulo q
+            public_key[0] = 0x01;
+            public_key[1] = (public_key[1] & 0xf0) | 0x0d;
+            assert!(matches!(
+                encapsulate(params, &public_key, &mut rng),
+                Err(PQKEMError::EncapsulationError)
+            ));
+        }
+    }
+
+    #[test]
+    fn mlkem_rejects_private_key_with_wrong_public_key_hash() {
+        let mut rng = rand::thread_rng();
+        let (public_key, mut private_key) = generate_key_pair(&MLKEM768, &mut rng);
+        let (ciphertext, _) = encapsulate(&MLKEM768, &public_key, &mut rng).unwrap();
+
+        // H(ek) sits right before z, at the end of the private key
+        let hash_position = private_key.len() - 64;
+        private_key[hash_position] ^= 1;
+        assert!(matches!(
+            decapsulate(&MLKEM768, &private_key, &ciphertext),
+            Err(PQKEMError::DecapsulationError)
+        ));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{crypto::secret::SecretBytes, errors::pqkem::PQKEMError};
+
+use super::{
+    aws_lc::{decapsulate, encapsulate, generate_key_pair, KYBER1024},
+    enum_pqkem_type::PQKEMType,
+    keys::{IdentifiedPQKEMKeyPair, PQKEMKeyPair, PQKEMPrivateKey, PQKEMPublicKey},
+    traits::PQKEMAlgorithm,
+};
+pub struct CrystalsKyber1024 {}
+
+impl PQKEMAlgorithm for CrystalsKyber1024 {
+    fn generate_key_pair<R: rand::RngCore + rand::CryptoRng>(&self, rng: &mut R) -> PQKEMKeyPair {
+        let (public_key, private_key) = generate_key_pair(&KYBER1024, rng);
+
+        PQKEMKeyPair {
+            key_type: PQKEMType::KYBER1024,
+            private_key: PQKEMPrivateKey {
+                key_type: PQKEMType::KYBER1024,
+                bytes: SecretBytes::new(private_key),
+            },
+            public_key: PQKEMPublicKey {
+                key_type: PQKEMType::KYBER1024,
+                bytes: public_key,
+            },
+        }
+    }
+
+    fn generate_identified_key_pair<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> IdentifiedPQKEMKeyPair {
+        IdentifiedPQKEMKeyPair::generate_from_key_pair(self.generate_key_pair(rng), rng)
+    }
+
+    fn encapsulate<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        public_key: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), PQKEMError> {
+        encapsulate(&KYBER1024, public_key, rng)
+    }
+
+    fn decapsulate(&self, private_key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, PQKEMError> {
+        decapsulate(&KYBER1024, private_key, ciphertext)
+    }
+
+    fn get_type(&self) -> PQKEMType {
+        PQKEMType::KYBER1024
+    }
+}
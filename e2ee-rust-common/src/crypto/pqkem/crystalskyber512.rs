@@ -1,8 +1,7 @@
-use pqc_kyber::{decapsulate, encapsulate, Keypair};
-
-use crate::errors::pqkem::PQKEMError;
+use crate::{crypto::secret::SecretBytes, errors::pqkem::PQKEMError};
 
 use super::{
+    aws_lc::{decapsulate, encapsulate, generate_key_pair, KYBER512},
     enum_pqkem_type::PQKEMType,
     keys::{IdentifiedPQKEMKeyPair, PQKEMKeyPair, PQKEMPrivateKey, PQKEMPublicKey},
     traits::PQKEMAlgorithm,
@@ -11,17 +10,17 @@ pub struct CrystalsKyber512 {}
 
 impl PQKEMAlgorithm for CrystalsKyber512 {
     fn generate_key_pair<R: rand::RngCore + rand::CryptoRng>(&self, rng: &mut R) -> PQKEMKeyPair {
-        let keypair = Keypair::generate(rng).unwrap();
+        let (public_key, private_key) = generate_key_pair(&KYBER512, rng);
 
         PQKEMKeyPair {
             key_type: PQKEMType::KYBER512,
             private_key: PQKEMPrivateKey {
                 key_type: PQKEMType::KYBER512,
-                bytes: keypair.secret.to_vec(),
+                bytes: SecretBytes::new(private_key),
             },
             public_key: PQKEMPublicKey {
                 key_type: PQKEMType::KYBER512,
-                bytes: keypair.public.to_vec(),
+                bytes: public_key,
             },
         }
     }
@@ -30,7 +29,7 @@ impl PQKEMAlgorithm for CrystalsKyber512 {
         &self,
         rng: &mut R,
     ) -> IdentifiedPQKEMKeyPair {
-        IdentifiedPQKEMKeyPair::generate_from_key_pair(&self.generate_key_pair(rng), rng)
+        IdentifiedPQKEMKeyPair::generate_from_key_pair(self.generate_key_pair(rng), rng)
     }
 
     fn encapsulate<R: rand::RngCore + rand::CryptoRng>(
@@ -38,14 +37,11 @@ impl PQKEMAlgorithm for CrystalsKyber512 {
         public_key: &[u8],
         rng: &mut R,
     ) -> Result<(Vec<u8>, Vec<u8>), PQKEMError> {
-        let (ct, ss) = encapsulate(public_key, rng).map_err(|_| PQKEMError::EncapsulationError)?;
-        Ok((ct.to_vec(), ss.to_vec()))
+        encapsulate(&KYBER512, public_key, rng)
     }
 
     fn decapsulate(&self, private_key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, PQKEMError> {
-        Ok(decapsulate(ciphertext, private_key)
-            .map_err(|_| PQKEMError::DecapsulationError)?
-            .to_vec())
+        decapsulate(&KYBER512, private_key, ciphertext)
     }
 
     fn get_type(&self) -> PQKEMType {
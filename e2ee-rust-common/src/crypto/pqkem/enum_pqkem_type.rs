@@ -1,12 +1,26 @@
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::protobuf::crypto::pqkem::PbpqkemType;
+use crate::{errors::pqkem::PQKEMError, protobuf::crypto::pqkem::PbpqkemType};
+
+use super::{
+    crystalskyber1024::CrystalsKyber1024,
+    crystalskyber512::CrystalsKyber512,
+    crystalskyber768::CrystalsKyber768,
+    keys::{IdentifiedPQKEMKeyPair, PQKEMKeyPair},
+    mlkem1024::MlKem1024,
+    mlkem512::MlKem512,
+    mlkem768::MlKem768,
+    traits::PQKEMAlgorithm,
+};
 
 #[derive(Zeroize, ZeroizeOnDrop, PartialEq, Debug, Clone)]
 pub enum PQKEMType {
     KYBER512,
     KYBER768,
     KYBER1024,
+    MLKEM512,
+    MLKEM768,
+    MLKEM1024,
 }
 
 impl PQKEMType {
@@ -15,6 +29,9 @@ impl PQKEMType {
             PQKEMType::KYBER512 => 0,
             PQKEMType::KYBER768 => 1,
             PQKEMType::KYBER1024 => 2,
+            PQKEMType::MLKEM512 => 3,
+            PQKEMType::MLKEM768 => 4,
+            PQKEMType::MLKEM1024 => 5,
         }
     }
 
@@ -23,6 +40,9 @@ impl PQKEMType {
             0 => Some(PQKEMType::KYBER512),
             1 => Some(PQKEMType::KYBER768),
             2 => Some(PQKEMType::KYBER1024),
+            3 => Some(PQKEMType::MLKEM512),
+            4 => Some(PQKEMType::MLKEM768),
+            5 => Some(PQKEMType::MLKEM1024),
             _ => None,
         }
     }
@@ -32,6 +52,9 @@ impl PQKEMType {
             PQKEMType::KYBER512 => 800,
             PQKEMType::KYBER768 => 1184,
             PQKEMType::KYBER1024 => 1568,
+            PQKEMType::MLKEM512 => 800,
+            PQKEMType::MLKEM768 => 1184,
+            PQKEMType::MLKEM1024 => 1568,
         }
     }
 
@@ -40,6 +63,20 @@ impl PQKEMType {
             PQKEMType::KYBER512 => 1632,
             PQKEMType::KYBER768 => 2400,
             PQKEMType::KYBER1024 => 3168,
+            PQKEMType::MLKEM512 => 1632,
+            PQKEMType::MLKEM768 => 2400,
+            PQKEMType::MLKEM1024 => 3168,
+        }
+    }
+
+    pub fn ciphertext_length(&self) -> usize {
+        match self {
+            PQKEMType::KYBER512 => 768,
+            PQKEMType::KYBER768 => 1088,
+            PQKEMType::KYBER1024 => 1568,
+            PQKEMType::MLKEM512 => 768,
+            PQKEMType::MLKEM768 => 1088,
+            PQKEMType::MLKEM1024 => 1568,
         }
     }
 
@@ -48,6 +85,9 @@ impl PQKEMType {
             PQKEMType::KYBER512 => "CRYSTALS-KYBER-512",
             PQKEMType::KYBER768 => "CRYSTALS-KYBER-768",
             PQKEMType::KYBER1024 => "CRYSTALS-KYBER-1024",
+            PQKEMType::MLKEM512 => "ML-KEM-512",
+            PQKEMType::MLKEM768 => "ML-KEM-768",
+            PQKEMType::MLKEM1024 => "ML-KEM-1024",
         }
     }
 
@@ -56,6 +96,60 @@ impl PQKEMType {
             PQKEMType::KYBER512 => PbpqkemType::Kyber512,
             PQKEMType::KYBER768 => PbpqkemType::Kyber768,
             PQKEMType::KYBER1024 => PbpqkemType::Kyber1024,
+            PQKEMType::MLKEM512 => PbpqkemType::Mlkem512,
+            PQKEMType::MLKEM768 => PbpqkemType::Mlkem768,
+            PQKEMType::MLKEM1024 => PbpqkemType::Mlkem1024,
         }
     }
 }
+
+// Resolves the PQKEM implementation at runtime, so that the PQKEM can be chosen from a value instead of a type parameter
+impl PQKEMAlgorithm for PQKEMType {
+    fn generate_key_pair<R: rand::RngCore + rand::CryptoRng>(&self, rng: &mut R) -> PQKEMKeyPair {
+        match self {
+            PQKEMType::KYBER512 => CrystalsKyber512 {}.generate_key_pair(rng),
+            PQKEMType::KYBER768 => CrystalsKyber768 {}.generate_key_pair(rng),
+            PQKEMType::KYBER1024 => CrystalsKyber1024 {}.generate_key_pair(rng),
+            PQKEMType::MLKEM512 => MlKem512 {}.generate_key_pair(rng),
+            PQKEMType::MLKEM768 => MlKem768 {}.generate_key_pair(rng),
+            PQKEMType::MLKEM1024 => MlKem1024 {}.generate_key_pair(rng),
+        }
+    }
+
+    fn generate_identified_key_pair<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> IdentifiedPQKEMKeyPair {
+        IdentifiedPQKEMKeyPair::generate_from_key_pair(self.generate_key_pair(rng), rng)
+    }
+
+    fn encapsulate<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        public_key: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), PQKEMError> {
+        match self {
+            PQKEMType::KYBER512 => CrystalsKyber512 {}.encapsulate(public_key, rng),
+            PQKEMType::KYBER768 => CrystalsKyber768 {}.encapsulate(public_key, rng),
+            PQKEMType::KYBER1024 => CrystalsKyber1024 {}.encapsulate(public_key, rng),
+            PQKEMType::MLKEM512 => MlKem512 {}.encapsulate(public_key, rng),
+            PQKEMType::MLKEM768 => MlKem768 {}.encapsulate(public_key, rng),
+            PQKEMType::MLKEM1024 => MlKem1024 {}.encapsulate(public_key, rng),
+        }
+    }
+
+    fn decapsulate(&self, private_key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, PQKEMError> {
+        match self {
+            PQKEMType::KYBER512 => CrystalsKyber512 {}.decapsulate(private_key, ciphertext),
+            PQKEMType::KYBER768 => CrystalsKyber768 {}.decapsulate(private_key, ciphertext),
+            PQKEMType::KYBER1024 => CrystalsKyber1024 {}.decapsulate(private_key, ciphertext),
+            PQKEMType::MLKEM512 => MlKem512 {}.decapsulate(private_key, ciphertext),
+            PQKEMType::MLKEM768 => MlKem768 {}.decapsulate(private_key, ciphertext),
+            PQKEMType::MLKEM1024 => MlKem1024 {}.decapsulate(private_key, ciphertext),
+        }
+    }
+
+    fn get_type(&self) -> PQKEMType {
+        self.clone()
+    }
+}
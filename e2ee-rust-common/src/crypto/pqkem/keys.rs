@@ -3,7 +3,7 @@ use uuid::Uuid;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{
-    crypto::traits::PrintableKey,
+    crypto::{secret::SecretBytes, traits::PrintableKey},
     errors::encoding::EncodingError,
     protobuf::crypto::pqkem::{PbIdentifiedPqkemPublicKey, PbpqkemPublicKey},
 };
@@ -16,21 +16,22 @@ pub struct PQKEMPublicKey {
     pub bytes: Vec<u8>,
 }
 
-#[derive(Zeroize, ZeroizeOnDrop, Debug, Clone)]
+#[derive(Zeroize, ZeroizeOnDrop, Debug, PartialEq)]
 pub struct PQKEMPrivateKey {
     pub key_type: PQKEMType,
-    pub bytes: Vec<u8>,
+    pub bytes: SecretBytes,
 }
 
-#[derive(Zeroize, ZeroizeOnDrop, Debug, Clone)]
+#[derive(Zeroize, ZeroizeOnDrop, Debug)]
 pub struct PQKEMKeyPair {
     pub key_type: PQKEMType,
     pub private_key: PQKEMPrivateKey,
     pub public_key: PQKEMPublicKey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Zeroize, ZeroizeOnDrop, Debug)]
 pub struct IdentifiedPQKEMKeyPair {
+    #[zeroize(skip)]
     pub id: Uuid,
     pub key_pair: PQKEMKeyPair,
 }
@@ -66,7 +67,7 @@ impl PQKEMKeyPair {
             return Err(EncodingError::InvalidKeyLength);
         }
         let private_key = PQKEMPrivateKey {
-            bytes: private_key_bytes,
+            bytes: SecretBytes::new(private_key_bytes),
             key_type: key_type.clone(),
         };
         Ok(Self {
@@ -79,13 +80,13 @@ impl PQKEMKeyPair {
 
 impl IdentifiedPQKEMKeyPair {
     pub fn generate_from_key_pair<R: rand::RngCore + rand::CryptoRng>(
-        key_pair: &PQKEMKeyPair,
+        key_pair: PQKEMKeyPair,
         rng: &mut R,
     ) -> Self {
         let uuid_bytes: [u8; 16] = rng.gen();
         Self {
             id: Uuid::from_bytes(uuid_bytes),
-            key_pair: key_pair.clone(),
+            key_pair,
         }
     }
 }
@@ -152,18 +153,6 @@ impl PQKEMPublicKey {
     }
 }
 
-impl PrintableKey for PQKEMPrivateKey {
-    fn print_key(&self) -> String {
-        format!(
-            "0x{}",
-            self.bytes
-                .iter()
-                .map(|b| format!("{:02x}", b))
-                .collect::<String>()
-        )
-    }
-}
-
 impl PrintableKey for PQKEMPublicKey {
     fn print_key(&self) -> String {
         format!(
@@ -0,0 +1,50 @@
+use crate::{crypto::secret::SecretBytes, errors::pqkem::PQKEMError};
+
+use super::{
+    aws_lc::{decapsulate, encapsulate, generate_key_pair, MLKEM512},
+    enum_pqkem_type::PQKEMType,
+    keys::{IdentifiedPQKEMKeyPair, PQKEMKeyPair, PQKEMPrivateKey, PQKEMPublicKey},
+    traits::PQKEMAlgorithm,
+};
+pub struct MlKem512 {}
+
+impl PQKEMAlgorithm for MlKem512 {
+    fn generate_key_pair<R: rand::RngCore + rand::CryptoRng>(&self, rng: &mut R) -> PQKEMKeyPair {
+        let (public_key, private_key) = generate_key_pair(&MLKEM512, rng);
+
+        PQKEMKeyPair {
+            key_type: PQKEMType::MLKEM512,
+            private_key: PQKEMPrivateKey {
+                key_type: PQKEMType::MLKEM512,
+                bytes: SecretBytes::new(private_key),
+            },
+            public_key: PQKEMPublicKey {
+                key_type: PQKEMType::MLKEM512,
+                bytes: public_key,
+            },
+        }
+    }
+
+    fn generate_identified_key_pair<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> IdentifiedPQKEMKeyPair {
+        IdentifiedPQKEMKeyPair::generate_from_key_pair(self.generate_key_pair(rng), rng)
+    }
+
+    fn encapsulate<R: rand::RngCore + rand::CryptoRng>(
+        &self,
+        public_key: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), PQKEMError> {
+        encapsulate(&MLKEM512, public_key, rng)
+    }
+
+    fn decapsulate(&self, private_key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, PQKEMError> {
+        decapsulate(&MLKEM512, private_key, ciphertext)
+    }
+
+    fn get_type(&self) -> PQKEMType {
+        PQKEMType::MLKEM512
+    }
+}
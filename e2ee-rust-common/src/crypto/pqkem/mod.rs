@@ -1,4 +1,10 @@
+mod aws_lc;
+pub mod crystalskyber1024;
 pub mod crystalskyber512;
+pub mod crystalskyber768;
 pub mod enum_pqkem_type;
 pub mod keys;
+pub mod mlkem1024;
+pub mod mlkem512;
+pub mod mlkem768;
 pub mod traits;
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::utils::secret_logging::print_secret;
+
+// Buffer holding secret key material
+// The bytes are zeroized on drop, redacted when debug-printed (see print_secret) and compared in constant time
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        SecretBytes(bytes)
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        SecretBytes(bytes.to_vec())
+    }
+
+    // Gives access to the secret bytes, which should only be handed to cryptographic primitives or the storage
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        SecretBytes(bytes)
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes({})", print_secret(&self.0))
+    }
+}
+
+// The comparison only leaks the lengths of the secrets, which are fixed by their key types
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for SecretBytes {}
+
+#[cfg(test)]
+mod tests {
+    use super::SecretBytes;
+
+    const SECRET: [u8; 8] = [0xde, 0xad, 0xbe, 0xef, 0x01, 0x23, 0x45, 0x67];
+
+    // The raw bytes are only printed when built with the insecure-debug-secrets feature
+    #[test]
+    #[cfg(not(feature = "insecure-debug-secrets"))]
+    fn debug_output_is_redacted() {
+        use crate::{
+            crypto::curve::{
+                enum_elliptic_curve_type::EllipticCurveType, keys::EllipticCurvePrivateKey,
+            },
+            utils::display::print_slice,
+        };
+
+        let secret = SecretBytes::from_slice(&SECRET);
+        let output = format!("{:?}", secret);
+        assert!(output.contains("redacted 8 bytes"));
+        assert!(!output.contains("deadbeef"));
+        assert!(!output.contains(&print_slice(&SECRET)));
+
+        let private_key = EllipticCurvePrivateKey {
+            key_type: EllipticCurveType::CURVE25519,
+            bytes: SecretBytes::from_slice(&SECRET),
+        };
+        let output = format!("{:?}", private_key);
+        assert!(output.contains("redacted 8 bytes"));
+        assert!(!output.contains("deadbeef"));
+    }
+
+    #[test]
+    fn comparison() {
+        let secret = SecretBytes::from_slice(&SECRET);
+        assert_eq!(secret, SecretBytes::from_slice(&SECRET));
+
+        let mut other = SECRET;
+        other[7] ^= 1;
+        assert_ne!(secret, SecretBytes::from_slice(&other));
+        assert_ne!(secret, SecretBytes::from_slice(&SECRET[..7]));
+        assert_ne!(secret, SecretBytes::new(vec![]));
+        assert_eq!(SecretBytes::new(vec![]), SecretBytes::new(vec![]));
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Debug)]
+pub enum ConfigError {
+    CannotReadFile(String),
+    InvalidFile(String),
+    UnknownOption(String),
+    MissingOptionValue(String),
+    InvalidValue(&'static str, String),
+}
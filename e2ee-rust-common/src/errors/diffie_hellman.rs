@@ -1,4 +1,8 @@
 #[derive(Debug)]
 pub enum DiffieHellmanError {
     WrongKeyType,
+    InvalidPrivateKeyLength(usize),
+    InvalidPublicKeyLength(usize),
+    LowOrderPublicKey,
+    NonContributoryOutput,
 }
@@ -0,0 +1,11 @@
+use uuid::Uuid;
+
+use super::protobuf::ProtobufError;
+
+#[derive(Debug)]
+pub enum FingerprintError {
+    UnknownPeer(Uuid),
+    InvalidScannableEncoding,
+    ScannableVersionMismatch(u32, u32),
+    Protobuf(ProtobufError),
+}
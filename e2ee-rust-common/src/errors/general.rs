@@ -1,8 +1,9 @@
 use crate::storage::errors::StorageInterfaceError;
 
 use super::{
-    aead::AEADError, diffie_hellman::DiffieHellmanError, encoding::EncodingError,
-    pqkem::PQKEMError, protobuf::ProtobufError, xeddsa::XedDSAError, zmq::ZMQError,
+    aead::AEADError, config::ConfigError, diffie_hellman::DiffieHellmanError,
+    encoding::EncodingError, fingerprint::FingerprintError, pqkem::PQKEMError, pqxdh::PQXDHError,
+    protobuf::ProtobufError, ratchet::RatchetError, xeddsa::XedDSAError, zmq::ZMQError,
 };
 
 #[derive(Debug)]
@@ -12,8 +13,12 @@ pub enum GeneralError {
     XedDSA(XedDSAError),
     AEAD(AEADError),
     PQKEM(PQKEMError),
+    PQXDH(PQXDHError),
+    Ratchet(RatchetError),
     Protobuf(ProtobufError),
     ZMQ(ZMQError),
+    Fingerprint(FingerprintError),
+    Config(ConfigError),
     ServerError,
     ClientError,
     StorageError(StorageInterfaceError),
@@ -27,8 +32,12 @@ impl std::fmt::Display for GeneralError {
             GeneralError::XedDSA(e) => write!(f, "XedDSA signature error: {:?}", e),
             GeneralError::AEAD(e) => write!(f, "AEAD error: {:?}", e),
             GeneralError::PQKEM(e) => write!(f, "PQKEM error: {:?}", e),
+            GeneralError::PQXDH(e) => write!(f, "PQXDH error: {:?}", e),
+            GeneralError::Ratchet(e) => write!(f, "Double Ratchet error: {:?}", e),
             GeneralError::Protobuf(e) => write!(f, "Protobuf error: {:?}", e),
             GeneralError::ZMQ(e) => write!(f, "ZMQ error: {:?}", e),
+            GeneralError::Fingerprint(e) => write!(f, "Fingerprint error: {:?}", e),
+            GeneralError::Config(e) => write!(f, "Configuration error: {:?}", e),
             GeneralError::ServerError => write!(f, "Server error"),
             GeneralError::ClientError => write!(f, "Client error"),
             GeneralError::StorageError(e) => write!(f, "Storage error: {:?}", e),
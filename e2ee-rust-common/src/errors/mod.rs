@@ -1,8 +1,15 @@
 pub mod aead;
+pub mod config;
 pub mod diffie_hellman;
 pub mod encoding;
+pub mod fingerprint;
 pub mod general;
+pub mod hash;
 pub mod pqkem;
+pub mod pqxdh;
+pub mod pqxdh_parameters;
+pub mod prekey_bundle;
 pub mod protobuf;
+pub mod ratchet;
 pub mod xeddsa;
 pub mod zmq;
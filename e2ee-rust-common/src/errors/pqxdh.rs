@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+use super::{
+    aead::AEADError, diffie_hellman::DiffieHellmanError, pqkem::PQKEMError,
+    pqxdh_parameters::PqxdhParametersError, prekey_bundle::PrekeyBundleError,
+};
+
+#[derive(Debug)]
+pub enum PQXDHError {
+    KeyTypeMismatch,
+    AEADTypeMismatch,
+    InvalidParameters(PqxdhParametersError),
+    UnknownSignedCurvePrekey(Uuid),
+    UnknownOneTimeCurvePrekey(Uuid),
+    UnknownPQKEMPrekey(Uuid),
+    DiffieHellman(DiffieHellmanError),
+    PrekeyBundle(PrekeyBundleError),
+    PQKEM(PQKEMError),
+    AEAD(AEADError),
+}
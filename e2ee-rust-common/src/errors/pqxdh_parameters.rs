@@ -0,0 +1,7 @@
+// Violations of the constraints put on the PQXDH parameters (see https://signal.org/docs/specifications/pqxdh/#pqxdh-parameters)
+#[derive(Debug, Clone, PartialEq)]
+pub enum PqxdhParametersError {
+    NonAsciiInfo,
+    InfoTooShort(usize),
+    UnsupportedHashOutputSize(usize),
+}
@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+use super::{diffie_hellman::DiffieHellmanError, xeddsa::XedDSAError};
+
+// Identifies the key of a prekey bundle that failed verification
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrekeyBundleKey {
+    IdentityKey,
+    SignedCurvePrekey(Uuid),
+    OneTimeCurvePrekey(Uuid),
+    PQKEMPrekey(Uuid),
+}
+
+#[derive(Debug)]
+pub enum PrekeyBundleError {
+    KeyTypeMismatch(PrekeyBundleKey),
+    InvalidSignature(PrekeyBundleKey),
+    InvalidPublicKey(PrekeyBundleKey, DiffieHellmanError),
+    XedDSA(PrekeyBundleKey, XedDSAError),
+}
@@ -0,0 +1,13 @@
+use super::{aead::AEADError, diffie_hellman::DiffieHellmanError, hash::HashError};
+
+#[derive(Debug)]
+pub enum RatchetError {
+    KeyTypeMismatch,
+    AEADTypeMismatch,
+    MissingSendingChain,
+    MissingReceivingChain,
+    TooManySkippedMessages,
+    DiffieHellman(DiffieHellmanError),
+    Hash(HashError),
+    AEAD(AEADError),
+}
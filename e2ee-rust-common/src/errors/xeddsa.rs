@@ -1,4 +1,7 @@
 #[derive(Debug)]
 pub enum XedDSAError {
     WrongKeyType,
+    InvalidSignatureLength,
+    InvalidPrivateKeyLength(usize),
+    InvalidPublicKeyLength(usize),
 }
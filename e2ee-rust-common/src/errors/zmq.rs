@@ -5,5 +5,13 @@ pub enum ZMQError {
     RecvError,
     SendError,
     MonitorError,
+    ProxyError,
     SetIdentityError,
+    CurveNotSupported,
+    InsecureTransportNotAllowed,
+    CurveSetupError,
+    CurveKeyFileError,
+    InvalidCurveKey,
+    CurveHandshakeFailed,
+    CurveHandshakeTimeout,
 }
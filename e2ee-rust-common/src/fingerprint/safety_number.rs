@@ -0,0 +1,260 @@
+use prost::Message;
+use sha2::{Digest, Sha512};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::{
+    crypto::curve::keys::EllipticCurvePublicKey,
+    errors::{fingerprint::FingerprintError, protobuf::ProtobufError},
+    protobuf::fingerprint::{PbCombinedFingerprints, PbFingerprintData},
+};
+
+// Number of hash iterations used to derive a fingerprint, which makes finding a colliding identity key expensive
+pub const FINGERPRINT_ITERATIONS: usize = 5200;
+
+// Version of the hashed data, prepended to the first iteration
+const FINGERPRINT_VERSION: u16 = 0;
+
+// Version of the scannable encoding
+pub const SCANNABLE_FINGERPRINT_VERSION: u32 = 1;
+
+// Each displayable fingerprint is made of 6 chunks of 5 digits, each one taken from 5 bytes of the hash
+const DISPLAYABLE_CHUNKS: usize = 6;
+const DISPLAYABLE_CHUNK_BYTES: usize = 5;
+
+// Number of hash bytes carried by the scannable encoding
+const SCANNABLE_FINGERPRINT_LENGTH: usize = 32;
+
+// Safety number of the conversation between two clients (see https://signal.org/blog/safety-number-updates/)
+// Both clients derive the same number from their identity keys and UUIDs, and can compare it out-of-band, either by reading it or by scanning it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetyNumber {
+    local_fingerprint: Vec<u8>,
+    remote_fingerprint: Vec<u8>,
+}
+
+impl SafetyNumber {
+    pub fn new(
+        local_uuid: &Uuid,
+        local_identity_key: &EllipticCurvePublicKey,
+        remote_uuid: &Uuid,
+        remote_identity_key: &EllipticCurvePublicKey,
+    ) -> Self {
+        SafetyNumber {
+            local_fingerprint: fingerprint(local_uuid, local_identity_key),
+            remote_fingerprint: fingerprint(remote_uuid, remote_identity_key),
+        }
+    }
+
+    // Returns the 60 digits of the safety number
+    // The lowest fingerprint comes first, so that both clients display the same number
+    pub fn displayable(&self) -> String {
+        let local = displayable_fingerprint(&self.local_fingerprint);
+        let remote = displayable_fingerprint(&self.remote_fingerprint);
+        if local <= remote {
+            local + &remote
+        } else {
+            remote + &local
+        }
+    }
+
+    // Encodes the fingerprints as an uppercase hexadecimal string, which fits the alphanumeric mode of QR codes
+    pub fn scannable(&self) -> String {
+        let combined = PbCombinedFingerprints {
+            version: SCANNABLE_FINGERPRINT_VERSION,
+            local_fingerprint: Some(PbFingerprintData {
+                content: self.local_fingerprint[..SCANNABLE_FINGERPRINT_LENGTH].to_vec(),
+            }),
+            remote_fingerprint: Some(PbFingerprintData {
+                content: self.remote_fingerprint[..SCANNABLE_FINGERPRINT_LENGTH].to_vec(),
+            }),
+        };
+
+        combined
+            .encode_to_vec()
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect()
+    }
+
+    // Compares the scannable string produced by the peer with this safety number
+    // The peer's local fingerprint must match our remote one and the other way around
+    pub fn compare_scannable(&self, scanned: &str) -> Result<bool, FingerprintError> {
+        let bytes = decode_hex(scanned.trim()).ok_or(FingerprintError::InvalidScannableEncoding)?;
+        let combined = PbCombinedFingerprints::decode(bytes.as_slice())
+            .map_err(|e| FingerprintError::Protobuf(ProtobufError::DecodeError(e)))?;
+
+        if combined.version != SCANNABLE_FINGERPRINT_VERSION {
+            return Err(FingerprintError::ScannableVersionMismatch(
+                combined.version,
+                SCANNABLE_FINGERPRINT_VERSION,
+            ));
+        }
+
+        let their_local = combined
+            .local_fingerprint
+            .ok_or(FingerprintError::Protobuf(ProtobufError::MissingField(
+                "combined_fingerprints.local_fingerprint",
+            )))?
+            .content;
+        let their_remote = combined
+            .remote_fingerprint
+            .ok_or(FingerprintError::Protobuf(ProtobufError::MissingField(
+                "combined_fingerprints.remote_fingerprint",
+            )))?
+            .content;
+
+        let local_matches =
+            their_remote.ct_eq(&self.local_fingerprint[..SCANNABLE_FINGERPRINT_LENGTH]);
+        let remote_matches =
+            their_local.ct_eq(&self.remote_fingerprint[..SCANNABLE_FINGERPRINT_LENGTH]);
+        Ok((local_matches & remote_matches).into())
+    }
+}
+
+// Iterated hash of the identity key and the client UUID
+// hash_0 = version || key || uuid, then hash_i+1 = SHA-512(hash_i || key)
+fn fingerprint(uuid: &Uuid, identity_key: &EllipticCurvePublicKey) -> Vec<u8> {
+    let encoded_key = identity_key.encode_ec();
+
+    let mut hash = FINGERPRINT_VERSION.to_be_bytes().to_vec();
+    hash.extend_from_slice(&encoded_key);
+    hash.extend_from_slice(uuid.as_bytes());
+
+    for _ in 0..FINGERPRINT_ITERATIONS {
+        hash = Sha512::new()
+            .chain_update(&hash)
+            .chain_update(&encoded_key)
+            .finalize()
+            .to_vec();
+    }
+
+    hash
+}
+
+// Turns every 5 bytes of the fingerprint into a 5 digits chunk
+fn displayable_fingerprint(fingerprint: &[u8]) -> String {
+    (0..DISPLAYABLE_CHUNKS)
+        .map(|i| {
+            let chunk =
+                &fingerprint[i * DISPLAYABLE_CHUNK_BYTES..(i + 1) * DISPLAYABLE_CHUNK_BYTES];
+            let value = chunk.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            format!("{:05}", value % 100000)
+        })
+        .collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        crypto::curve::{
+            curve25519::Curve25519, keys::EllipticCurvePublicKey, traits::EllipticCurveAlgorithm,
+        },
+        errors::fingerprint::FingerprintError,
+    };
+
+    use super::SafetyNumber;
+
+    struct Party {
+        uuid: Uuid,
+        identity_key: EllipticCurvePublicKey,
+    }
+
+    fn party() -> Party {
+        Party {
+            uuid: Uuid::new_v4(),
+            identity_key: Curve25519 {}
+                .generate_key_pair(&mut rand::thread_rng())
+                .public_key
+                .clone(),
+        }
+    }
+
+    // Safety number computed by the first party for its conversation with the second one
+    fn safety_number(local: &Party, remote: &Party) -> SafetyNumber {
+        SafetyNumber::new(
+            &local.uuid,
+            &local.identity_key,
+            &remote.uuid,
+            &remote.identity_key,
+        )
+    }
+
+    #[test]
+    fn both_parties_display_the_same_number() {
+        let alice = party();
+        let bob = party();
+        let alice_number = safety_number(&alice, &bob).displayable();
+        let bob_number = safety_number(&bob, &alice).displayable();
+
+        assert_eq!(alice_number.len(), 60);
+        assert!(alice_number.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(alice_number, bob_number);
+
+        // Another identity key for the peer gives another number
+        let mallory = Party {
+            uuid: bob.uuid,
+            ..party()
+        };
+        assert_ne!(alice_number, safety_number(&alice, &mallory).displayable());
+    }
+
+    #[test]
+    fn scanned_number_is_compared() {
+        let alice = party();
+        let bob = party();
+        let alice_number = safety_number(&alice, &bob);
+        let bob_number = safety_number(&bob, &alice);
+
+        assert!(alice_number
+            .compare_scannable(&bob_number.scannable())
+            .unwrap());
+        assert!(bob_number
+            .compare_scannable(&alice_number.scannable())
+            .unwrap());
+
+        // Our own string has the fingerprints swapped compared to the peer's
+        assert!(!alice_number
+            .compare_scannable(&alice_number.scannable())
+            .unwrap());
+
+        // A number computed with another identity key for the peer does not match
+        let mallory = Party {
+            uuid: bob.uuid,
+            ..party()
+        };
+        assert!(!alice_number
+            .compare_scannable(&safety_number(&mallory, &alice).scannable())
+            .unwrap());
+    }
+
+    #[test]
+    fn invalid_scannable_strings_are_rejected() {
+        let alice = party();
+        let bob = party();
+        let alice_number = safety_number(&alice, &bob);
+        let scannable = safety_number(&bob, &alice).scannable();
+
+        assert!(alice_number.compare_scannable("not hexadecimal").is_err());
+        assert!(alice_number.compare_scannable(&scannable[1..]).is_err());
+
+        // The first field is the version, 1
+        let other_version = format!("0802{}", &scannable[4..]);
+        assert!(matches!(
+            alice_number.compare_scannable(&other_version),
+            Err(FingerprintError::ScannableVersionMismatch(2, 1))
+        ));
+    }
+}
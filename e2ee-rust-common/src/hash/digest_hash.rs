@@ -0,0 +1,33 @@
+use std::marker::PhantomData;
+
+use hkdf::{
+    hmac::{digest::core_api::BlockSizeUser, Mac, SimpleHmac},
+    Hkdf,
+};
+use sha2::Digest;
+
+use crate::errors::hash::HashError;
+
+use super::traits::HashAlgorithm;
+
+// Hash algorithm backed by any RustCrypto digest
+// HMAC is instantiated with SimpleHmac, which only needs the digest API and thus also covers hashes without a block-level core such as BLAKE2
+pub struct DigestHash<D>(pub PhantomData<D>);
+
+impl<D: Digest + BlockSizeUser + Clone> HashAlgorithm for DigestHash<D> {
+    fn output_size(&self) -> usize {
+        <D as Digest>::output_size()
+    }
+
+    fn hkdf(&self, salt: &[u8], ikm: &[u8], info: &[u8], okm: &mut [u8]) -> Result<(), HashError> {
+        Hkdf::<D, SimpleHmac<D>>::new(Some(salt), ikm)
+            .expand(info, okm)
+            .map_err(|_| HashError::InvalidOutputLength)
+    }
+
+    fn hmac(&self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(key).unwrap();
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+}
@@ -1,20 +1,39 @@
+use std::marker::PhantomData;
+
+use super::{digest_hash::DigestHash, traits::HashAlgorithm};
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum HashType {
     SHA256,
     SHA512,
+    SHA3_256,
+    SHA3_512,
+    BLAKE2B512,
 }
 
 impl HashType {
-    pub fn get_output_size(&self) -> usize {
+    // Resolves the implementation of the hash function, which is the only place where a new hash has to be wired in
+    pub fn algorithm(&self) -> &'static dyn HashAlgorithm {
         match self {
-            Self::SHA256 => 32,
-            Self::SHA512 => 64,
+            Self::SHA256 => &DigestHash::<sha2::Sha256>(PhantomData),
+            Self::SHA512 => &DigestHash::<sha2::Sha512>(PhantomData),
+            Self::SHA3_256 => &DigestHash::<sha3::Sha3_256>(PhantomData),
+            Self::SHA3_512 => &DigestHash::<sha3::Sha3_512>(PhantomData),
+            Self::BLAKE2B512 => &DigestHash::<blake2::Blake2b512>(PhantomData),
         }
     }
 
+    pub fn get_output_size(&self) -> usize {
+        self.algorithm().output_size()
+    }
+
     pub fn to_str(&self) -> &str {
         match self {
             Self::SHA256 => "SHA-256",
             Self::SHA512 => "SHA-512",
+            Self::SHA3_256 => "SHA3-256",
+            Self::SHA3_512 => "SHA3-512",
+            Self::BLAKE2B512 => "BLAKE2b-512",
         }
     }
 }
@@ -0,0 +1,19 @@
+use crate::errors::hash::HashError;
+
+use super::enum_hash_types::HashType;
+
+// Derives output key material of the length of okm using HKDF (see https://www.rfc-editor.org/rfc/rfc5869)
+pub fn hkdf(
+    hash_type: &HashType,
+    salt: &[u8],
+    ikm: &[u8],
+    info: &[u8],
+    okm: &mut [u8],
+) -> Result<(), HashError> {
+    hash_type.algorithm().hkdf(salt, ikm, info, okm)
+}
+
+// Computes the HMAC of the data with the given key
+pub fn hmac(hash_type: &HashType, key: &[u8], data: &[u8]) -> Vec<u8> {
+    hash_type.algorithm().hmac(key, data)
+}
@@ -1 +1,4 @@
+mod digest_hash;
 pub mod enum_hash_types;
+pub mod hkdf;
+pub mod traits;
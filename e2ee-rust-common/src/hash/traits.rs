@@ -0,0 +1,12 @@
+use crate::errors::hash::HashError;
+
+pub trait HashAlgorithm {
+    // Get the size of the hash output, in bytes
+    fn output_size(&self) -> usize;
+
+    // Derives output key material of the length of okm using HKDF (see https://www.rfc-editor.org/rfc/rfc5869)
+    fn hkdf(&self, salt: &[u8], ikm: &[u8], info: &[u8], okm: &mut [u8]) -> Result<(), HashError>;
+
+    // Computes the HMAC of the data with the given key
+    fn hmac(&self, key: &[u8], data: &[u8]) -> Vec<u8>;
+}
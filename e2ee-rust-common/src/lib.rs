@@ -1,8 +1,10 @@
 pub mod crypto;
 pub mod errors;
+pub mod fingerprint;
 pub mod hash;
 pub mod messages;
 pub mod pqxdh;
 pub mod protobuf;
+pub mod ratchet;
 pub mod storage;
 pub mod utils;
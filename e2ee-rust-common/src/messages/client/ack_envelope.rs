@@ -0,0 +1,26 @@
+use uuid::Uuid;
+
+use crate::{
+    errors::protobuf::ProtobufError,
+    protobuf::{client::PbClientAckEnvelope, utils::uuid_from_bytes},
+};
+
+pub struct AckEnvelope {
+    pub envelope_id: Uuid,
+}
+
+impl AckEnvelope {
+    pub fn to_protobuf(&self) -> PbClientAckEnvelope {
+        PbClientAckEnvelope {
+            envelope_uuid: self.envelope_id.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn from_protobuf(
+        pb_client_ack_envelope: &PbClientAckEnvelope,
+    ) -> Result<Self, ProtobufError> {
+        Ok(Self {
+            envelope_id: uuid_from_bytes(&pb_client_ack_envelope.envelope_uuid)?,
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{errors::protobuf::ProtobufError, protobuf::client::PbClientAuthenticationResponse};
+
+// Signature of the server authentication challenge under the client identity key
+pub struct AuthenticationResponse {
+    pub signature: Vec<u8>,
+}
+
+impl AuthenticationResponse {
+    pub fn to_protobuf(&self) -> PbClientAuthenticationResponse {
+        PbClientAuthenticationResponse {
+            signature: self.signature.clone(),
+        }
+    }
+
+    pub fn from_protobuf(
+        pb_client_authentication_response: &PbClientAuthenticationResponse,
+    ) -> Result<Self, ProtobufError> {
+        Ok(Self {
+            signature: pb_client_authentication_response.signature.clone(),
+        })
+    }
+}
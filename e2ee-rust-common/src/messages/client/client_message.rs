@@ -4,9 +4,13 @@ use crate::pqxdh::registration_bundle::RegistrationBundle;
 
 use crate::protobuf::client::{pb_client_message, PbClientMessage};
 
+use super::ack_envelope::AckEnvelope;
+use super::authentication_response::AuthenticationResponse;
 use super::client_hello::ClientHello;
 use super::new_keys::NewKeys;
+use super::post_envelope::PostEnvelope;
 use super::request_peer_bundle::RequestPeerBundle;
+use super::send_first_message::SendFirstMessage;
 
 pub struct ClientMessage {
     pub message_type: ClientMessageType,
@@ -15,6 +19,12 @@ pub struct ClientMessage {
     pub registration_bundle: Option<RegistrationBundle>,
     pub new_keys: Option<NewKeys>,
     pub request_peer_bundle: Option<RequestPeerBundle>,
+    pub send_first_message: Option<SendFirstMessage>,
+    pub post_envelope: Option<PostEnvelope>,
+    pub ack_envelope: Option<AckEnvelope>,
+    pub authentication_response: Option<AuthenticationResponse>,
+    // Token of the session opened by the last authentication challenge, empty before the first one
+    pub session_token: Vec<u8>,
 }
 
 impl ClientMessage {
@@ -26,6 +36,11 @@ impl ClientMessage {
             registration_bundle: None,
             new_keys: None,
             request_peer_bundle: None,
+            send_first_message: None,
+            post_envelope: None,
+            ack_envelope: None,
+            authentication_response: None,
+            session_token: Vec::new(),
         }
     }
 
@@ -49,7 +64,24 @@ impl ClientMessage {
                         self.request_peer_bundle.as_ref().unwrap().to_protobuf(),
                     ))
                 }
+                ClientMessageType::SendFirstMessage => {
+                    Some(pb_client_message::Message::SendFirstMessage(
+                        self.send_first_message.as_ref().unwrap().to_protobuf(),
+                    ))
+                }
+                ClientMessageType::PostEnvelope => Some(pb_client_message::Message::PostEnvelope(
+                    self.post_envelope.as_ref().unwrap().to_protobuf(),
+                )),
+                ClientMessageType::AckEnvelope => Some(pb_client_message::Message::AckEnvelope(
+                    self.ack_envelope.as_ref().unwrap().to_protobuf(),
+                )),
+                ClientMessageType::AuthenticationResponse => {
+                    Some(pb_client_message::Message::AuthenticationResponse(
+                        self.authentication_response.as_ref().unwrap().to_protobuf(),
+                    ))
+                }
             },
+            session_token: self.session_token.clone(),
         }
     }
 }
@@ -59,4 +91,8 @@ pub enum ClientMessageType {
     RegistrationBundle,
     NewKeys,
     RequestPeerBundle,
+    SendFirstMessage,
+    PostEnvelope,
+    AckEnvelope,
+    AuthenticationResponse,
 }
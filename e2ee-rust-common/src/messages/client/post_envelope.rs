@@ -0,0 +1,29 @@
+use uuid::Uuid;
+
+use crate::{
+    errors::protobuf::ProtobufError,
+    protobuf::{client::PbClientPostEnvelope, utils::uuid_from_str},
+};
+
+pub struct PostEnvelope {
+    pub recipient_uuid: Uuid,
+    pub payload: Vec<u8>,
+}
+
+impl PostEnvelope {
+    pub fn to_protobuf(&self) -> PbClientPostEnvelope {
+        PbClientPostEnvelope {
+            recipient_uuid: self.recipient_uuid.to_string(),
+            payload: self.payload.clone(),
+        }
+    }
+
+    pub fn from_protobuf(
+        pb_client_post_envelope: PbClientPostEnvelope,
+    ) -> Result<Self, ProtobufError> {
+        Ok(Self {
+            recipient_uuid: uuid_from_str(&pb_client_post_envelope.recipient_uuid)?,
+            payload: pb_client_post_envelope.payload,
+        })
+    }
+}
@@ -0,0 +1,34 @@
+use uuid::Uuid;
+
+use crate::{
+    errors::protobuf::ProtobufError,
+    pqxdh::first_message::FirstMessage,
+    protobuf::{client::PbClientSendFirstMessage, utils::uuid_from_str},
+};
+
+pub struct SendFirstMessage {
+    pub peer_uuid: Uuid,
+    pub first_message: FirstMessage,
+}
+
+impl SendFirstMessage {
+    pub fn to_protobuf(&self) -> PbClientSendFirstMessage {
+        PbClientSendFirstMessage {
+            peer_uuid: self.peer_uuid.to_string(),
+            first_message: Some(self.first_message.to_protobuf()),
+        }
+    }
+
+    pub fn from_protobuf(
+        pb_client_send_first_message: PbClientSendFirstMessage,
+    ) -> Result<Self, ProtobufError> {
+        Ok(Self {
+            peer_uuid: uuid_from_str(&pb_client_send_first_message.peer_uuid)?,
+            first_message: FirstMessage::from_protobuf(
+                pb_client_send_first_message
+                    .first_message
+                    .ok_or(ProtobufError::MissingField("first_message"))?,
+            )?,
+        })
+    }
+}
@@ -1,2 +1,4 @@
+pub mod server_authentication_challenge;
+pub mod server_envelope;
 pub mod server_message;
 pub mod server_peer_bundle;
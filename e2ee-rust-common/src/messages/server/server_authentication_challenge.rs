@@ -0,0 +1,75 @@
+use rand::{CryptoRng, RngCore};
+use uuid::Uuid;
+
+use crate::{errors::protobuf::ProtobufError, protobuf::server::PbServerAuthenticationChallenge};
+
+pub const AUTHENTICATION_NONCE_LENGTH: usize = 32;
+pub const SESSION_TOKEN_LENGTH: usize = 32;
+
+// Domain separation prefix, so that an answer to a challenge can never be mistaken for a prekey signature
+const AUTHENTICATION_CONTEXT: &[u8] = b"E2EE-RUST-CLIENT-AUTHENTICATION";
+
+// A fresh nonce that the client has to sign with its identity key before the server runs privileged requests
+// The session token names the session opened by the signature, the client sends it with every request
+// It is only sent to the connection that said hello, so another connection cannot use the session, even with the same ZMQ identity
+#[derive(Clone, Debug)]
+pub struct ServerAuthenticationChallenge {
+    pub nonce: Vec<u8>,
+    pub session_token: Vec<u8>,
+}
+
+impl ServerAuthenticationChallenge {
+    pub fn new<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut nonce = vec![0u8; AUTHENTICATION_NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+        let mut session_token = vec![0u8; SESSION_TOKEN_LENGTH];
+        rng.fill_bytes(&mut session_token);
+        Self {
+            nonce,
+            session_token,
+        }
+    }
+
+    // Message signed by the client: context || client UUID || nonce
+    // Binding the UUID prevents a signature from being replayed to authenticate another client
+    pub fn signed_message(&self, client_id: Uuid) -> Vec<u8> {
+        let mut message = AUTHENTICATION_CONTEXT.to_vec();
+        message.extend_from_slice(client_id.as_bytes());
+        message.extend_from_slice(&self.nonce);
+        message
+    }
+
+    pub fn to_protobuf(&self) -> PbServerAuthenticationChallenge {
+        PbServerAuthenticationChallenge {
+            nonce: self.nonce.clone(),
+            session_token: self.session_token.clone(),
+        }
+    }
+
+    pub fn from_protobuf(
+        pb_server_authentication_challenge: &PbServerAuthenticationChallenge,
+    ) -> Result<Self, ProtobufError> {
+        let nonce = &pb_server_authentication_challenge.nonce;
+        if nonce.len() != AUTHENTICATION_NONCE_LENGTH {
+            return Err(ProtobufError::InvalidFieldLength(
+                "nonce",
+                nonce.len(),
+                AUTHENTICATION_NONCE_LENGTH,
+            ));
+        }
+
+        let session_token = &pb_server_authentication_challenge.session_token;
+        if session_token.len() != SESSION_TOKEN_LENGTH {
+            return Err(ProtobufError::InvalidFieldLength(
+                "session_token",
+                session_token.len(),
+                SESSION_TOKEN_LENGTH,
+            ));
+        }
+
+        Ok(Self {
+            nonce: nonce.clone(),
+            session_token: session_token.clone(),
+        })
+    }
+}
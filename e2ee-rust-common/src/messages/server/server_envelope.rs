@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    errors::protobuf::ProtobufError,
+    protobuf::{
+        server::{PbServerEnvelope, PbServerEnvelopeType},
+        utils::{uuid_from_bytes, uuid_from_str},
+    },
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerEnvelopeType {
+    FirstMessage,
+    Message,
+}
+
+impl ServerEnvelopeType {
+    pub fn id(&self) -> u8 {
+        match self {
+            ServerEnvelopeType::FirstMessage => 0,
+            ServerEnvelopeType::Message => 1,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<ServerEnvelopeType> {
+        match id {
+            0 => Some(ServerEnvelopeType::FirstMessage),
+            1 => Some(ServerEnvelopeType::Message),
+            _ => None,
+        }
+    }
+
+    pub fn to_protobuf(&self) -> PbServerEnvelopeType {
+        match self {
+            ServerEnvelopeType::FirstMessage => PbServerEnvelopeType::FirstMessage,
+            ServerEnvelopeType::Message => PbServerEnvelopeType::Message,
+        }
+    }
+}
+
+// An opaque payload queued by the server until the recipient fetches it
+#[derive(Clone, Debug)]
+pub struct ServerEnvelope {
+    pub id: Uuid,
+    pub sender_uuid: Uuid,
+    pub envelope_type: ServerEnvelopeType,
+    pub payload: Vec<u8>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ServerEnvelope {
+    pub fn to_protobuf(&self) -> PbServerEnvelope {
+        PbServerEnvelope {
+            uuid: self.id.as_bytes().to_vec(),
+            sender_uuid: self.sender_uuid.to_string(),
+            envelope_type: self.envelope_type.to_protobuf().into(),
+            payload: self.payload.clone(),
+            timestamp: self.timestamp.timestamp_millis(),
+        }
+    }
+
+    pub fn from_protobuf(pb_server_envelope: &PbServerEnvelope) -> Result<Self, ProtobufError> {
+        Ok(Self {
+            id: uuid_from_bytes(&pb_server_envelope.uuid)?,
+            sender_uuid: uuid_from_str(&pb_server_envelope.sender_uuid)?,
+            envelope_type: ServerEnvelopeType::from_id(pb_server_envelope.envelope_type as u8)
+                .ok_or(ProtobufError::InvalidField("envelope_type"))?,
+            payload: pb_server_envelope.payload.clone(),
+            timestamp: DateTime::from_timestamp_millis(pb_server_envelope.timestamp)
+                .ok_or(ProtobufError::InvalidField("timestamp"))?,
+        })
+    }
+}
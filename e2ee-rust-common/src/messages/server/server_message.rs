@@ -1,6 +1,9 @@
 use crate::{
     errors::protobuf::ProtobufError,
-    messages::server::server_peer_bundle::ServerPeerBundle,
+    messages::server::{
+        server_authentication_challenge::ServerAuthenticationChallenge,
+        server_envelope::ServerEnvelope, server_peer_bundle::ServerPeerBundle,
+    },
     protobuf::server::{
         pb_server_message, pb_server_message_data::Data, PbServerCommand, PbServerError,
         PbServerMessage, PbServerMessageData,
@@ -22,6 +25,10 @@ pub enum ServerError {
     ClientAlreadyRegistered,
     ClientNotRegistered,
     BadResponse,
+    InvalidSignature,
+    InvalidPublicKey,
+    NotAuthenticated,
+    AuthenticationFailed,
 }
 
 impl Into<PbServerError> for &ServerError {
@@ -32,6 +39,10 @@ impl Into<PbServerError> for &ServerError {
             ServerError::ClientAlreadyRegistered => PbServerError::ClientAlreadyRegistered,
             ServerError::ClientNotRegistered => PbServerError::ClientNotRegistered,
             ServerError::BadResponse => PbServerError::BadResponse,
+            ServerError::InvalidSignature => PbServerError::InvalidSignature,
+            ServerError::InvalidPublicKey => PbServerError::InvalidPublicKey,
+            ServerError::NotAuthenticated => PbServerError::NotAuthenticated,
+            ServerError::AuthenticationFailed => PbServerError::AuthenticationFailed,
         }
     }
 }
@@ -44,6 +55,10 @@ impl ServerError {
             PbServerError::ClientAlreadyRegistered => ServerError::ClientAlreadyRegistered,
             PbServerError::ClientNotRegistered => ServerError::ClientNotRegistered,
             PbServerError::BadResponse => ServerError::BadResponse,
+            PbServerError::InvalidSignature => ServerError::InvalidSignature,
+            PbServerError::InvalidPublicKey => ServerError::InvalidPublicKey,
+            PbServerError::NotAuthenticated => ServerError::NotAuthenticated,
+            PbServerError::AuthenticationFailed => ServerError::AuthenticationFailed,
         }
     }
 }
@@ -88,12 +103,16 @@ impl ServerCommand {
 #[derive(Debug, Clone)]
 pub enum ServerDataType {
     PeerBundle,
+    Envelope,
+    AuthenticationChallenge,
 }
 
 #[derive(Debug, Clone)]
 pub struct ServerMessageData {
     pub data_type: ServerDataType,
     pub peer_bundle: Option<ServerPeerBundle>,
+    pub envelope: Option<ServerEnvelope>,
+    pub authentication_challenge: Option<ServerAuthenticationChallenge>,
 }
 
 impl ServerMessageData {
@@ -104,6 +123,22 @@ impl ServerMessageData {
             Data::PeerBundle(pb_server_peer_bundle) => Ok(Self {
                 data_type: ServerDataType::PeerBundle,
                 peer_bundle: Some(ServerPeerBundle::from_protobuf(&pb_server_peer_bundle)?),
+                envelope: None,
+                authentication_challenge: None,
+            }),
+            Data::Envelope(pb_server_envelope) => Ok(Self {
+                data_type: ServerDataType::Envelope,
+                peer_bundle: None,
+                envelope: Some(ServerEnvelope::from_protobuf(&pb_server_envelope)?),
+                authentication_challenge: None,
+            }),
+            Data::AuthenticationChallenge(pb_server_authentication_challenge) => Ok(Self {
+                data_type: ServerDataType::AuthenticationChallenge,
+                peer_bundle: None,
+                envelope: None,
+                authentication_challenge: Some(ServerAuthenticationChallenge::from_protobuf(
+                    &pb_server_authentication_challenge,
+                )?),
             }),
         }
     }
@@ -113,6 +148,15 @@ impl ServerMessageData {
             ServerDataType::PeerBundle => {
                 Data::PeerBundle(self.peer_bundle.as_ref().unwrap().to_protobuf())
             }
+            ServerDataType::Envelope => {
+                Data::Envelope(self.envelope.as_ref().unwrap().to_protobuf())
+            }
+            ServerDataType::AuthenticationChallenge => Data::AuthenticationChallenge(
+                self.authentication_challenge
+                    .as_ref()
+                    .unwrap()
+                    .to_protobuf(),
+            ),
         };
 
         PbServerMessageData { data: Some(data) }
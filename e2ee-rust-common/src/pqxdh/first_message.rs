@@ -2,14 +2,24 @@ use log::debug;
 use uuid::Uuid;
 
 use crate::{
-    crypto::{aead::enum_aead_types::AEADType, curve::keys::EllipticCurvePublicKey},
+    crypto::{
+        aead::enum_aead_types::AEADType,
+        curve::{enum_elliptic_curve_type::EllipticCurveType, keys::EllipticCurvePublicKey},
+        pqkem::enum_pqkem_type::PQKEMType,
+    },
+    errors::protobuf::ProtobufError,
+    protobuf::{
+        crypto::curve::PbEllipticCurvePublicKey, pqxdh::PbFirstMessage, utils::uuid_from_bytes,
+    },
     utils::display::print_slice,
 };
 
 pub struct FirstMessage {
     pub peer_identity_key: EllipticCurvePublicKey,
     pub peer_ephemeral_key: EllipticCurvePublicKey,
+    pub pqkem_type: PQKEMType,
     pub pqkem_ciphertext: Vec<u8>,
+    pub used_signed_curve_prekey_id: Uuid,
     pub used_curve_prekey_id: Option<Uuid>,
     pub used_pqkem_prekey_id: Uuid,
     pub ciphertext: Vec<u8>,
@@ -24,9 +34,118 @@ impl FirstMessage {
             "peer_ephemeral_key: {}",
             self.peer_ephemeral_key.print_key()
         );
+        debug!("pqkem_type: {}", self.pqkem_type.to_str());
         debug!("pqkem_ciphertext: {}", print_slice(&self.pqkem_ciphertext));
+        debug!(
+            "used_signed_curve_prekey_id: {:?}",
+            self.used_signed_curve_prekey_id
+        );
         debug!("used_curve_prekey_id: {:?}", self.used_curve_prekey_id);
         debug!("used_pqkem_prekey_id: {:?}", self.used_pqkem_prekey_id);
         debug!("ciphertext: {}", print_slice(&self.ciphertext));
     }
+
+    pub fn to_protobuf(&self) -> PbFirstMessage {
+        PbFirstMessage {
+            identity_key: Some(PbEllipticCurvePublicKey {
+                key_type: self.peer_identity_key.key_type.to_protobuf().into(),
+                key_bytes: self.peer_identity_key.bytes.clone(),
+            }),
+            ephemeral_key: Some(PbEllipticCurvePublicKey {
+                key_type: self.peer_ephemeral_key.key_type.to_protobuf().into(),
+                key_bytes: self.peer_ephemeral_key.bytes.clone(),
+            }),
+            pqkem_type: self.pqkem_type.to_protobuf().into(),
+            pqkem_ciphertext: self.pqkem_ciphertext.clone(),
+            used_signed_curve_prekey_id: self.used_signed_curve_prekey_id.as_bytes().to_vec(),
+            used_curve_prekey_id: self.used_curve_prekey_id.map(|id| id.as_bytes().to_vec()),
+            used_pqkem_prekey_id: self.used_pqkem_prekey_id.as_bytes().to_vec(),
+            encryption_type: self.encryption_type.to_protobuf().into(),
+            encryption_nonce: self.encryption_nonce.clone(),
+            ciphertext: self.ciphertext.clone(),
+        }
+    }
+
+    pub fn from_protobuf(pb_first_message: PbFirstMessage) -> Result<Self, ProtobufError> {
+        // Identity Key
+        let pb_identity_key = pb_first_message
+            .identity_key
+            .ok_or(ProtobufError::MissingField("identity_key"))?;
+        let identity_key_type = EllipticCurveType::from_id(pb_identity_key.key_type as u8)
+            .ok_or(ProtobufError::InvalidField("identity_key.key_type"))?;
+        let identity_key_bytes = pb_identity_key.key_bytes;
+        if identity_key_bytes.len() != identity_key_type.public_key_length() {
+            return Err(ProtobufError::InvalidFieldLength(
+                "identity_key.key_bytes",
+                identity_key_bytes.len(),
+                identity_key_type.public_key_length(),
+            ));
+        }
+
+        // Ephemeral Key
+        let pb_ephemeral_key = pb_first_message
+            .ephemeral_key
+            .ok_or(ProtobufError::MissingField("ephemeral_key"))?;
+        let ephemeral_key_type = EllipticCurveType::from_id(pb_ephemeral_key.key_type as u8)
+            .ok_or(ProtobufError::InvalidField("ephemeral_key.key_type"))?;
+        let ephemeral_key_bytes = pb_ephemeral_key.key_bytes;
+        if ephemeral_key_bytes.len() != ephemeral_key_type.public_key_length() {
+            return Err(ProtobufError::InvalidFieldLength(
+                "ephemeral_key.key_bytes",
+                ephemeral_key_bytes.len(),
+                ephemeral_key_type.public_key_length(),
+            ));
+        }
+
+        // PQKEM Type and Ciphertext
+        let pqkem_type = PQKEMType::from_id(pb_first_message.pqkem_type as u8)
+            .ok_or(ProtobufError::InvalidField("pqkem_type"))?;
+        if pb_first_message.pqkem_ciphertext.len() != pqkem_type.ciphertext_length() {
+            return Err(ProtobufError::InvalidFieldLength(
+                "pqkem_ciphertext",
+                pb_first_message.pqkem_ciphertext.len(),
+                pqkem_type.ciphertext_length(),
+            ));
+        }
+
+        // Used Prekey IDs
+        let used_signed_curve_prekey_id =
+            uuid_from_bytes(&pb_first_message.used_signed_curve_prekey_id)?;
+        let used_curve_prekey_id = pb_first_message
+            .used_curve_prekey_id
+            .map(|id| uuid_from_bytes(&id))
+            .transpose()?;
+        let used_pqkem_prekey_id = uuid_from_bytes(&pb_first_message.used_pqkem_prekey_id)?;
+
+        // Encryption Type and Nonce
+        let encryption_type = AEADType::from_id(pb_first_message.encryption_type as u8)
+            .ok_or(ProtobufError::InvalidField("encryption_type"))?;
+        let encryption_nonce = pb_first_message.encryption_nonce;
+        if encryption_nonce.len() != encryption_type.nonce_length() {
+            return Err(ProtobufError::InvalidFieldLength(
+                "encryption_nonce",
+                encryption_nonce.len(),
+                encryption_type.nonce_length(),
+            ));
+        }
+
+        Ok(Self {
+            peer_identity_key: EllipticCurvePublicKey {
+                key_type: identity_key_type,
+                bytes: identity_key_bytes,
+            },
+            peer_ephemeral_key: EllipticCurvePublicKey {
+                key_type: ephemeral_key_type,
+                bytes: ephemeral_key_bytes,
+            },
+            pqkem_type,
+            pqkem_ciphertext: pb_first_message.pqkem_ciphertext,
+            used_signed_curve_prekey_id,
+            used_curve_prekey_id,
+            used_pqkem_prekey_id,
+            ciphertext: pb_first_message.ciphertext,
+            encryption_nonce,
+            encryption_type,
+        })
+    }
 }
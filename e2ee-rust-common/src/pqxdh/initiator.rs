@@ -0,0 +1,190 @@
+use rand::{CryptoRng, RngCore};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    crypto::{
+        aead::traits::AEADScheme, curve::traits::EllipticCurveAlgorithm,
+        pqkem::traits::PQKEMAlgorithm,
+    },
+    errors::pqxdh::PQXDHError,
+};
+
+use super::{
+    first_message::FirstMessage, kdf::kdf, parameters::PqxdhParameters,
+    prekey_bundle::PrekeyBundle, private_bundle::PrivateBundle,
+};
+
+pub struct InitiatorOutput {
+    pub first_message: FirstMessage,
+    pub shared_key: Zeroizing<[u8; 32]>,
+    pub associated_data: Vec<u8>,
+}
+
+// Runs the initiator side of the PQXDH key agreement (see https://signal.org/docs/specifications/pqxdh/#sending-the-initial-message)
+// Returns the first message to send to the peer, along with the derived shared key and associated data
+pub fn initiate<R: RngCore + CryptoRng>(
+    prekey_bundle: &PrekeyBundle,
+    private_bundle: &PrivateBundle,
+    plaintext: &[u8],
+    parameters: &PqxdhParameters,
+    rng: &mut R,
+) -> Result<InitiatorOutput, PQXDHError> {
+    let curve_type = parameters.curve_type();
+    let pqkem_type = parameters.pqkem_type();
+    let aead_type = parameters.aead_type();
+
+    // Make sure the bundle uses the expected algorithms
+    if private_bundle.identity_key.key_type != curve_type.get_type() {
+        return Err(PQXDHError::KeyTypeMismatch);
+    }
+
+    // Verify the peer key types and prekey signatures
+    prekey_bundle
+        .verify(curve_type, pqkem_type)
+        .map_err(PQXDHError::PrekeyBundle)?;
+    let signed_curve_prekey = &prekey_bundle.signed_curve_prekey;
+    let pqkem_prekey = &prekey_bundle.one_time_pqkem_prekey;
+
+    // Generate the ephemeral key pair
+    let ephemeral_key = curve_type.generate_key_pair(rng);
+
+    // Encapsulate a shared secret to the PQKEM prekey
+    let (pqkem_ciphertext, mut pqkem_shared_secret) = pqkem_type
+        .encapsulate(&pqkem_prekey.identified_public_key.public_key.bytes, rng)
+        .map_err(PQXDHError::PQKEM)?;
+
+    // Compute the Diffie-Hellman outputs
+    let dh1 = curve_type
+        .dh(
+            &private_bundle.identity_key.private_key,
+            &signed_curve_prekey.identified_public_key.public_key,
+        )
+        .map_err(PQXDHError::DiffieHellman)?;
+    let dh2 = curve_type
+        .dh(&ephemeral_key.private_key, &prekey_bundle.identity_key)
+        .map_err(PQXDHError::DiffieHellman)?;
+    let dh3 = curve_type
+        .dh(
+            &ephemeral_key.private_key,
+            &signed_curve_prekey.identified_public_key.public_key,
+        )
+        .map_err(PQXDHError::DiffieHellman)?;
+
+    let mut kdf_input: Vec<u8> = vec![];
+    kdf_input.extend_from_slice(&dh1);
+    kdf_input.extend_from_slice(&dh2);
+    kdf_input.extend_from_slice(&dh3);
+
+    // The fourth Diffie-Hellman is only performed if the bundle contains a one-time curve prekey
+    if let Some(one_time_curve_prekey) = &prekey_bundle.one_time_curve_prekey {
+        let dh4 = curve_type
+            .dh(
+                &ephemeral_key.private_key,
+                &one_time_curve_prekey.public_key,
+            )
+            .map_err(PQXDHError::DiffieHellman)?;
+        kdf_input.extend_from_slice(&dh4);
+    }
+    kdf_input.extend_from_slice(&pqkem_shared_secret);
+
+    // Derive the shared key
+    let shared_key = kdf(&kdf_input, parameters);
+    kdf_input.zeroize();
+    pqkem_shared_secret.zeroize();
+
+    // Compute the associated data from both identity keys
+    let mut associated_data = private_bundle.identity_key.public_key.encode_ec();
+    associated_data.extend_from_slice(&prekey_bundle.identity_key.encode_ec());
+
+    // Encrypt the initial payload
+    let (ciphertext, nonce) = aead_type
+        .encrypt(&shared_key, plaintext, &associated_data, rng)
+        .map_err(PQXDHError::AEAD)?;
+
+    Ok(InitiatorOutput {
+        first_message: FirstMessage {
+            peer_identity_key: private_bundle.identity_key.public_key.clone(),
+            peer_ephemeral_key: ephemeral_key.public_key.clone(),
+            pqkem_type: pqkem_type.get_type(),
+            pqkem_ciphertext,
+            used_signed_curve_prekey_id: signed_curve_prekey.identified_public_key.id,
+            used_curve_prekey_id: prekey_bundle.one_time_curve_prekey.as_ref().map(|k| k.id),
+            used_pqkem_prekey_id: pqkem_prekey.identified_public_key.id,
+            ciphertext,
+            encryption_nonce: nonce,
+            encryption_type: aead_type.get_type(),
+        },
+        shared_key,
+        associated_data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::{
+            aead::enum_aead_types::AEADType,
+            curve::{
+                enum_elliptic_curve_type::EllipticCurveType,
+                keys::IdentifiedEllipticCurvePublicKey, traits::EllipticCurveAlgorithm,
+            },
+            pqkem::enum_pqkem_type::PQKEMType,
+        },
+        errors::{
+            pqxdh::PQXDHError,
+            prekey_bundle::{PrekeyBundleError, PrekeyBundleKey},
+        },
+        hash::enum_hash_types::HashType,
+        pqxdh::{
+            parameters::PqxdhParameters, prekey_bundle::PrekeyBundle,
+            private_bundle::PrivateBundle, registration_bundle::RegistrationBundle,
+        },
+    };
+
+    use super::initiate;
+
+    #[test]
+    fn tampered_signed_prekey_is_rejected() {
+        let mut rng = rand::thread_rng();
+        let parameters = PqxdhParameters::new(
+            EllipticCurveType::CURVE25519,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap();
+        let alice = PrivateBundle::new(&parameters, 0, 0, &mut rng);
+        let bob = PrivateBundle::new(&parameters, 1, 1, &mut rng);
+        let mut registration_bundle =
+            RegistrationBundle::from_private_bundle(&bob, parameters.curve_type(), &mut rng)
+                .unwrap();
+        let bundle = PrekeyBundle::from_registration_bundle(&mut registration_bundle);
+        let signed_prekey_id = bundle.signed_curve_prekey.identified_public_key.id;
+
+        // A signature that does not match the prekey
+        let mut tampered_bundle = bundle.clone();
+        tampered_bundle.signed_curve_prekey.signature[0] ^= 1;
+        assert!(matches!(
+            initiate(&tampered_bundle, &alice, b"", &parameters, &mut rng),
+            Err(PQXDHError::PrekeyBundle(PrekeyBundleError::InvalidSignature(
+                PrekeyBundleKey::SignedCurvePrekey(id)
+            ))) if id == signed_prekey_id
+        ));
+
+        // A prekey substituted by the server, with the original signature
+        let substituted_prekey = parameters.curve_type().generate_key_pair(&mut rng);
+        let mut tampered_bundle = bundle.clone();
+        tampered_bundle.signed_curve_prekey.identified_public_key =
+            IdentifiedEllipticCurvePublicKey {
+                id: signed_prekey_id,
+                public_key: substituted_prekey.public_key.clone(),
+            };
+        assert!(matches!(
+            initiate(&tampered_bundle, &alice, b"", &parameters, &mut rng),
+            Err(PQXDHError::PrekeyBundle(
+                PrekeyBundleError::InvalidSignature(PrekeyBundleKey::SignedCurvePrekey(_))
+            ))
+        ));
+    }
+}
@@ -1,18 +1,21 @@
 use log::debug;
+use zeroize::{Zeroize, Zeroizing};
 
 use crate::{
-    crypto::{curve::traits::EllipticCurveAlgorithm, pqkem::traits::PQKEMAlgorithm},
-    hash::enum_hash_types::HashType,
-    utils::display::print_slice,
+    crypto::curve::traits::EllipticCurveAlgorithm,
+    hash::hkdf::hkdf,
+    utils::{display::print_slice, secret_logging::print_secret},
 };
 
-pub fn kdf<T: EllipticCurveAlgorithm, U: PQKEMAlgorithm>(
-    input: &[u8],
-    curve_type: &T,
-    kyber_type: &U,
-    hash_type: &HashType,
-    info: &str,
-) -> [u8; 32] {
+use super::parameters::PqxdhParameters;
+
+// The output key material is zeroized when dropped
+pub fn kdf(input: &[u8], parameters: &PqxdhParameters) -> Zeroizing<[u8; 32]> {
+    let curve_type = parameters.curve_type();
+    let pqkem_type = parameters.pqkem_type();
+    let hash_type = parameters.hash_type();
+    let info = parameters.info();
+
     // Generate the salt
     let hkdf_salt: Vec<u8> = vec![0u8; hash_type.get_output_size()];
 
@@ -25,29 +28,24 @@ pub fn kdf<T: EllipticCurveAlgorithm, U: PQKEMAlgorithm>(
     let info_string = format!(
         "{}_{}_{}_{}",
         info,
-        curve_type.get_type().to_str(),
+        curve_type.to_str(),
         hash_type.to_str(),
-        kyber_type.get_type().to_str()
+        pqkem_type.to_str()
     );
     let hkdf_info: &[u8] = info_string.as_bytes();
-    debug!("ikm: {}", print_slice(&hkdf_ikm));
+    debug!("ikm: {}", print_secret(&hkdf_ikm));
     debug!("info: {}", print_slice(&hkdf_info));
     debug!("salt: {}", print_slice(&hkdf_salt));
     debug!("hash_type: {}", hash_type.to_str());
-    debug!("curve_type: {}", curve_type.get_type().to_str());
-    debug!("kyber_type: {}", kyber_type.get_type().to_str());
+    debug!("curve_type: {}", curve_type.to_str());
+    debug!("pqkem_type: {}", pqkem_type.to_str());
     debug!("info: {:?}", info);
     debug!("info_string: {:?}", info_string);
 
-    let mut okm = [0u8; 32];
-    let res =
-        match hash_type {
-            HashType::SHA256 => hkdf::Hkdf::<sha2::Sha256>::new(Some(&hkdf_salt), &hkdf_ikm)
-                .expand(hkdf_info, &mut okm),
-            HashType::SHA512 => hkdf::Hkdf::<sha2::Sha512>::new(Some(&hkdf_salt), &hkdf_ikm)
-                .expand(hkdf_info, &mut okm),
-        };
-    debug!("OKM: {:?}", okm);
+    let mut okm = Zeroizing::new([0u8; 32]);
+    let res = hkdf(hash_type, &hkdf_salt, &hkdf_ikm, hkdf_info, &mut *okm);
+    hkdf_ikm.zeroize();
+    debug!("OKM: {}", print_secret(&*okm));
     debug!("res: {:?}", res);
 
     okm
@@ -1,9 +1,12 @@
 pub mod first_message;
+pub mod initiator;
 pub mod kdf;
 pub mod one_time_curve_prekey_set;
+pub mod parameters;
 pub mod prekey_bundle;
 pub mod private_bundle;
 pub mod registration_bundle;
+pub mod responder;
 pub mod signed_curve_prekey;
 pub mod signed_one_time_pqkem_prekey_set;
 pub mod signed_pqkem_prekey;
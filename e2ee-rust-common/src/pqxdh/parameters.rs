@@ -0,0 +1,162 @@
+use crate::{
+    crypto::{
+        aead::enum_aead_types::AEADType, cipher_suite::CipherSuite,
+        curve::enum_elliptic_curve_type::EllipticCurveType, pqkem::enum_pqkem_type::PQKEMType,
+    },
+    errors::pqxdh_parameters::PqxdhParametersError,
+    hash::enum_hash_types::HashType,
+};
+
+// Minimum length of the info string, in bytes
+pub const MIN_INFO_LENGTH: usize = 8;
+
+// Parameters an application must agree on to use PQXDH (see https://signal.org/docs/specifications/pqxdh/#pqxdh-parameters)
+// The suite can only be built through the constructors, so a value of this type always satisfies the spec constraints
+#[derive(PartialEq, Debug, Clone)]
+pub struct PqxdhParameters {
+    cipher_suite: CipherSuite,
+}
+
+impl PqxdhParameters {
+    pub fn new(
+        curve_type: EllipticCurveType,
+        hash_type: HashType,
+        info: &str,
+        pqkem_type: PQKEMType,
+        aead_type: AEADType,
+    ) -> Result<Self, PqxdhParametersError> {
+        Self::from_cipher_suite(CipherSuite::new(
+            curve_type, hash_type, pqkem_type, aead_type, info,
+        ))
+    }
+
+    pub fn from_cipher_suite(cipher_suite: CipherSuite) -> Result<Self, PqxdhParametersError> {
+        // info: An ASCII string identifying the application with a minimum length of 8 bytes
+        if !cipher_suite.info.is_ascii() {
+            return Err(PqxdhParametersError::NonAsciiInfo);
+        }
+        if cipher_suite.info.len() < MIN_INFO_LENGTH {
+            return Err(PqxdhParametersError::InfoTooShort(cipher_suite.info.len()));
+        }
+
+        // hash: A 256 or 512-bit hash function
+        check_hash_output_size(cipher_suite.hash_type.get_output_size())?;
+
+        // curve, pqkem and aead: every supported algorithm satisfies the spec requirements
+        Ok(PqxdhParameters { cipher_suite })
+    }
+
+    pub fn curve_type(&self) -> &EllipticCurveType {
+        &self.cipher_suite.curve_type
+    }
+
+    pub fn hash_type(&self) -> &HashType {
+        &self.cipher_suite.hash_type
+    }
+
+    pub fn info(&self) -> &str {
+        &self.cipher_suite.info
+    }
+
+    pub fn pqkem_type(&self) -> &PQKEMType {
+        &self.cipher_suite.pqkem_type
+    }
+
+    pub fn aead_type(&self) -> &AEADType {
+        &self.cipher_suite.aead_type
+    }
+
+    pub fn cipher_suite(&self) -> &CipherSuite {
+        &self.cipher_suite
+    }
+}
+
+// Every supported hash type has a valid output size, the check guards against adding one that does not
+fn check_hash_output_size(hash_output_size: usize) -> Result<(), PqxdhParametersError> {
+    if hash_output_size != 32 && hash_output_size != 64 {
+        return Err(PqxdhParametersError::UnsupportedHashOutputSize(
+            hash_output_size,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::{
+            aead::enum_aead_types::AEADType, cipher_suite::CipherSuite,
+            curve::enum_elliptic_curve_type::EllipticCurveType, pqkem::enum_pqkem_type::PQKEMType,
+        },
+        errors::pqxdh_parameters::PqxdhParametersError,
+        hash::enum_hash_types::HashType,
+    };
+
+    use super::{check_hash_output_size, PqxdhParameters};
+
+    fn cipher_suite(hash_type: HashType, info: &str) -> CipherSuite {
+        CipherSuite::new(
+            EllipticCurveType::CURVE448,
+            hash_type,
+            PQKEMType::MLKEM1024,
+            AEADType::XCHACHA20POLY1305,
+            info,
+        )
+    }
+
+    #[test]
+    fn valid_cipher_suite_is_accepted() {
+        let suite = cipher_suite(HashType::SHA512, "PQXDHTestApplication");
+        let parameters = PqxdhParameters::from_cipher_suite(suite.clone()).unwrap();
+
+        assert_eq!(parameters.cipher_suite(), &suite);
+        assert_eq!(parameters.curve_type(), &EllipticCurveType::CURVE448);
+        assert_eq!(parameters.hash_type(), &HashType::SHA512);
+        assert_eq!(parameters.info(), "PQXDHTestApplication");
+        assert_eq!(parameters.pqkem_type(), &PQKEMType::MLKEM1024);
+        assert_eq!(parameters.aead_type(), &AEADType::XCHACHA20POLY1305);
+    }
+
+    #[test]
+    fn every_hash_type_is_accepted() {
+        for hash_type in [
+            HashType::SHA256,
+            HashType::SHA512,
+            HashType::SHA3_256,
+            HashType::SHA3_512,
+            HashType::BLAKE2B512,
+        ] {
+            PqxdhParameters::from_cipher_suite(cipher_suite(hash_type, "PQXDHTestApplication"))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn non_ascii_info_is_rejected() {
+        assert_eq!(
+            PqxdhParameters::from_cipher_suite(cipher_suite(HashType::SHA256, "PQXDHTestAppé")),
+            Err(PqxdhParametersError::NonAsciiInfo)
+        );
+    }
+
+    #[test]
+    fn short_info_is_rejected() {
+        assert_eq!(
+            PqxdhParameters::from_cipher_suite(cipher_suite(HashType::SHA256, "PQXDH")),
+            Err(PqxdhParametersError::InfoTooShort(5))
+        );
+
+        // Exactly 8 bytes is enough
+        PqxdhParameters::from_cipher_suite(cipher_suite(HashType::SHA256, "PQXDHApp")).unwrap();
+    }
+
+    #[test]
+    fn unsupported_hash_output_size_is_rejected() {
+        assert_eq!(
+            check_hash_output_size(48),
+            Err(PqxdhParametersError::UnsupportedHashOutputSize(48))
+        );
+        assert_eq!(check_hash_output_size(32), Ok(()));
+        assert_eq!(check_hash_output_size(64), Ok(()));
+    }
+}
@@ -1,7 +1,14 @@
 use log::debug;
 
 use crate::{
-    crypto::curve::keys::{EllipticCurvePublicKey, IdentifiedEllipticCurvePublicKey},
+    crypto::{
+        curve::{
+            keys::{EllipticCurvePublicKey, IdentifiedEllipticCurvePublicKey},
+            traits::EllipticCurveAlgorithm,
+        },
+        pqkem::traits::PQKEMAlgorithm,
+    },
+    errors::prekey_bundle::{PrekeyBundleError, PrekeyBundleKey},
     utils::display::print_slice,
 };
 
@@ -38,6 +45,82 @@ impl PrekeyBundle {
         }
     }
 
+    // Verifies that the bundle keys use the expected curve and PQKEM, and that the prekeys were signed by the identity key
+    pub fn verify(
+        &self,
+        curve: &impl EllipticCurveAlgorithm,
+        pqkem: &impl PQKEMAlgorithm,
+    ) -> Result<(), PrekeyBundleError> {
+        debug!("Verifying the prekey bundle");
+        let signed_curve_prekey = &self.signed_curve_prekey.identified_public_key;
+        let pqkem_prekey = &self.one_time_pqkem_prekey.identified_public_key;
+
+        // Make sure the curve keys use the expected algorithm
+        if self.identity_key.key_type != curve.get_type() {
+            return Err(PrekeyBundleError::KeyTypeMismatch(
+                PrekeyBundleKey::IdentityKey,
+            ));
+        }
+        if signed_curve_prekey.public_key.key_type != curve.get_type() {
+            return Err(PrekeyBundleError::KeyTypeMismatch(
+                PrekeyBundleKey::SignedCurvePrekey(signed_curve_prekey.id),
+            ));
+        }
+        if let Some(one_time_curve_prekey) = &self.one_time_curve_prekey {
+            if one_time_curve_prekey.public_key.key_type != curve.get_type() {
+                return Err(PrekeyBundleError::KeyTypeMismatch(
+                    PrekeyBundleKey::OneTimeCurvePrekey(one_time_curve_prekey.id),
+                ));
+            }
+        }
+
+        // Make sure the PQKEM prekey, one-time or last resort, uses the expected algorithm
+        if pqkem_prekey.public_key.key_type != pqkem.get_type() {
+            return Err(PrekeyBundleError::KeyTypeMismatch(
+                PrekeyBundleKey::PQKEMPrekey(pqkem_prekey.id),
+            ));
+        }
+
+        // Make sure the curve keys can safely be used for Diffie-Hellman
+        curve
+            .validate_public_key(&self.identity_key)
+            .map_err(|e| PrekeyBundleError::InvalidPublicKey(PrekeyBundleKey::IdentityKey, e))?;
+        curve
+            .validate_public_key(&signed_curve_prekey.public_key)
+            .map_err(|e| {
+                PrekeyBundleError::InvalidPublicKey(
+                    PrekeyBundleKey::SignedCurvePrekey(signed_curve_prekey.id),
+                    e,
+                )
+            })?;
+        if let Some(one_time_curve_prekey) = &self.one_time_curve_prekey {
+            curve
+                .validate_public_key(&one_time_curve_prekey.public_key)
+                .map_err(|e| {
+                    PrekeyBundleError::InvalidPublicKey(
+                        PrekeyBundleKey::OneTimeCurvePrekey(one_time_curve_prekey.id),
+                        e,
+                    )
+                })?;
+        }
+
+        // Verify the signed curve prekey signature
+        let key = PrekeyBundleKey::SignedCurvePrekey(signed_curve_prekey.id);
+        match self.signed_curve_prekey.verify(&self.identity_key, curve) {
+            Ok(true) => {}
+            Ok(false) => return Err(PrekeyBundleError::InvalidSignature(key)),
+            Err(e) => return Err(PrekeyBundleError::XedDSA(key, e)),
+        }
+
+        // Verify the PQKEM prekey signature
+        let key = PrekeyBundleKey::PQKEMPrekey(pqkem_prekey.id);
+        match self.one_time_pqkem_prekey.verify(&self.identity_key, curve) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(PrekeyBundleError::InvalidSignature(key)),
+            Err(e) => Err(PrekeyBundleError::XedDSA(key, e)),
+        }
+    }
+
     pub fn print(&self) {
         debug!("identity_key: {}", self.identity_key.print_key());
         debug!(
@@ -75,3 +158,243 @@ impl PrekeyBundle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crypto::{
+            aead::enum_aead_types::AEADType,
+            curve::{
+                curve25519::Curve25519, enum_elliptic_curve_type::EllipticCurveType,
+                keys::EllipticCurvePublicKey,
+            },
+            pqkem::enum_pqkem_type::PQKEMType,
+        },
+        errors::{
+            diffie_hellman::DiffieHellmanError,
+            prekey_bundle::{PrekeyBundleError, PrekeyBundleKey},
+            xeddsa::XedDSAError,
+        },
+        hash::enum_hash_types::HashType,
+        pqxdh::{
+            parameters::PqxdhParameters, private_bundle::PrivateBundle,
+            registration_bundle::RegistrationBundle,
+        },
+    };
+
+    use super::PrekeyBundle;
+
+    // Builds a valid bundle with a one-time curve prekey and a one-time PQKEM prekey
+    fn prekey_bundle() -> PrekeyBundle {
+        prekey_bundle_with_one_time_pqkem_prekeys(1)
+    }
+
+    // Without one-time PQKEM prekeys, the bundle holds the last resort prekey
+    fn prekey_bundle_with_one_time_pqkem_prekeys(one_time_pqkem_prekeys: usize) -> PrekeyBundle {
+        let mut rng = rand::thread_rng();
+        let parameters = PqxdhParameters::new(
+            EllipticCurveType::CURVE25519,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap();
+        let private_bundle = PrivateBundle::new(&parameters, 1, one_time_pqkem_prekeys, &mut rng);
+        let mut registration_bundle = RegistrationBundle::from_private_bundle(
+            &private_bundle,
+            parameters.curve_type(),
+            &mut rng,
+        )
+        .unwrap();
+        PrekeyBundle::from_registration_bundle(&mut registration_bundle)
+    }
+
+    fn curve448_key() -> EllipticCurvePublicKey {
+        EllipticCurvePublicKey {
+            key_type: EllipticCurveType::CURVE448,
+            bytes: vec![5u8; 56],
+        }
+    }
+
+    fn low_order_key() -> EllipticCurvePublicKey {
+        EllipticCurvePublicKey {
+            key_type: EllipticCurveType::CURVE25519,
+            bytes: vec![0u8; 32],
+        }
+    }
+
+    #[test]
+    fn valid_bundle_is_accepted() {
+        let bundle = prekey_bundle();
+        assert!(bundle.one_time_curve_prekey.is_some());
+        bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512).unwrap();
+    }
+
+    #[test]
+    fn key_type_mismatch_is_reported() {
+        let bundle = prekey_bundle();
+        let signed_curve_prekey_id = bundle.signed_curve_prekey.identified_public_key.id;
+        let one_time_curve_prekey_id = bundle.one_time_curve_prekey.as_ref().unwrap().id;
+
+        let mut identity_key_bundle = bundle.clone();
+        identity_key_bundle.identity_key = curve448_key();
+        assert!(matches!(
+            identity_key_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::KeyTypeMismatch(
+                PrekeyBundleKey::IdentityKey
+            ))
+        ));
+
+        let mut signed_curve_prekey_bundle = bundle.clone();
+        signed_curve_prekey_bundle
+            .signed_curve_prekey
+            .identified_public_key
+            .public_key = curve448_key();
+        assert!(matches!(
+            signed_curve_prekey_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::KeyTypeMismatch(PrekeyBundleKey::SignedCurvePrekey(id)))
+                if id == signed_curve_prekey_id
+        ));
+
+        let mut one_time_curve_prekey_bundle = bundle;
+        one_time_curve_prekey_bundle
+            .one_time_curve_prekey
+            .as_mut()
+            .unwrap()
+            .public_key = curve448_key();
+        assert!(matches!(
+            one_time_curve_prekey_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::KeyTypeMismatch(PrekeyBundleKey::OneTimeCurvePrekey(id)))
+                if id == one_time_curve_prekey_id
+        ));
+    }
+
+    #[test]
+    fn pqkem_key_type_mismatch_is_reported() {
+        // The one-time PQKEM prekey and the last resort prekey are both checked
+        for bundle in [
+            prekey_bundle(),
+            prekey_bundle_with_one_time_pqkem_prekeys(0),
+        ] {
+            let pqkem_prekey_id = bundle.one_time_pqkem_prekey.identified_public_key.id;
+
+            // The bundle uses another PQKEM than the one expected
+            assert!(matches!(
+                bundle.verify(&Curve25519 {}, &PQKEMType::KYBER1024),
+                Err(PrekeyBundleError::KeyTypeMismatch(PrekeyBundleKey::PQKEMPrekey(id)))
+                    if id == pqkem_prekey_id
+            ));
+
+            // The prekey is labelled with another PQKEM
+            let mut relabelled_bundle = bundle;
+            relabelled_bundle
+                .one_time_pqkem_prekey
+                .identified_public_key
+                .public_key
+                .key_type = PQKEMType::MLKEM512;
+            assert!(matches!(
+                relabelled_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+                Err(PrekeyBundleError::KeyTypeMismatch(PrekeyBundleKey::PQKEMPrekey(id)))
+                    if id == pqkem_prekey_id
+            ));
+        }
+    }
+
+    #[test]
+    fn low_order_key_is_reported() {
+        let bundle = prekey_bundle();
+        let signed_curve_prekey_id = bundle.signed_curve_prekey.identified_public_key.id;
+        let one_time_curve_prekey_id = bundle.one_time_curve_prekey.as_ref().unwrap().id;
+
+        let mut identity_key_bundle = bundle.clone();
+        identity_key_bundle.identity_key = low_order_key();
+        assert!(matches!(
+            identity_key_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::InvalidPublicKey(
+                PrekeyBundleKey::IdentityKey,
+                DiffieHellmanError::LowOrderPublicKey
+            ))
+        ));
+
+        let mut signed_curve_prekey_bundle = bundle.clone();
+        signed_curve_prekey_bundle
+            .signed_curve_prekey
+            .identified_public_key
+            .public_key = low_order_key();
+        assert!(matches!(
+            signed_curve_prekey_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::InvalidPublicKey(
+                PrekeyBundleKey::SignedCurvePrekey(id),
+                DiffieHellmanError::LowOrderPublicKey
+            )) if id == signed_curve_prekey_id
+        ));
+
+        let mut one_time_curve_prekey_bundle = bundle;
+        one_time_curve_prekey_bundle
+            .one_time_curve_prekey
+            .as_mut()
+            .unwrap()
+            .public_key = low_order_key();
+        assert!(matches!(
+            one_time_curve_prekey_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::InvalidPublicKey(
+                PrekeyBundleKey::OneTimeCurvePrekey(id),
+                DiffieHellmanError::LowOrderPublicKey
+            )) if id == one_time_curve_prekey_id
+        ));
+    }
+
+    #[test]
+    fn bad_signature_is_reported() {
+        let bundle = prekey_bundle();
+        let signed_curve_prekey_id = bundle.signed_curve_prekey.identified_public_key.id;
+        let pqkem_prekey_id = bundle.one_time_pqkem_prekey.identified_public_key.id;
+
+        let mut signed_curve_prekey_bundle = bundle.clone();
+        signed_curve_prekey_bundle.signed_curve_prekey.signature[0] ^= 1;
+        assert!(matches!(
+            signed_curve_prekey_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::InvalidSignature(PrekeyBundleKey::SignedCurvePrekey(id)))
+                if id == signed_curve_prekey_id
+        ));
+
+        let mut pqkem_prekey_bundle = bundle.clone();
+        pqkem_prekey_bundle.one_time_pqkem_prekey.signature[0] ^= 1;
+        assert!(matches!(
+            pqkem_prekey_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::InvalidSignature(PrekeyBundleKey::PQKEMPrekey(id)))
+                if id == pqkem_prekey_id
+        ));
+
+        // Signatures of the wrong size cannot be checked at all
+        let mut truncated_signature_bundle = bundle;
+        truncated_signature_bundle
+            .one_time_pqkem_prekey
+            .signature
+            .pop();
+        assert!(matches!(
+            truncated_signature_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::XedDSA(
+                PrekeyBundleKey::PQKEMPrekey(id),
+                XedDSAError::InvalidSignatureLength
+            )) if id == pqkem_prekey_id
+        ));
+    }
+
+    #[test]
+    fn identity_key_substitution_is_detected() {
+        let bundle = prekey_bundle();
+        let other_bundle = prekey_bundle();
+
+        // The prekeys of the bundle were not signed by the substituted identity key
+        let mut substituted_bundle = bundle;
+        substituted_bundle.identity_key = other_bundle.identity_key.clone();
+        assert!(matches!(
+            substituted_bundle.verify(&Curve25519 {}, &PQKEMType::KYBER512),
+            Err(PrekeyBundleError::InvalidSignature(
+                PrekeyBundleKey::SignedCurvePrekey(_)
+            ))
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use log::debug;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::crypto::{
     curve::{
@@ -6,9 +7,11 @@ use crate::crypto::{
         traits::EllipticCurveAlgorithm,
     },
     pqkem::{keys::IdentifiedPQKEMKeyPair, traits::PQKEMAlgorithm},
-    traits::PrintableKey,
 };
 
+use super::parameters::PqxdhParameters;
+
+#[derive(Zeroize, ZeroizeOnDrop, Debug)]
 pub struct PrivateBundle {
     pub identity_key: EllipticCurveKeyPair,
     pub curve_prekey: IdentifiedEllipticCurveKeyPair,
@@ -18,13 +21,15 @@ pub struct PrivateBundle {
 }
 
 impl PrivateBundle {
-    pub fn new<T: EllipticCurveAlgorithm, U: PQKEMAlgorithm, R: rand::RngCore + rand::CryptoRng>(
-        curve_type: &T,
-        pqkem_type: &U,
+    pub fn new<R: rand::RngCore + rand::CryptoRng>(
+        parameters: &PqxdhParameters,
         nb_ot_curve_prekeys: usize,
         nb_ot_pqkem_prekeys: usize,
         rng: &mut R,
     ) -> Self {
+        let curve_type = parameters.curve_type();
+        let pqkem_type = parameters.pqkem_type();
+
         PrivateBundle {
             identity_key: curve_type.generate_key_pair(rng),
             curve_prekey: curve_type.generate_identified_key_pair(rng),
@@ -38,33 +43,31 @@ impl PrivateBundle {
         }
     }
 
+    // Only logs the key ids and public keys, the private keys never leave the bundle
     pub fn print(&self) {
-        debug!(
-            "identity_key: {}",
-            self.identity_key.private_key.print_key()
-        );
+        debug!("identity_key: {}", self.identity_key.public_key.print_key());
         debug!(
             "curve_prekey: {}, {}",
             self.curve_prekey.id,
-            self.curve_prekey.key_pair.private_key.print_key()
+            self.curve_prekey.key_pair.public_key.print_key()
         );
         debug!(
             "last_resort_prekey: {}, {}",
             self.last_resort_prekey.id,
-            self.last_resort_prekey.key_pair.private_key.print_key()
+            self.last_resort_prekey.key_pair.public_key.print_key()
         );
         for c_pk in &self.one_time_curve_prekeys {
             debug!(
                 "one_time_curve_prekey: {}, {}",
                 c_pk.id,
-                c_pk.key_pair.private_key.print_key()
+                c_pk.key_pair.public_key.print_key()
             );
         }
         for pq_pk in &self.one_time_pqkem_prekeys {
             debug!(
                 "one_time_pqkem_prekey: {}, {}",
                 pq_pk.id,
-                pq_pk.key_pair.private_key.print_key()
+                pq_pk.key_pair.public_key.print_key()
             );
         }
     }
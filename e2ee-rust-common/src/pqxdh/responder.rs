@@ -0,0 +1,369 @@
+use log::debug;
+use uuid::Uuid;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+    crypto::{
+        aead::traits::AEADScheme,
+        curve::{keys::EllipticCurvePrivateKey, traits::EllipticCurveAlgorithm},
+        pqkem::{keys::PQKEMPrivateKey, traits::PQKEMAlgorithm},
+    },
+    errors::pqxdh::PQXDHError,
+};
+
+use super::{
+    first_message::FirstMessage, kdf::kdf, parameters::PqxdhParameters,
+    private_bundle::PrivateBundle,
+};
+
+pub struct ResponderOutput {
+    pub plaintext: Vec<u8>,
+    pub shared_key: Zeroizing<[u8; 32]>,
+    pub associated_data: Vec<u8>,
+    // The one-time prekeys used by the peer, the last resort prekey is never reported
+    pub used_one_time_curve_prekey_id: Option<Uuid>,
+    pub used_one_time_pqkem_prekey_id: Option<Uuid>,
+}
+
+// Runs the responder side of the PQXDH key agreement (see https://signal.org/docs/specifications/pqxdh/#receiving-the-initial-message)
+// The storage is left untouched: the caller removes the used one-time prekeys together with storing the session
+// Only the current signed curve prekey is accepted, so a first message built against a bundle fetched
+// before the last rotation fails with UnknownSignedCurvePrekey and the peer has to fetch a new bundle
+pub fn respond(
+    first_message: &FirstMessage,
+    private_bundle: &PrivateBundle,
+    parameters: &PqxdhParameters,
+) -> Result<ResponderOutput, PQXDHError> {
+    let curve_type = parameters.curve_type();
+    let pqkem_type = parameters.pqkem_type();
+    let aead_type = parameters.aead_type();
+
+    // Make sure the message uses the expected algorithms
+    if first_message.peer_identity_key.key_type != curve_type.get_type()
+        || first_message.peer_ephemeral_key.key_type != curve_type.get_type()
+        || private_bundle.identity_key.key_type != curve_type.get_type()
+        || first_message.pqkem_type != pqkem_type.get_type()
+    {
+        return Err(PQXDHError::KeyTypeMismatch);
+    }
+    if first_message.encryption_type != aead_type.get_type() {
+        return Err(PQXDHError::AEADTypeMismatch);
+    }
+
+    // Look up the signed curve prekey
+    debug!("Looking up the prekeys used by the peer");
+    if private_bundle.curve_prekey.id != first_message.used_signed_curve_prekey_id {
+        return Err(PQXDHError::UnknownSignedCurvePrekey(
+            first_message.used_signed_curve_prekey_id,
+        ));
+    }
+    let signed_curve_prekey: &EllipticCurvePrivateKey =
+        &private_bundle.curve_prekey.key_pair.private_key;
+
+    // Look up the one-time curve prekey, if one was used
+    let one_time_curve_prekey: Option<&EllipticCurvePrivateKey> =
+        match first_message.used_curve_prekey_id {
+            Some(prekey_id) => Some(
+                &private_bundle
+                    .one_time_curve_prekeys
+                    .iter()
+                    .find(|k| k.id == prekey_id)
+                    .ok_or(PQXDHError::UnknownOneTimeCurvePrekey(prekey_id))?
+                    .key_pair
+                    .private_key,
+            ),
+            None => None,
+        };
+
+    // Look up the PQKEM prekey, which is either a one-time prekey or the last resort prekey
+    let pqkem_prekey_id = first_message.used_pqkem_prekey_id;
+    let is_one_time_pqkem_prekey = private_bundle.last_resort_prekey.id != pqkem_prekey_id;
+    let pqkem_prekey: &PQKEMPrivateKey = if is_one_time_pqkem_prekey {
+        &private_bundle
+            .one_time_pqkem_prekeys
+            .iter()
+            .find(|k| k.id == pqkem_prekey_id)
+            .ok_or(PQXDHError::UnknownPQKEMPrekey(pqkem_prekey_id))?
+            .key_pair
+            .private_key
+    } else {
+        &private_bundle.last_resort_prekey.key_pair.private_key
+    };
+    if pqkem_prekey.key_type != pqkem_type.get_type() {
+        return Err(PQXDHError::KeyTypeMismatch);
+    }
+
+    // Decapsulate the PQKEM shared secret
+    let mut pqkem_shared_secret = pqkem_type
+        .decapsulate(
+            pqkem_prekey.bytes.expose_secret(),
+            &first_message.pqkem_ciphertext,
+        )
+        .map_err(PQXDHError::PQKEM)?;
+
+    // Compute the Diffie-Hellman outputs
+    let dh1 = curve_type
+        .dh(signed_curve_prekey, &first_message.peer_identity_key)
+        .map_err(PQXDHError::DiffieHellman)?;
+    let dh2 = curve_type
+        .dh(
+            &private_bundle.identity_key.private_key,
+            &first_message.peer_ephemeral_key,
+        )
+        .map_err(PQXDHError::DiffieHellman)?;
+    let dh3 = curve_type
+        .dh(signed_curve_prekey, &first_message.peer_ephemeral_key)
+        .map_err(PQXDHError::DiffieHellman)?;
+
+    let mut kdf_input: Vec<u8> = vec![];
+    kdf_input.extend_from_slice(&dh1);
+    kdf_input.extend_from_slice(&dh2);
+    kdf_input.extend_from_slice(&dh3);
+    if let Some(one_time_curve_prekey) = one_time_curve_prekey {
+        let dh4 = curve_type
+            .dh(one_time_curve_prekey, &first_message.peer_ephemeral_key)
+            .map_err(PQXDHError::DiffieHellman)?;
+        kdf_input.extend_from_slice(&dh4);
+    }
+    kdf_input.extend_from_slice(&pqkem_shared_secret);
+
+    // Derive the shared key
+    let shared_key = kdf(&kdf_input, parameters);
+    kdf_input.zeroize();
+    pqkem_shared_secret.zeroize();
+
+    // Compute the associated data from both identity keys
+    let mut associated_data = first_message.peer_identity_key.encode_ec();
+    associated_data.extend_from_slice(&private_bundle.identity_key.public_key.encode_ec());
+
+    // Decrypt the initial payload
+    let plaintext = aead_type
+        .decrypt(
+            &shared_key,
+            &first_message.ciphertext,
+            &associated_data,
+            &first_message.encryption_nonce,
+        )
+        .map_err(PQXDHError::AEAD)?;
+
+    Ok(ResponderOutput {
+        plaintext,
+        shared_key,
+        associated_data,
+        used_one_time_curve_prekey_id: first_message.used_curve_prekey_id,
+        used_one_time_pqkem_prekey_id: is_one_time_pqkem_prekey.then_some(pqkem_prekey_id),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        crypto::{
+            aead::enum_aead_types::AEADType,
+            curve::{enum_elliptic_curve_type::EllipticCurveType, traits::EllipticCurveAlgorithm},
+            pqkem::enum_pqkem_type::PQKEMType,
+        },
+        errors::pqxdh::PQXDHError,
+        hash::enum_hash_types::HashType,
+        pqxdh::{
+            initiator::{initiate, InitiatorOutput},
+            parameters::PqxdhParameters,
+            prekey_bundle::PrekeyBundle,
+            private_bundle::PrivateBundle,
+            registration_bundle::RegistrationBundle,
+        },
+    };
+
+    use super::respond;
+
+    fn parameters() -> PqxdhParameters {
+        PqxdhParameters::new(
+            EllipticCurveType::CURVE25519,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap()
+    }
+
+    // Builds the bundle the server would hand out for the given private bundle
+    fn prekey_bundle(private_bundle: &PrivateBundle, parameters: &PqxdhParameters) -> PrekeyBundle {
+        let mut registration_bundle = RegistrationBundle::from_private_bundle(
+            private_bundle,
+            parameters.curve_type(),
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+        PrekeyBundle::from_registration_bundle(&mut registration_bundle)
+    }
+
+    // Alice initiates the key agreement with Bob, who has one prekey of each kind
+    fn first_message_to_bob(parameters: &PqxdhParameters) -> (PrivateBundle, InitiatorOutput) {
+        let mut rng = rand::thread_rng();
+        let alice = PrivateBundle::new(parameters, 0, 0, &mut rng);
+        let bob = PrivateBundle::new(parameters, 1, 1, &mut rng);
+        let bundle = prekey_bundle(&bob, parameters);
+        let initiator_output =
+            initiate(&bundle, &alice, b"hello bob", parameters, &mut rng).unwrap();
+        (bob, initiator_output)
+    }
+
+    #[test]
+    fn initiator_and_responder_agree() {
+        let mut rng = rand::thread_rng();
+        let parameters = parameters();
+        let alice = PrivateBundle::new(&parameters, 0, 0, &mut rng);
+        let bob = PrivateBundle::new(&parameters, 1, 1, &mut rng);
+        let bundle = prekey_bundle(&bob, &parameters);
+
+        let initiator_output =
+            initiate(&bundle, &alice, b"hello bob", &parameters, &mut rng).unwrap();
+        let responder_output = respond(&initiator_output.first_message, &bob, &parameters).unwrap();
+
+        assert_eq!(responder_output.plaintext, b"hello bob");
+        assert_eq!(initiator_output.shared_key, responder_output.shared_key);
+        assert_eq!(
+            initiator_output.associated_data,
+            responder_output.associated_data
+        );
+
+        // AD = Encode(IK_A) || Encode(IK_B)
+        let mut associated_data = alice.identity_key.public_key.encode_ec();
+        associated_data.extend_from_slice(&bob.identity_key.public_key.encode_ec());
+        assert_eq!(initiator_output.associated_data, associated_data);
+
+        // Both one-time prekeys were used, and must be removed
+        assert_eq!(
+            responder_output.used_one_time_curve_prekey_id,
+            Some(bob.one_time_curve_prekeys[0].id)
+        );
+        assert_eq!(
+            responder_output.used_one_time_pqkem_prekey_id,
+            Some(bob.one_time_pqkem_prekeys[0].id)
+        );
+    }
+
+    #[test]
+    fn last_resort_prekey_is_kept() {
+        let mut rng = rand::thread_rng();
+        let parameters = parameters();
+        let alice = PrivateBundle::new(&parameters, 0, 0, &mut rng);
+        let bob = PrivateBundle::new(&parameters, 0, 0, &mut rng);
+        let bundle = prekey_bundle(&bob, &parameters);
+        assert!(bundle.one_time_curve_prekey.is_none());
+
+        let initiator_output = initiate(&bundle, &alice, b"", &parameters, &mut rng).unwrap();
+        let responder_output = respond(&initiator_output.first_message, &bob, &parameters).unwrap();
+
+        assert_eq!(initiator_output.shared_key, responder_output.shared_key);
+        assert!(responder_output.used_one_time_curve_prekey_id.is_none());
+        assert!(responder_output.used_one_time_pqkem_prekey_id.is_none());
+    }
+
+    #[test]
+    fn rotated_signed_prekey_is_unknown() {
+        let parameters = parameters();
+        let (mut bob, initiator_output) = first_message_to_bob(&parameters);
+
+        // Bob rotated his signed prekey after Alice fetched the bundle
+        bob.curve_prekey = parameters
+            .curve_type()
+            .generate_identified_key_pair(&mut rand::thread_rng());
+        let signed_prekey_id = initiator_output.first_message.used_signed_curve_prekey_id;
+        assert!(matches!(
+            respond(&initiator_output.first_message, &bob, &parameters),
+            Err(PQXDHError::UnknownSignedCurvePrekey(id)) if id == signed_prekey_id
+        ));
+    }
+
+    #[test]
+    fn used_one_time_curve_prekey_is_unknown() {
+        let parameters = parameters();
+        let (mut bob, initiator_output) = first_message_to_bob(&parameters);
+
+        // The one-time curve prekey was already consumed by another first message
+        let prekey_id = bob.one_time_curve_prekeys.remove(0).id;
+        assert!(matches!(
+            respond(&initiator_output.first_message, &bob, &parameters),
+            Err(PQXDHError::UnknownOneTimeCurvePrekey(id)) if id == prekey_id
+        ));
+    }
+
+    #[test]
+    fn used_one_time_pqkem_prekey_is_unknown() {
+        let parameters = parameters();
+        let (mut bob, initiator_output) = first_message_to_bob(&parameters);
+
+        // The one-time PQKEM prekey was already consumed by another first message
+        let prekey_id = bob.one_time_pqkem_prekeys.remove(0).id;
+        assert!(matches!(
+            respond(&initiator_output.first_message, &bob, &parameters),
+            Err(PQXDHError::UnknownPQKEMPrekey(id)) if id == prekey_id
+        ));
+
+        // Neither is an unknown last resort prekey
+        let mut first_message = initiator_output.first_message;
+        first_message.used_pqkem_prekey_id = Uuid::new_v4();
+        assert!(matches!(
+            respond(&first_message, &bob, &parameters),
+            Err(PQXDHError::UnknownPQKEMPrekey(id)) if id == first_message.used_pqkem_prekey_id
+        ));
+    }
+
+    #[test]
+    fn other_key_types_are_rejected() {
+        let parameters = parameters();
+        let (bob, initiator_output) = first_message_to_bob(&parameters);
+        let mut first_message = initiator_output.first_message;
+
+        // The responder expects another curve
+        let curve448_parameters = PqxdhParameters::new(
+            EllipticCurveType::CURVE448,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap();
+        assert!(matches!(
+            respond(&first_message, &bob, &curve448_parameters),
+            Err(PQXDHError::KeyTypeMismatch)
+        ));
+
+        // The message claims another PQKEM
+        first_message.pqkem_type = PQKEMType::KYBER768;
+        assert!(matches!(
+            respond(&first_message, &bob, &parameters),
+            Err(PQXDHError::KeyTypeMismatch)
+        ));
+    }
+
+    #[test]
+    fn other_aead_type_is_rejected() {
+        let parameters = parameters();
+        let (bob, initiator_output) = first_message_to_bob(&parameters);
+        let mut first_message = initiator_output.first_message;
+
+        first_message.encryption_type = AEADType::CHACHA20POLY1305;
+        assert!(matches!(
+            respond(&first_message, &bob, &parameters),
+            Err(PQXDHError::AEADTypeMismatch)
+        ));
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let parameters = parameters();
+        let (bob, initiator_output) = first_message_to_bob(&parameters);
+        let mut first_message = initiator_output.first_message;
+
+        first_message.ciphertext[0] ^= 1;
+        assert!(matches!(
+            respond(&first_message, &bob, &parameters),
+            Err(PQXDHError::AEAD(_))
+        ));
+    }
+}
@@ -2,18 +2,32 @@ use crate::{
     crypto::curve::{
         enum_elliptic_curve_type::EllipticCurveType,
         keys::{EllipticCurvePublicKey, IdentifiedEllipticCurvePublicKey},
+        traits::EllipticCurveAlgorithm,
     },
-    errors::protobuf::ProtobufError,
+    errors::{protobuf::ProtobufError, xeddsa::XedDSAError},
     protobuf::{pqxdh::PbSignedCurvePrekey, utils::uuid_from_bytes},
 };
 
 #[derive(Clone, Debug)]
 pub struct SignedCurvePrekey {
     pub identified_public_key: IdentifiedEllipticCurvePublicKey,
-    pub signature: [u8; 64],
+    pub signature: Vec<u8>,
 }
 
 impl SignedCurvePrekey {
+    // Verifies that the prekey was signed by the given identity key
+    pub fn verify<T: EllipticCurveAlgorithm>(
+        &self,
+        identity_key: &EllipticCurvePublicKey,
+        curve_type: &T,
+    ) -> Result<bool, XedDSAError> {
+        curve_type.xeddsa_verify(
+            identity_key,
+            &self.identified_public_key.public_key.encode_ec(),
+            &self.signature,
+        )
+    }
+
     pub fn to_protobuf(&self) -> PbSignedCurvePrekey {
         PbSignedCurvePrekey {
             identified_public_key: Some(self.identified_public_key.to_protobuf()),
@@ -48,13 +62,6 @@ impl SignedCurvePrekey {
             ));
         }
 
-        let pb_curve_prekey_signature = pb_signed_curve_prekey.signature;
-        let pb_curve_prekey_signature_len = pb_curve_prekey_signature.len();
-        let curve_prekey_signature_bytes: [u8; 64] =
-            pb_curve_prekey_signature.try_into().map_err(|_| {
-                ProtobufError::InvalidFieldLength("signature", pb_curve_prekey_signature_len, 64)
-            })?;
-
         Ok(Self {
             identified_public_key: IdentifiedEllipticCurvePublicKey {
                 id: uuid_from_bytes(&pb_curve_prekey.uuid)?,
@@ -63,7 +70,8 @@ impl SignedCurvePrekey {
                     bytes: curve_prekey_public_key_bytes,
                 },
             },
-            signature: curve_prekey_signature_bytes,
+            // The signature length depends on the identity key type, so it is checked on verification
+            signature: pb_signed_curve_prekey.signature,
         })
     }
 }
@@ -56,19 +56,6 @@ impl SignedOneTimePqkemPrekeySet {
                         one_time_pqkem_prekey_public_key_type.public_key_length(),
                     ));
                 }
-                let pb_one_time_pqkem_prekey_signature_bytes = pb_one_time_pqkem_prekey.signature;
-                let pb_one_time_pqkem_prekey_signature_bytes_len =
-                    pb_one_time_pqkem_prekey_signature_bytes.len();
-                let one_time_pqkem_prekey_signature_bytes: [u8; 64] =
-                    pb_one_time_pqkem_prekey_signature_bytes
-                        .try_into()
-                        .map_err(|_| {
-                            ProtobufError::InvalidFieldLength(
-                                "one_time_pqkem_prekeys.signature",
-                                pb_one_time_pqkem_prekey_signature_bytes_len,
-                                64,
-                            )
-                        })?;
 
                 Ok(SignedPQKEMPrekey {
                     identified_public_key: IdentifiedPQKEMPublicKey {
@@ -78,7 +65,7 @@ impl SignedOneTimePqkemPrekeySet {
                             bytes: one_time_pqkem_prekey_public_key_bytes,
                         },
                     },
-                    signature: one_time_pqkem_prekey_signature_bytes,
+                    signature: pb_one_time_pqkem_prekey.signature,
                 })
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -1,19 +1,35 @@
 use crate::{
-    crypto::pqkem::{
-        enum_pqkem_type::PQKEMType,
-        keys::{IdentifiedPQKEMPublicKey, PQKEMPublicKey},
+    crypto::{
+        curve::{keys::EllipticCurvePublicKey, traits::EllipticCurveAlgorithm},
+        pqkem::{
+            enum_pqkem_type::PQKEMType,
+            keys::{IdentifiedPQKEMPublicKey, PQKEMPublicKey},
+        },
     },
-    errors::protobuf::ProtobufError,
+    errors::{protobuf::ProtobufError, xeddsa::XedDSAError},
     protobuf::{pqxdh::PbSignedPqkemPrekey, utils::uuid_from_bytes},
 };
 
 #[derive(Clone, Debug)]
 pub struct SignedPQKEMPrekey {
     pub identified_public_key: IdentifiedPQKEMPublicKey,
-    pub signature: [u8; 64],
+    pub signature: Vec<u8>,
 }
 
 impl SignedPQKEMPrekey {
+    // Verifies that the prekey was signed by the given identity key
+    pub fn verify<T: EllipticCurveAlgorithm>(
+        &self,
+        identity_key: &EllipticCurvePublicKey,
+        curve_type: &T,
+    ) -> Result<bool, XedDSAError> {
+        curve_type.xeddsa_verify(
+            identity_key,
+            &self.identified_public_key.public_key.encode_kem(),
+            &self.signature,
+        )
+    }
+
     pub fn to_protobuf(&self) -> PbSignedPqkemPrekey {
         PbSignedPqkemPrekey {
             signature: self.signature.as_slice().to_vec(),
@@ -46,13 +62,6 @@ impl SignedPQKEMPrekey {
             ));
         }
 
-        let pb_pqkem_prekey_signature = pb_signed_pqkem_prekey.signature;
-        let pb_pqkem_prekey_signature_len = pb_pqkem_prekey_signature.len();
-        let pqkem_prekey_signature_bytes: [u8; 64] =
-            pb_pqkem_prekey_signature.try_into().map_err(|_| {
-                ProtobufError::InvalidFieldLength("signature", pb_pqkem_prekey_signature_len, 64)
-            })?;
-
         Ok(Self {
             identified_public_key: IdentifiedPQKEMPublicKey {
                 id: uuid_from_bytes(&pb_identified_pqkem_public_key.uuid)?,
@@ -61,7 +70,8 @@ impl SignedPQKEMPrekey {
                     bytes: pqkem_prekey_bytes,
                 },
             },
-            signature: pqkem_prekey_signature_bytes,
+            // The signature length depends on the identity key type, so it is checked on verification
+            signature: pb_signed_pqkem_prekey.signature,
         })
     }
 }
@@ -5,3 +5,7 @@ pub mod curve {
 pub mod pqkem {
     include!(concat!(env!("OUT_DIR"), "/crypto.pqkem.rs"));
 }
+
+pub mod aead {
+    include!(concat!(env!("OUT_DIR"), "/crypto.aead.rs"));
+}
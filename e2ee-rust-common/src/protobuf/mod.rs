@@ -12,3 +12,11 @@ pub mod client {
 pub mod server {
     include!(concat!(env!("OUT_DIR"), "/server.rs"));
 }
+
+pub mod ratchet {
+    include!(concat!(env!("OUT_DIR"), "/ratchet.rs"));
+}
+
+pub mod fingerprint {
+    include!(concat!(env!("OUT_DIR"), "/fingerprint.rs"));
+}
@@ -5,19 +5,26 @@ use crate::{
     errors::protobuf::ProtobufError,
     messages::{
         client::{
+            ack_envelope::AckEnvelope,
+            authentication_response::AuthenticationResponse,
             client_hello::ClientHello,
             client_message::{ClientMessage, ClientMessageType},
             new_keys::NewKeys,
+            post_envelope::PostEnvelope,
             request_peer_bundle::RequestPeerBundle,
+            send_first_message::SendFirstMessage,
         },
         server::server_message::{ServerCommand, ServerError, ServerMessage, ServerMessageData},
     },
-    pqxdh::registration_bundle::RegistrationBundle,
+    pqxdh::{first_message::FirstMessage, registration_bundle::RegistrationBundle},
     protobuf::server::PbServerMessageData,
+    ratchet::message::RatchetMessage,
 };
 
 use super::{
     client::{pb_client_message, PbClientMessage},
+    pqxdh::PbFirstMessage,
+    ratchet::PbRatchetMessage,
     server::{pb_server_message, PbServerCommand, PbServerError, PbServerMessage},
 };
 
@@ -35,7 +42,7 @@ pub fn decode_client_message(data: &[u8]) -> Result<ClientMessage, ProtobufError
 
     let client_id = uuid_from_str(&pb_client_msg.client_id)?;
 
-    match pb_client_msg
+    let mut client_message = match pb_client_msg
         .message
         .ok_or(ProtobufError::MissingMessageType)?
     {
@@ -63,7 +70,35 @@ pub fn decode_client_message(data: &[u8]) -> Result<ClientMessage, ProtobufError
                 Some(RequestPeerBundle::from_protobuf(&pb_request_peer_bundle)?);
             Ok(client_message)
         }
-    }
+        pb_client_message::Message::SendFirstMessage(pb_send_first_message) => {
+            let mut client_message =
+                ClientMessage::new(ClientMessageType::SendFirstMessage, client_id);
+            client_message.send_first_message =
+                Some(SendFirstMessage::from_protobuf(pb_send_first_message)?);
+            Ok(client_message)
+        }
+        pb_client_message::Message::PostEnvelope(pb_post_envelope) => {
+            let mut client_message = ClientMessage::new(ClientMessageType::PostEnvelope, client_id);
+            client_message.post_envelope = Some(PostEnvelope::from_protobuf(pb_post_envelope)?);
+            Ok(client_message)
+        }
+        pb_client_message::Message::AckEnvelope(pb_ack_envelope) => {
+            let mut client_message = ClientMessage::new(ClientMessageType::AckEnvelope, client_id);
+            client_message.ack_envelope = Some(AckEnvelope::from_protobuf(&pb_ack_envelope)?);
+            Ok(client_message)
+        }
+        pb_client_message::Message::AuthenticationResponse(pb_authentication_response) => {
+            let mut client_message =
+                ClientMessage::new(ClientMessageType::AuthenticationResponse, client_id);
+            client_message.authentication_response = Some(AuthenticationResponse::from_protobuf(
+                &pb_authentication_response,
+            )?);
+            Ok(client_message)
+        }
+    }?;
+    client_message.session_token = pb_client_msg.session_token;
+
+    Ok(client_message)
 }
 
 pub fn decode_server_message(data: &[u8]) -> Result<ServerMessage, ProtobufError> {
@@ -106,3 +141,23 @@ pub fn create_client_message(client_message: &ClientMessage) -> Vec<u8> {
 pub fn create_server_message(server_message: &ServerMessage) -> Vec<u8> {
     server_message.to_protobuf().encode_to_vec()
 }
+
+pub fn create_first_message(first_message: &FirstMessage) -> Vec<u8> {
+    first_message.to_protobuf().encode_to_vec()
+}
+
+pub fn decode_first_message(data: &[u8]) -> Result<FirstMessage, ProtobufError> {
+    let pb_first_message: PbFirstMessage =
+        PbFirstMessage::decode(data).map_err(ProtobufError::DecodeError)?;
+    FirstMessage::from_protobuf(pb_first_message)
+}
+
+pub fn create_ratchet_message(ratchet_message: &RatchetMessage) -> Vec<u8> {
+    ratchet_message.to_protobuf().encode_to_vec()
+}
+
+pub fn decode_ratchet_message(data: &[u8]) -> Result<RatchetMessage, ProtobufError> {
+    let pb_ratchet_message: PbRatchetMessage =
+        PbRatchetMessage::decode(data).map_err(ProtobufError::DecodeError)?;
+    RatchetMessage::from_protobuf(&pb_ratchet_message)
+}
@@ -0,0 +1,12 @@
+// Maximum number of message keys that can be skipped in a single receiving chain
+pub const MAX_SKIP: u32 = 1000;
+
+// Maximum number of skipped message keys kept in a session, the oldest ones are dropped first
+pub const MAX_SKIPPED_MESSAGE_KEYS: usize = 2000;
+
+// HKDF info used to derive the root and chain keys after a Diffie-Hellman ratchet step
+pub const ROOT_KDF_INFO: &[u8] = b"DoubleRatchetRootKey";
+
+// HMAC inputs used to derive the message key and the next chain key from a chain key
+pub const MESSAGE_KEY_SEED: &[u8] = &[0x01];
+pub const CHAIN_KEY_SEED: &[u8] = &[0x02];
@@ -0,0 +1,94 @@
+use crate::{
+    crypto::{aead::enum_aead_types::AEADType, curve::keys::EllipticCurvePublicKey},
+    errors::protobuf::ProtobufError,
+    protobuf::ratchet::{PbRatchetHeader, PbRatchetMessage},
+};
+
+#[derive(Clone, Debug)]
+pub struct RatchetHeader {
+    pub ratchet_key: EllipticCurvePublicKey,
+    pub previous_chain_length: u32,
+    pub message_number: u32,
+}
+
+impl RatchetHeader {
+    // Encodes the header so that it can be authenticated as associated data
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded = self.ratchet_key.encode_ec();
+        encoded.extend_from_slice(&self.previous_chain_length.to_be_bytes());
+        encoded.extend_from_slice(&self.message_number.to_be_bytes());
+        encoded
+    }
+
+    pub fn to_protobuf(&self) -> PbRatchetHeader {
+        PbRatchetHeader {
+            ratchet_key: Some(self.ratchet_key.to_protobuf()),
+            previous_chain_length: self.previous_chain_length,
+            message_number: self.message_number,
+        }
+    }
+
+    pub fn from_protobuf(pb_ratchet_header: &PbRatchetHeader) -> Result<Self, ProtobufError> {
+        let pb_ratchet_key = pb_ratchet_header
+            .ratchet_key
+            .as_ref()
+            .ok_or(ProtobufError::MissingField("ratchet_key"))?;
+
+        Ok(Self {
+            ratchet_key: EllipticCurvePublicKey::from_protobuf(pb_ratchet_key)?,
+            previous_chain_length: pb_ratchet_header.previous_chain_length,
+            message_number: pb_ratchet_header.message_number,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RatchetMessage {
+    pub header: RatchetHeader,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub encryption_type: AEADType,
+}
+
+impl RatchetMessage {
+    pub fn to_protobuf(&self) -> PbRatchetMessage {
+        PbRatchetMessage {
+            header: Some(self.header.to_protobuf()),
+            nonce: self.nonce.clone(),
+            ciphertext: self.ciphertext.clone(),
+            encryption_type: self.encryption_type.to_protobuf().into(),
+        }
+    }
+
+    pub fn from_protobuf(pb_ratchet_message: &PbRatchetMessage) -> Result<Self, ProtobufError> {
+        let pb_header = pb_ratchet_message
+            .header
+            .as_ref()
+            .ok_or(ProtobufError::MissingField("header"))?;
+
+        // Encryption Type, Nonce and Ciphertext
+        let encryption_type = AEADType::from_id(pb_ratchet_message.encryption_type as u8)
+            .ok_or(ProtobufError::InvalidField("encryption_type"))?;
+        if pb_ratchet_message.nonce.len() != encryption_type.nonce_length() {
+            return Err(ProtobufError::InvalidFieldLength(
+                "nonce",
+                pb_ratchet_message.nonce.len(),
+                encryption_type.nonce_length(),
+            ));
+        }
+        if pb_ratchet_message.ciphertext.len() < encryption_type.tag_length() {
+            return Err(ProtobufError::InvalidFieldLength(
+                "ciphertext",
+                pb_ratchet_message.ciphertext.len(),
+                encryption_type.tag_length(),
+            ));
+        }
+
+        Ok(Self {
+            header: RatchetHeader::from_protobuf(pb_header)?,
+            nonce: pb_ratchet_message.nonce.clone(),
+            ciphertext: pb_ratchet_message.ciphertext.clone(),
+            encryption_type,
+        })
+    }
+}
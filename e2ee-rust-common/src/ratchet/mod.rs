@@ -0,0 +1,3 @@
+pub mod consts;
+pub mod message;
+pub mod session;
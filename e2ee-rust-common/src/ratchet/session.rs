@@ -0,0 +1,604 @@
+use log::debug;
+use rand::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{
+    crypto::{
+        aead::traits::AEADScheme,
+        curve::{
+            keys::{EllipticCurveKeyPair, EllipticCurvePublicKey},
+            traits::EllipticCurveAlgorithm,
+        },
+    },
+    errors::{aead::AEADError, ratchet::RatchetError},
+    hash::{
+        enum_hash_types::HashType,
+        hkdf::{hkdf, hmac},
+    },
+};
+
+use super::{
+    consts::{CHAIN_KEY_SEED, MAX_SKIP, MAX_SKIPPED_MESSAGE_KEYS, MESSAGE_KEY_SEED, ROOT_KDF_INFO},
+    message::{RatchetHeader, RatchetMessage},
+};
+
+// A message key derived for a message that has not been received yet
+#[derive(Zeroize, ZeroizeOnDrop, Clone)]
+pub struct SkippedMessageKey {
+    pub ratchet_key: Vec<u8>,
+    pub message_number: u32,
+    pub message_key: [u8; 32],
+}
+
+// State of a Double Ratchet session (see https://signal.org/docs/specifications/doubleratchet/#state-variables)
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct RatchetSession {
+    pub associated_data: Vec<u8>,
+    // Identity key the peer used in the key agreement, from which the safety number is derived
+    pub peer_identity_key: EllipticCurvePublicKey,
+    pub root_key: [u8; 32],
+    pub sending_ratchet_key: EllipticCurveKeyPair,
+    pub receiving_ratchet_key: Option<EllipticCurvePublicKey>,
+    pub sending_chain_key: Option<[u8; 32]>,
+    pub receiving_chain_key: Option<[u8; 32]>,
+    pub sending_message_number: u32,
+    pub receiving_message_number: u32,
+    pub previous_sending_chain_length: u32,
+    pub skipped_message_keys: Vec<SkippedMessageKey>,
+}
+
+// Decryption works on a copy of the session, which replaces it once the message is authenticated
+impl Clone for RatchetSession {
+    fn clone(&self) -> Self {
+        Self {
+            associated_data: self.associated_data.clone(),
+            peer_identity_key: self.peer_identity_key.clone(),
+            root_key: self.root_key,
+            sending_ratchet_key: self.sending_ratchet_key.duplicate(),
+            receiving_ratchet_key: self.receiving_ratchet_key.clone(),
+            sending_chain_key: self.sending_chain_key,
+            receiving_chain_key: self.receiving_chain_key,
+            sending_message_number: self.sending_message_number,
+            receiving_message_number: self.receiving_message_number,
+            previous_sending_chain_length: self.previous_sending_chain_length,
+            skipped_message_keys: self.skipped_message_keys.clone(),
+        }
+    }
+}
+
+impl RatchetSession {
+    // Creates the session of the PQXDH initiator, which can send right away
+    // The peer's signed curve prekey is used as its first ratchet key
+    pub fn new_initiator<T: EllipticCurveAlgorithm, R: RngCore + CryptoRng>(
+        shared_key: &[u8; 32],
+        associated_data: &[u8],
+        peer_identity_key: &EllipticCurvePublicKey,
+        peer_signed_curve_prekey: &EllipticCurvePublicKey,
+        curve_type: &T,
+        hash_type: &HashType,
+        rng: &mut R,
+    ) -> Result<Self, RatchetError> {
+        if peer_signed_curve_prekey.key_type != curve_type.get_type() {
+            return Err(RatchetError::KeyTypeMismatch);
+        }
+
+        let sending_ratchet_key = curve_type.generate_key_pair(rng);
+        let mut dh_output = curve_type
+            .dh(&sending_ratchet_key.private_key, peer_signed_curve_prekey)
+            .map_err(RatchetError::DiffieHellman)?;
+        let (root_key, sending_chain_key) = kdf_rk(shared_key, &dh_output, hash_type)?;
+        dh_output.zeroize();
+
+        Ok(Self {
+            associated_data: associated_data.to_vec(),
+            peer_identity_key: peer_identity_key.clone(),
+            root_key,
+            sending_ratchet_key,
+            receiving_ratchet_key: Some(peer_signed_curve_prekey.clone()),
+            sending_chain_key: Some(sending_chain_key),
+            receiving_chain_key: None,
+            sending_message_number: 0,
+            receiving_message_number: 0,
+            previous_sending_chain_length: 0,
+            skipped_message_keys: vec![],
+        })
+    }
+
+    // Creates the session of the PQXDH responder, which has to receive a message before sending
+    pub fn new_responder(
+        shared_key: &[u8; 32],
+        associated_data: &[u8],
+        peer_identity_key: &EllipticCurvePublicKey,
+        signed_curve_prekey: &EllipticCurveKeyPair,
+    ) -> Self {
+        Self {
+            associated_data: associated_data.to_vec(),
+            peer_identity_key: peer_identity_key.clone(),
+            root_key: *shared_key,
+            sending_ratchet_key: signed_curve_prekey.duplicate(),
+            receiving_ratchet_key: None,
+            sending_chain_key: None,
+            receiving_chain_key: None,
+            sending_message_number: 0,
+            receiving_message_number: 0,
+            previous_sending_chain_length: 0,
+            skipped_message_keys: vec![],
+        }
+    }
+
+    // Encrypts the plaintext with the next sending message key
+    // The session associated data is authenticated along with the message header
+    pub fn encrypt<V: AEADScheme<[u8; 32], N>, N: AsRef<[u8]>, R: RngCore + CryptoRng>(
+        &mut self,
+        plaintext: &[u8],
+        aead_type: &V,
+        hash_type: &HashType,
+        rng: &mut R,
+    ) -> Result<RatchetMessage, RatchetError> {
+        let chain_key = self
+            .sending_chain_key
+            .ok_or(RatchetError::MissingSendingChain)?;
+        let (next_chain_key, mut message_key) = kdf_ck(&chain_key, hash_type);
+
+        let header = RatchetHeader {
+            ratchet_key: self.sending_ratchet_key.public_key.clone(),
+            previous_chain_length: self.previous_sending_chain_length,
+            message_number: self.sending_message_number,
+        };
+
+        let mut full_associated_data = self.associated_data.clone();
+        full_associated_data.extend_from_slice(&header.encode());
+        let res = aead_type.encrypt(&message_key, plaintext, &full_associated_data, rng);
+        message_key.zeroize();
+        let (ciphertext, nonce) = res.map_err(RatchetError::AEAD)?;
+
+        // Only advance the chain once the message is encrypted
+        self.sending_chain_key = Some(next_chain_key);
+        self.sending_message_number += 1;
+
+        Ok(RatchetMessage {
+            header,
+            nonce: nonce.as_ref().to_vec(),
+            ciphertext,
+            encryption_type: aead_type.get_type(),
+        })
+    }
+
+    // Decrypts a received message, performing a Diffie-Hellman ratchet step if the peer's ratchet key changed
+    // The session is left untouched if the message cannot be decrypted
+    pub fn decrypt<
+        T: EllipticCurveAlgorithm,
+        V: AEADScheme<[u8; 32], N>,
+        N: AsRef<[u8]> + for<'a> TryFrom<&'a [u8]>,
+        R: RngCore + CryptoRng,
+    >(
+        &mut self,
+        message: &RatchetMessage,
+        curve_type: &T,
+        aead_type: &V,
+        hash_type: &HashType,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, RatchetError> {
+        if message.encryption_type != aead_type.get_type() {
+            return Err(RatchetError::AEADTypeMismatch);
+        }
+        let nonce: N = message
+            .nonce
+            .as_slice()
+            .try_into()
+            .map_err(|_| RatchetError::AEAD(AEADError::InvalidNonceSize))?;
+        let header = &message.header;
+        if header.ratchet_key.key_type != curve_type.get_type() {
+            return Err(RatchetError::KeyTypeMismatch);
+        }
+
+        let mut full_associated_data = self.associated_data.clone();
+        full_associated_data.extend_from_slice(&header.encode());
+
+        // Work on a copy of the state so that a failed decryption does not alter the session
+        let mut state = self.clone();
+
+        // Check if the message key was skipped previously
+        if let Some(position) = state.skipped_message_keys.iter().position(|k| {
+            k.ratchet_key == header.ratchet_key.bytes && k.message_number == header.message_number
+        }) {
+            debug!("Using skipped message key {}", header.message_number);
+            let skipped_message_key = state.skipped_message_keys.remove(position);
+            let plaintext = aead_type
+                .decrypt(
+                    &skipped_message_key.message_key,
+                    &message.ciphertext,
+                    &full_associated_data,
+                    &nonce,
+                )
+                .map_err(RatchetError::AEAD)?;
+            *self = state;
+            return Ok(plaintext);
+        }
+
+        // Perform a Diffie-Hellman ratchet step if the peer has a new ratchet key
+        if state
+            .receiving_ratchet_key
+            .as_ref()
+            .is_none_or(|k| k.bytes != header.ratchet_key.bytes)
+        {
+            state.skip_message_keys(header.previous_chain_length, hash_type)?;
+            state.dh_ratchet(&header.ratchet_key, curve_type, hash_type, rng)?;
+        }
+
+        // Derive the message key from the receiving chain
+        state.skip_message_keys(header.message_number, hash_type)?;
+        let chain_key = state
+            .receiving_chain_key
+            .ok_or(RatchetError::MissingReceivingChain)?;
+        let (next_chain_key, mut message_key) = kdf_ck(&chain_key, hash_type);
+        state.receiving_chain_key = Some(next_chain_key);
+        state.receiving_message_number += 1;
+
+        let res = aead_type.decrypt(
+            &message_key,
+            &message.ciphertext,
+            &full_associated_data,
+            &nonce,
+        );
+        message_key.zeroize();
+        let plaintext = res.map_err(RatchetError::AEAD)?;
+
+        *self = state;
+        Ok(plaintext)
+    }
+
+    // Stores the message keys of the current receiving chain up to the given message number
+    fn skip_message_keys(&mut self, until: u32, hash_type: &HashType) -> Result<(), RatchetError> {
+        if until > self.receiving_message_number.saturating_add(MAX_SKIP) {
+            return Err(RatchetError::TooManySkippedMessages);
+        }
+
+        if let (Some(mut chain_key), Some(ratchet_key)) =
+            (self.receiving_chain_key, &self.receiving_ratchet_key)
+        {
+            while self.receiving_message_number < until {
+                let (next_chain_key, message_key) = kdf_ck(&chain_key, hash_type);
+                self.skipped_message_keys.push(SkippedMessageKey {
+                    ratchet_key: ratchet_key.bytes.clone(),
+                    message_number: self.receiving_message_number,
+                    message_key,
+                });
+                chain_key = next_chain_key;
+                self.receiving_message_number += 1;
+            }
+            self.receiving_chain_key = Some(chain_key);
+        }
+
+        // Drop the oldest skipped message keys if there are too many
+        if self.skipped_message_keys.len() > MAX_SKIPPED_MESSAGE_KEYS {
+            let excess = self.skipped_message_keys.len() - MAX_SKIPPED_MESSAGE_KEYS;
+            self.skipped_message_keys.drain(..excess);
+        }
+
+        Ok(())
+    }
+
+    // Performs a Diffie-Hellman ratchet step with the peer's new ratchet key
+    fn dh_ratchet<T: EllipticCurveAlgorithm, R: RngCore + CryptoRng>(
+        &mut self,
+        peer_ratchet_key: &EllipticCurvePublicKey,
+        curve_type: &T,
+        hash_type: &HashType,
+        rng: &mut R,
+    ) -> Result<(), RatchetError> {
+        debug!("Performing a Diffie-Hellman ratchet step");
+        self.previous_sending_chain_length = self.sending_message_number;
+        self.sending_message_number = 0;
+        self.receiving_message_number = 0;
+        self.receiving_ratchet_key = Some(peer_ratchet_key.clone());
+
+        // Derive the new receiving chain
+        let mut dh_output = curve_type
+            .dh(&self.sending_ratchet_key.private_key, peer_ratchet_key)
+            .map_err(RatchetError::DiffieHellman)?;
+        let (root_key, receiving_chain_key) = kdf_rk(&self.root_key, &dh_output, hash_type)?;
+        self.root_key = root_key;
+        self.receiving_chain_key = Some(receiving_chain_key);
+
+        // Derive the new sending chain from a fresh ratchet key
+        self.sending_ratchet_key = curve_type.generate_key_pair(rng);
+        dh_output = curve_type
+            .dh(&self.sending_ratchet_key.private_key, peer_ratchet_key)
+            .map_err(RatchetError::DiffieHellman)?;
+        let (root_key, sending_chain_key) = kdf_rk(&self.root_key, &dh_output, hash_type)?;
+        self.root_key = root_key;
+        self.sending_chain_key = Some(sending_chain_key);
+        dh_output.zeroize();
+
+        Ok(())
+    }
+}
+
+// Derives the next root key and a chain key from the current root key and a Diffie-Hellman output
+fn kdf_rk(
+    root_key: &[u8; 32],
+    dh_output: &[u8],
+    hash_type: &HashType,
+) -> Result<([u8; 32], [u8; 32]), RatchetError> {
+    let mut okm = [0u8; 64];
+    hkdf(hash_type, root_key, dh_output, ROOT_KDF_INFO, &mut okm).map_err(RatchetError::Hash)?;
+
+    let mut next_root_key = [0u8; 32];
+    let mut chain_key = [0u8; 32];
+    next_root_key.copy_from_slice(&okm[..32]);
+    chain_key.copy_from_slice(&okm[32..]);
+    okm.zeroize();
+
+    Ok((next_root_key, chain_key))
+}
+
+// Derives the next chain key and a message key from the current chain key
+fn kdf_ck(chain_key: &[u8; 32], hash_type: &HashType) -> ([u8; 32], [u8; 32]) {
+    let mut next_chain_key_mac = hmac(hash_type, chain_key, CHAIN_KEY_SEED);
+    let mut message_key_mac = hmac(hash_type, chain_key, MESSAGE_KEY_SEED);
+
+    let mut next_chain_key = [0u8; 32];
+    let mut message_key = [0u8; 32];
+    next_chain_key.copy_from_slice(&next_chain_key_mac[..32]);
+    message_key.copy_from_slice(&message_key_mac[..32]);
+    next_chain_key_mac.zeroize();
+    message_key_mac.zeroize();
+
+    (next_chain_key, message_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{CryptoRng, RngCore};
+
+    use crate::{
+        crypto::{
+            aead::{aes256gcm::AES256GCM, chacha20poly1305::CHACHA20POLY1305},
+            curve::{curve25519::Curve25519, curve448::Curve448, traits::EllipticCurveAlgorithm},
+        },
+        errors::{aead::AEADError, protobuf::ProtobufError, ratchet::RatchetError},
+        hash::enum_hash_types::HashType,
+        ratchet::{consts::MAX_SKIP, message::RatchetMessage},
+    };
+
+    use super::RatchetSession;
+
+    const SHARED_KEY: [u8; 32] = [7u8; 32];
+    const ASSOCIATED_DATA: &[u8] = b"associated data";
+
+    // Creates the sessions of an initiator (Alice) and a responder (Bob) sharing the same key
+    fn sessions<T: EllipticCurveAlgorithm, R: RngCore + CryptoRng>(
+        curve: &T,
+        rng: &mut R,
+    ) -> (RatchetSession, RatchetSession) {
+        let alice_identity_key = curve.generate_key_pair(rng);
+        let bob_identity_key = curve.generate_key_pair(rng);
+        let signed_prekey = curve.generate_key_pair(rng);
+        let alice = RatchetSession::new_initiator(
+            &SHARED_KEY,
+            ASSOCIATED_DATA,
+            &bob_identity_key.public_key,
+            &signed_prekey.public_key,
+            curve,
+            &HashType::SHA256,
+            rng,
+        )
+        .unwrap();
+        let bob = RatchetSession::new_responder(
+            &SHARED_KEY,
+            ASSOCIATED_DATA,
+            &alice_identity_key.public_key,
+            &signed_prekey,
+        );
+        (alice, bob)
+    }
+
+    fn encrypt<R: RngCore + CryptoRng>(
+        session: &mut RatchetSession,
+        plaintext: &[u8],
+        rng: &mut R,
+    ) -> RatchetMessage {
+        session
+            .encrypt(plaintext, &AES256GCM {}, &HashType::SHA256, rng)
+            .unwrap()
+    }
+
+    fn decrypt<R: RngCore + CryptoRng>(
+        session: &mut RatchetSession,
+        message: &RatchetMessage,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, RatchetError> {
+        session.decrypt(
+            message,
+            &Curve25519 {},
+            &AES256GCM {},
+            &HashType::SHA256,
+            rng,
+        )
+    }
+
+    // Checks that two sessions are in the same state
+    fn assert_same_state(session: &RatchetSession, other: &RatchetSession) {
+        assert_eq!(session.root_key, other.root_key);
+        assert_eq!(session.sending_chain_key, other.sending_chain_key);
+        assert_eq!(session.receiving_chain_key, other.receiving_chain_key);
+        assert_eq!(
+            session.receiving_ratchet_key.as_ref().map(|k| &k.bytes),
+            other.receiving_ratchet_key.as_ref().map(|k| &k.bytes)
+        );
+        assert_eq!(session.sending_message_number, other.sending_message_number);
+        assert_eq!(
+            session.receiving_message_number,
+            other.receiving_message_number
+        );
+        assert_eq!(
+            session.skipped_message_keys.len(),
+            other.skipped_message_keys.len()
+        );
+    }
+
+    fn round_trip<T: EllipticCurveAlgorithm>(curve: &T) {
+        let mut rng = rand::thread_rng();
+        let (mut alice, mut bob) = sessions(curve, &mut rng);
+
+        let message = alice
+            .encrypt(b"hello bob", &AES256GCM {}, &HashType::SHA256, &mut rng)
+            .unwrap();
+        assert_eq!(
+            bob.decrypt(&message, curve, &AES256GCM {}, &HashType::SHA256, &mut rng)
+                .unwrap(),
+            b"hello bob"
+        );
+
+        let reply = bob
+            .encrypt(b"hello alice", &AES256GCM {}, &HashType::SHA256, &mut rng)
+            .unwrap();
+        assert_eq!(
+            alice
+                .decrypt(&reply, curve, &AES256GCM {}, &HashType::SHA256, &mut rng)
+                .unwrap(),
+            b"hello alice"
+        );
+    }
+
+    #[test]
+    fn ratchet_round_trip() {
+        round_trip(&Curve25519 {});
+        round_trip(&Curve448 {});
+    }
+
+    #[test]
+    fn out_of_order_messages_are_decrypted() {
+        let mut rng = rand::thread_rng();
+        let (mut alice, mut bob) = sessions(&Curve25519 {}, &mut rng);
+
+        let messages: Vec<RatchetMessage> = (0..3)
+            .map(|i| encrypt(&mut alice, &[i], &mut rng))
+            .collect();
+
+        // The last message is received first, the keys of the two others are kept
+        assert_eq!(decrypt(&mut bob, &messages[2], &mut rng).unwrap(), [2]);
+        assert_eq!(bob.skipped_message_keys.len(), 2);
+        assert_eq!(decrypt(&mut bob, &messages[0], &mut rng).unwrap(), [0]);
+        assert_eq!(decrypt(&mut bob, &messages[1], &mut rng).unwrap(), [1]);
+        assert!(bob.skipped_message_keys.is_empty());
+
+        // Messages of a previous sending chain are still decrypted after a ratchet step
+        let reply = encrypt(&mut bob, b"reply", &mut rng);
+        let late_message = encrypt(&mut alice, b"late", &mut rng);
+        decrypt(&mut alice, &reply, &mut rng).unwrap();
+        let new_chain_message = encrypt(&mut alice, b"new chain", &mut rng);
+        assert_eq!(
+            decrypt(&mut bob, &new_chain_message, &mut rng).unwrap(),
+            b"new chain"
+        );
+        assert_eq!(decrypt(&mut bob, &late_message, &mut rng).unwrap(), b"late");
+    }
+
+    #[test]
+    fn skipped_message_key_is_used_once() {
+        let mut rng = rand::thread_rng();
+        let (mut alice, mut bob) = sessions(&Curve25519 {}, &mut rng);
+
+        let first_message = encrypt(&mut alice, b"first", &mut rng);
+        let second_message = encrypt(&mut alice, b"second", &mut rng);
+        decrypt(&mut bob, &second_message, &mut rng).unwrap();
+        decrypt(&mut bob, &first_message, &mut rng).unwrap();
+
+        // Replaying a message whose skipped key was used must fail without altering the session
+        let state = bob.clone();
+        assert!(matches!(
+            decrypt(&mut bob, &first_message, &mut rng),
+            Err(RatchetError::AEAD(AEADError::AuthenticationFailed))
+        ));
+        assert_same_state(&bob, &state);
+
+        // The session still decrypts the messages that follow
+        let third_message = encrypt(&mut alice, b"third", &mut rng);
+        assert_eq!(
+            decrypt(&mut bob, &third_message, &mut rng).unwrap(),
+            b"third"
+        );
+    }
+
+    #[test]
+    fn too_many_skipped_messages_are_rejected() {
+        let mut rng = rand::thread_rng();
+        let (mut alice, mut bob) = sessions(&Curve25519 {}, &mut rng);
+
+        let messages: Vec<RatchetMessage> = (0..MAX_SKIP + 2)
+            .map(|_| encrypt(&mut alice, b"message", &mut rng))
+            .collect();
+
+        // Skipping more than MAX_SKIP messages is rejected without altering the session
+        let state = bob.clone();
+        assert!(matches!(
+            decrypt(&mut bob, &messages[MAX_SKIP as usize + 1], &mut rng),
+            Err(RatchetError::TooManySkippedMessages)
+        ));
+        assert_same_state(&bob, &state);
+
+        // Skipping exactly MAX_SKIP messages is allowed
+        decrypt(&mut bob, &messages[MAX_SKIP as usize], &mut rng).unwrap();
+        assert_eq!(bob.skipped_message_keys.len(), MAX_SKIP as usize);
+    }
+
+    #[test]
+    fn failed_decryption_leaves_session_unchanged() {
+        let mut rng = rand::thread_rng();
+        let (mut alice, mut bob) = sessions(&Curve25519 {}, &mut rng);
+
+        let message = encrypt(&mut alice, b"hello bob", &mut rng);
+        let mut tampered_message = message.clone();
+        tampered_message.ciphertext[0] ^= 1;
+
+        // The tampered message would trigger a ratchet step if it was accepted
+        let state = bob.clone();
+        assert!(matches!(
+            decrypt(&mut bob, &tampered_message, &mut rng),
+            Err(RatchetError::AEAD(AEADError::AuthenticationFailed))
+        ));
+        assert_same_state(&bob, &state);
+
+        // A message encrypted with another AEAD is rejected as well
+        assert!(matches!(
+            bob.decrypt(
+                &message,
+                &Curve25519 {},
+                &CHACHA20POLY1305 {},
+                &HashType::SHA256,
+                &mut rng
+            ),
+            Err(RatchetError::AEADTypeMismatch)
+        ));
+        assert_same_state(&bob, &state);
+
+        assert_eq!(decrypt(&mut bob, &message, &mut rng).unwrap(), b"hello bob");
+    }
+
+    #[test]
+    fn ratchet_message_protobuf_lengths_are_checked() {
+        let mut rng = rand::thread_rng();
+        let (mut alice, _) = sessions(&Curve25519 {}, &mut rng);
+        let message = encrypt(&mut alice, b"", &mut rng);
+
+        let decoded = RatchetMessage::from_protobuf(&message.to_protobuf()).unwrap();
+        assert_eq!(decoded.nonce, message.nonce);
+        assert_eq!(decoded.ciphertext, message.ciphertext);
+
+        let mut pb_message = message.to_protobuf();
+        pb_message.nonce.pop();
+        assert!(matches!(
+            RatchetMessage::from_protobuf(&pb_message),
+            Err(ProtobufError::InvalidFieldLength("nonce", 11, 12))
+        ));
+
+        let mut pb_message = message.to_protobuf();
+        pb_message.ciphertext.pop();
+        assert!(matches!(
+            RatchetMessage::from_protobuf(&pb_message),
+            Err(ProtobufError::InvalidFieldLength("ciphertext", 15, 16))
+        ));
+    }
+}
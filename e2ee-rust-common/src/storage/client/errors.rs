@@ -1,4 +1,6 @@
 #[derive(Debug, Clone)]
 pub enum ClientStorageError {
     ClientNotFound,
+    PrekeyNotFound,
+    SessionNotFound,
 }
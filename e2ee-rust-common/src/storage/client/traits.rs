@@ -3,6 +3,7 @@ use uuid::Uuid;
 use crate::{
     crypto::{curve::keys::IdentifiedEllipticCurveKeyPair, pqkem::keys::IdentifiedPQKEMKeyPair},
     pqxdh::private_bundle::PrivateBundle,
+    ratchet::session::RatchetSession,
     storage::errors::StorageInterfaceError,
 };
 
@@ -49,4 +50,32 @@ pub trait ClientStorage {
         &self,
         new_signed_pqkem_prekeys: &Vec<IdentifiedPQKEMKeyPair>,
     ) -> Result<(), StorageInterfaceError>;
+
+    // Removes a curve one time prekey, identified by its UUID
+    fn remove_curve_one_time_prekey(&self, prekey_id: &Uuid) -> Result<(), StorageInterfaceError>;
+
+    // Removes a signed one time PQKEM prekey, identified by its UUID
+    fn remove_pqkem_one_time_prekey(&self, prekey_id: &Uuid) -> Result<(), StorageInterfaceError>;
+
+    // Removes the one time prekeys used by a peer in a single transaction, so that none of them is kept if one cannot be removed
+    fn remove_one_time_prekeys(
+        &self,
+        curve_prekey_id: Option<&Uuid>,
+        pqkem_prekey_id: Option<&Uuid>,
+    ) -> Result<(), StorageInterfaceError>;
+
+    // Fetches the ratchet session established with a peer, if any
+    fn load_session(&self, peer_id: &Uuid)
+        -> Result<Option<RatchetSession>, StorageInterfaceError>;
+
+    // Stores the ratchet session established with a peer, replacing the previous one
+    // The session and its skipped message keys are written atomically, so that a message key is never reused after a crash
+    fn store_session(
+        &self,
+        peer_id: &Uuid,
+        session: &RatchetSession,
+    ) -> Result<(), StorageInterfaceError>;
+
+    // Deletes the ratchet session established with a peer
+    fn delete_session(&self, peer_id: &Uuid) -> Result<(), StorageInterfaceError>;
 }
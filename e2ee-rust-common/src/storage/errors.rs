@@ -23,6 +23,7 @@ pub enum InitializationError {
     CannotCreateSchema,
     NoSchemaVersion,
     IncompatibleSchemaVersion(i32, i32),
+    CannotMigrateSchema(i32),
     CannotCreateConnection,
 }
 
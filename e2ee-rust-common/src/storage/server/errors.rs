@@ -9,4 +9,5 @@ pub enum ServerStorageError {
     PQKEMPublicKeyNotFound,
     IdentifiedPQKEMPublicKeyNotFound,
     SignedPQKEMPrekeyNotFound,
+    EnvelopeNotFound,
 }
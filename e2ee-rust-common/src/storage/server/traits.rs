@@ -3,6 +3,7 @@ use uuid::Uuid;
 
 use crate::{
     crypto::curve::keys::IdentifiedEllipticCurvePublicKey,
+    messages::server::server_envelope::ServerEnvelope,
     pqxdh::{
         one_time_curve_prekey_set::OneTimeCurvePrekeySet, signed_curve_prekey::SignedCurvePrekey,
         signed_one_time_pqkem_prekey_set::SignedOneTimePqkemPrekeySet,
@@ -73,4 +74,32 @@ pub trait ServerStorage {
         &self,
         client_id: Uuid,
     ) -> Result<Option<SignedPQKEMPrekey>, StorageInterfaceError>;
+
+    // Adds an envelope to a client's mailbox
+    // Returns a ClientNotFound error if the recipient is not registered
+    fn enqueue_envelope(
+        &self,
+        recipient_id: Uuid,
+        envelope: &ServerEnvelope,
+    ) -> Result<(), StorageInterfaceError>;
+
+    // Lists the envelopes waiting in a client's mailbox, oldest first
+    fn get_envelopes(
+        &self,
+        recipient_id: Uuid,
+    ) -> Result<Vec<ServerEnvelope>, StorageInterfaceError>;
+
+    // Fetches the oldest envelope waiting in a client's mailbox, if any
+    fn get_next_envelope(
+        &self,
+        recipient_id: Uuid,
+    ) -> Result<Option<ServerEnvelope>, StorageInterfaceError>;
+
+    // Removes an envelope from a client's mailbox
+    // Returns an EnvelopeNotFound error if the envelope is not in the client's mailbox
+    fn delete_envelope(
+        &self,
+        recipient_id: Uuid,
+        envelope_id: &Uuid,
+    ) -> Result<(), StorageInterfaceError>;
 }
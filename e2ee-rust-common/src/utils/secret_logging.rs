@@ -0,0 +1,70 @@
+use sha2::{Digest, Sha256};
+
+use super::display::print_slice;
+
+// Domain separation for the fingerprints, so that they cannot be confused with any other hash of the secret
+const FINGERPRINT_PREFIX: &[u8] = b"e2ee-rust-log-fingerprint";
+const FINGERPRINT_LENGTH: usize = 4;
+
+// Formats a secret for the logs
+// Only its length and a short fingerprint are shown, enough to check that both peers derived the same value
+// The raw bytes are only printed when built with the `insecure-debug-secrets` feature
+pub fn print_secret(secret: &[u8]) -> String {
+    if cfg!(feature = "insecure-debug-secrets") {
+        format!("{} (INSECURE)", print_slice(secret))
+    } else {
+        format!(
+            "<redacted {} bytes, fingerprint {}>",
+            secret.len(),
+            fingerprint(secret)
+        )
+    }
+}
+
+// Truncated hash of a secret, which does not reveal anything usable about a high entropy secret
+fn fingerprint(secret: &[u8]) -> String {
+    let hash = Sha256::new()
+        .chain_update(FINGERPRINT_PREFIX)
+        .chain_update(secret)
+        .finalize();
+
+    let mut s = String::from("0x");
+    for byte in &hash[..FINGERPRINT_LENGTH] {
+        s.push_str(&format!("{:02x}", byte));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::print_secret;
+    use crate::utils::display::print_slice;
+
+    const SECRET: [u8; 32] = [0x5a; 32];
+
+    #[test]
+    #[cfg(not(feature = "insecure-debug-secrets"))]
+    fn secrets_are_printed_as_fingerprints() {
+        let output = print_secret(&SECRET);
+        assert!(output.starts_with("<redacted 32 bytes, fingerprint 0x"));
+        assert!(!output.contains("5a5a"));
+        assert!(!output.contains(&print_slice(&SECRET)));
+
+        // 4 bytes of fingerprint, which only depend on the secret
+        let fingerprint = output
+            .trim_start_matches("<redacted 32 bytes, fingerprint 0x")
+            .trim_end_matches('>');
+        assert_eq!(fingerprint.len(), 8);
+        assert_eq!(output, print_secret(&SECRET));
+        assert_ne!(output, print_secret(&[0x5b; 32]));
+    }
+
+    #[test]
+    #[cfg(feature = "insecure-debug-secrets")]
+    fn secrets_are_printed_with_the_insecure_feature() {
+        assert_eq!(
+            print_secret(&SECRET),
+            format!("{} (INSECURE)", print_slice(&SECRET))
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use e2ee_rust_common::messages::server::server_authentication_challenge::ServerAuthenticationChallenge;
+use log::debug;
+use rand::{CryptoRng, RngCore};
+use uuid::Uuid;
+
+use crate::{AUTHENTICATION_CHALLENGE_LIFETIME_SECS, AUTHENTICATION_SESSION_LIFETIME_SECS};
+
+struct PendingChallenge {
+    challenge: ServerAuthenticationChallenge,
+    issued_at: Instant,
+}
+
+struct AuthenticatedSession {
+    authenticated_at: Instant,
+}
+
+// Client UUID and session token sent with the challenge
+type SessionKey = (Uuid, Vec<u8>);
+
+#[derive(Default)]
+struct AuthenticatorState {
+    pending_challenges: HashMap<SessionKey, PendingChallenge>,
+    sessions: HashMap<SessionKey, AuthenticatedSession>,
+}
+
+// Keeps track of the challenges sent to the clients and of the clients that answered them
+// Challenges and sessions are bound to the session token of the challenge rather than to the ZMQ routing identity, which the client
+// chooses: another connection reusing the identity cannot use a session without its token, and cannot end it
+// The state is shared by all the workers, a client may be served by a different worker at each request
+#[derive(Default)]
+pub struct Authenticator {
+    state: Mutex<AuthenticatorState>,
+}
+
+impl Authenticator {
+    // A worker that panicked while holding the lock cannot leave the maps half updated, so the state is still usable
+    fn state(&self) -> MutexGuard<'_, AuthenticatorState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Creates a new challenge for the client, with a new session token
+    // The client UUID is not authenticated yet, so the current sessions and challenges are left untouched
+    pub fn issue_challenge<R: RngCore + CryptoRng>(
+        &self,
+        client_id: Uuid,
+        rng: &mut R,
+    ) -> ServerAuthenticationChallenge {
+        let mut state = self.state();
+        state.remove_expired();
+
+        let challenge = ServerAuthenticationChallenge::new(rng);
+        state.pending_challenges.insert(
+            (client_id, challenge.session_token.clone()),
+            PendingChallenge {
+                challenge: challenge.clone(),
+                issued_at: Instant::now(),
+            },
+        );
+        debug!("Issued authentication challenge to client {}", client_id);
+
+        challenge
+    }
+
+    // Removes and returns the challenge pending for the client under the session token, if it has not expired
+    // A challenge can only be answered once, whatever the outcome
+    pub fn take_challenge(
+        &self,
+        client_id: Uuid,
+        session_token: &[u8],
+    ) -> Option<ServerAuthenticationChallenge> {
+        let pending = self
+            .state()
+            .pending_challenges
+            .remove(&(client_id, session_token.to_vec()))?;
+        if pending.issued_at.elapsed() > Duration::from_secs(AUTHENTICATION_CHALLENGE_LIFETIME_SECS)
+        {
+            return None;
+        }
+
+        Some(pending.challenge)
+    }
+
+    pub fn authenticate(&self, client_id: Uuid, session_token: &[u8]) {
+        debug!("Client {} is authenticated", client_id);
+        self.state().sessions.insert(
+            (client_id, session_token.to_vec()),
+            AuthenticatedSession {
+                authenticated_at: Instant::now(),
+            },
+        );
+    }
+
+    pub fn is_authenticated(&self, client_id: Uuid, session_token: &[u8]) -> bool {
+        match self
+            .state()
+            .sessions
+            .get(&(client_id, session_token.to_vec()))
+        {
+            Some(session) => {
+                session.authenticated_at.elapsed()
+                    <= Duration::from_secs(AUTHENTICATION_SESSION_LIFETIME_SECS)
+            }
+            None => false,
+        }
+    }
+}
+
+impl AuthenticatorState {
+    // Drops the challenges and sessions that expired, so that abandoned ones do not pile up
+    fn remove_expired(&mut self) {
+        self.pending_challenges.retain(|_, pending| {
+            pending.issued_at.elapsed()
+                <= Duration::from_secs(AUTHENTICATION_CHALLENGE_LIFETIME_SECS)
+        });
+        self.sessions.retain(|_, session| {
+            session.authenticated_at.elapsed()
+                <= Duration::from_secs(AUTHENTICATION_SESSION_LIFETIME_SECS)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::Authenticator;
+
+    #[test]
+    fn session_is_bound_to_its_token() {
+        let authenticator = Authenticator::default();
+        let mut rng = rand::thread_rng();
+        let client_id = Uuid::new_v4();
+
+        let challenge = authenticator.issue_challenge(client_id, &mut rng);
+        let session_token = &challenge.session_token;
+        assert!(authenticator
+            .take_challenge(client_id, session_token)
+            .is_some());
+        authenticator.authenticate(client_id, session_token);
+        assert!(authenticator.is_authenticated(client_id, session_token));
+
+        // A connection reusing the ZMQ identity of the client, once it disconnected, only has the client UUID
+        assert!(!authenticator.is_authenticated(client_id, &[]));
+
+        // Its hello gets a challenge with another token, which leaves the session untouched
+        let other_challenge = authenticator.issue_challenge(client_id, &mut rng);
+        assert_ne!(&other_challenge.session_token, session_token);
+        assert!(!authenticator.is_authenticated(client_id, &other_challenge.session_token));
+        assert!(authenticator.is_authenticated(client_id, session_token));
+
+        // The token does not authenticate another client
+        assert!(!authenticator.is_authenticated(Uuid::new_v4(), session_token));
+    }
+
+    #[test]
+    fn challenge_is_bound_to_its_token() {
+        let authenticator = Authenticator::default();
+        let mut rng = rand::thread_rng();
+        let client_id = Uuid::new_v4();
+
+        // The challenge of the client is not replaced by the one sent to another connection
+        let challenge = authenticator.issue_challenge(client_id, &mut rng);
+        authenticator.issue_challenge(client_id, &mut rng);
+        let taken = authenticator
+            .take_challenge(client_id, &challenge.session_token)
+            .unwrap();
+        assert_eq!(
+            taken.signed_message(client_id),
+            challenge.signed_message(client_id)
+        );
+
+        // A challenge can only be answered once
+        assert!(authenticator
+            .take_challenge(client_id, &challenge.session_token)
+            .is_none());
+    }
+}
@@ -0,0 +1,438 @@
+use std::{fs, path::Path};
+
+use e2ee_rust_common::errors::config::ConfigError;
+use log::{info, warn};
+use serde::Deserialize;
+
+// Config file read when neither --config nor E2EE_SERVER_CONFIG is given, if it exists
+const DEFAULT_CONFIG_FILE: &str = "./server.toml";
+const CONFIG_FILE_ENV: &str = "E2EE_SERVER_CONFIG";
+
+// Every option can be overridden by E2EE_SERVER_<OPTION> in the environment and by --<option> on the command line
+const ENV_PREFIX: &str = "E2EE_SERVER_";
+const OPTIONS: [&str; 9] = [
+    "endpoint",
+    "workers",
+    "storage_name",
+    "storage_path",
+    "curve_signed_prekey_lifetime_secs",
+    "pqkem_last_resort_signed_prekey_lifetime_secs",
+    "curve_one_time_prekeys_threshold",
+    "pqkem_one_time_prekeys_threshold",
+    "allow_insecure_transport",
+];
+
+// Below this, signed prekeys are rotated so often that the setting is most likely a leftover from testing
+const MIN_RECOMMENDED_PREKEY_LIFETIME_SECS: u64 = 60 * 60;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub endpoint: String,
+    pub workers: usize,
+    pub storage_name: String,
+    pub storage_path: String,
+    pub curve_signed_prekey_lifetime_secs: u64,
+    pub pqkem_last_resort_signed_prekey_lifetime_secs: u64,
+    pub curve_one_time_prekeys_threshold: usize,
+    pub pqkem_one_time_prekeys_threshold: usize,
+    // Lets the server start in cleartext when libzmq cannot encrypt the connections
+    pub allow_insecure_transport: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "tcp://*:5555".to_string(),
+            workers: 4,
+            storage_name: "test-server".to_string(),
+            storage_path: "./".to_string(),
+            curve_signed_prekey_lifetime_secs: 60 * 60 * 24 * 7,
+            pqkem_last_resort_signed_prekey_lifetime_secs: 60 * 60 * 24 * 7,
+            curve_one_time_prekeys_threshold: 5,
+            pqkem_one_time_prekeys_threshold: 5,
+            allow_insecure_transport: false,
+        }
+    }
+}
+
+impl ServerConfig {
+    // Builds the configuration from, by increasing priority: the defaults, the config file, the environment and the command line
+    pub fn load() -> Result<Self, ConfigError> {
+        let arguments = parse_arguments(std::env::args().skip(1))?;
+
+        // Read the config file
+        let mut config = match arguments
+            .config_file
+            .or_else(|| std::env::var(CONFIG_FILE_ENV).ok())
+        {
+            Some(config_file) => Self::from_file(&config_file)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Self::from_file(DEFAULT_CONFIG_FILE)?
+            }
+            None => Self::default(),
+        };
+
+        // Apply the environment overrides
+        for option in OPTIONS {
+            let variable = format!("{}{}", ENV_PREFIX, option.to_uppercase());
+            if let Ok(value) = std::env::var(&variable) {
+                config.set(option, &value)?;
+            }
+        }
+
+        // Apply the command line overrides
+        for (option, value) in arguments.overrides {
+            config.set(&option, &value)?;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn from_file(config_file: &str) -> Result<Self, ConfigError> {
+        info!("Reading configuration from {}", config_file);
+        let content = fs::read_to_string(config_file)
+            .map_err(|e| ConfigError::CannotReadFile(format!("{}: {}", config_file, e)))?;
+
+        toml::from_str(&content)
+            .map_err(|e| ConfigError::InvalidFile(format!("{}: {}", config_file, e)))
+    }
+
+    fn set(&mut self, option: &str, value: &str) -> Result<(), ConfigError> {
+        match option {
+            "endpoint" => self.endpoint = value.to_string(),
+            "workers" => self.workers = parse_value("workers", value)?,
+            "storage_name" => self.storage_name = value.to_string(),
+            "storage_path" => self.storage_path = value.to_string(),
+            "curve_signed_prekey_lifetime_secs" => {
+                self.curve_signed_prekey_lifetime_secs =
+                    parse_value("curve_signed_prekey_lifetime_secs", value)?
+            }
+            "pqkem_last_resort_signed_prekey_lifetime_secs" => {
+                self.pqkem_last_resort_signed_prekey_lifetime_secs =
+                    parse_value("pqkem_last_resort_signed_prekey_lifetime_secs", value)?
+            }
+            "curve_one_time_prekeys_threshold" => {
+                self.curve_one_time_prekeys_threshold =
+                    parse_value("curve_one_time_prekeys_threshold", value)?
+            }
+            "pqkem_one_time_prekeys_threshold" => {
+                self.pqkem_one_time_prekeys_threshold =
+                    parse_value("pqkem_one_time_prekeys_threshold", value)?
+            }
+            "allow_insecure_transport" => {
+                self.allow_insecure_transport = parse_value("allow_insecure_transport", value)?
+            }
+            _ => return Err(ConfigError::UnknownOption(option.to_string())),
+        }
+
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        // The server socket is reachable from the clients, so only network and IPC transports make sense
+        if !["tcp://", "ipc://"]
+            .iter()
+            .any(|transport| self.endpoint.starts_with(transport))
+        {
+            return Err(ConfigError::InvalidValue("endpoint", self.endpoint.clone()));
+        }
+
+        // Requests are only handled by the workers
+        if self.workers == 0 {
+            return Err(ConfigError::InvalidValue(
+                "workers",
+                self.workers.to_string(),
+            ));
+        }
+
+        // The storage name is used in a file name
+        if self.storage_name.is_empty()
+            || !self
+                .storage_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ConfigError::InvalidValue(
+                "storage_name",
+                self.storage_name.clone(),
+            ));
+        }
+        if !Path::new(&self.storage_path).is_dir() {
+            return Err(ConfigError::InvalidValue(
+                "storage_path",
+                self.storage_path.clone(),
+            ));
+        }
+
+        // Signed prekeys must live for a while, and clients must be asked for new one time prekeys before they run out
+        let lifetimes = [
+            (
+                "curve_signed_prekey_lifetime_secs",
+                self.curve_signed_prekey_lifetime_secs,
+            ),
+            (
+                "pqkem_last_resort_signed_prekey_lifetime_secs",
+                self.pqkem_last_resort_signed_prekey_lifetime_secs,
+            ),
+        ];
+        for (option, lifetime) in lifetimes {
+            if lifetime == 0 {
+                return Err(ConfigError::InvalidValue(option, lifetime.to_string()));
+            }
+            if lifetime < MIN_RECOMMENDED_PREKEY_LIFETIME_SECS {
+                warn!(
+                    "{} is only {} seconds, clients will rotate their signed prekeys very often",
+                    option, lifetime
+                );
+            }
+        }
+        let thresholds = [
+            (
+                "curve_one_time_prekeys_threshold",
+                self.curve_one_time_prekeys_threshold,
+            ),
+            (
+                "pqkem_one_time_prekeys_threshold",
+                self.pqkem_one_time_prekeys_threshold,
+            ),
+        ];
+        for (option, threshold) in thresholds {
+            if threshold == 0 {
+                return Err(ConfigError::InvalidValue(option, threshold.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Path of a file stored next to the server database
+    pub fn storage_file(&self, file_name: &str) -> String {
+        Path::new(&self.storage_path)
+            .join(file_name)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+// Config file and option overrides given on the command line
+struct Arguments {
+    config_file: Option<String>,
+    overrides: Vec<(String, String)>,
+}
+
+// Options are written --option-name value or --option-name=value
+fn parse_arguments(mut args: impl Iterator<Item = String>) -> Result<Arguments, ConfigError> {
+    let mut config_file = None;
+    let mut overrides = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            print_usage();
+            std::process::exit(0);
+        }
+
+        let Some(option) = arg.strip_prefix("--") else {
+            return Err(ConfigError::UnknownOption(arg));
+        };
+        let (option, value) = match option.split_once('=') {
+            Some((option, value)) => (option.to_string(), value.to_string()),
+            None => {
+                let value = args
+                    .next()
+                    .ok_or(ConfigError::MissingOptionValue(arg.clone()))?;
+                (option.to_string(), value)
+            }
+        };
+
+        let option = option.replace('-', "_");
+        if option == "config" {
+            config_file = Some(value);
+        } else if OPTIONS.contains(&option.as_str()) {
+            overrides.push((option, value));
+        } else {
+            return Err(ConfigError::UnknownOption(arg));
+        }
+    }
+
+    Ok(Arguments {
+        config_file,
+        overrides,
+    })
+}
+
+fn parse_value<T: std::str::FromStr>(option: &'static str, value: &str) -> Result<T, ConfigError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| ConfigError::InvalidValue(option, value.to_string()))
+}
+
+fn print_usage() {
+    println!("Usage: e2ee-rust-server [--config <file>] [--<option> <value>]...");
+    println!();
+    println!(
+        "The configuration is read from {} (or {}), then overridden by the environment and the command line",
+        DEFAULT_CONFIG_FILE, CONFIG_FILE_ENV
+    );
+    println!();
+    println!("Options (environment variable in brackets):");
+    for option in OPTIONS {
+        println!(
+            "  --{:<48} [{}{}]",
+            option.replace('_', "-"),
+            ENV_PREFIX,
+            option.to_uppercase()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use e2ee_rust_common::errors::config::ConfigError;
+
+    use super::{parse_arguments, Arguments, ServerConfig};
+
+    fn parse(args: &[&str]) -> Result<Arguments, ConfigError> {
+        parse_arguments(args.iter().map(|arg| arg.to_string()))
+    }
+
+    // Checks that the configuration is rejected because of the given option
+    fn assert_rejected(config: &ServerConfig, option: &str) {
+        match config.validate() {
+            Err(ConfigError::InvalidValue(rejected, _)) => assert_eq!(rejected, option),
+            other => panic!("{} is not rejected: {:?}", option, other),
+        }
+    }
+
+    #[test]
+    fn options_are_parsed() {
+        let arguments = parse(&[
+            "--workers",
+            "8",
+            "--storage-name=server",
+            "--config",
+            "server.toml",
+            "--endpoint=tcp://*:6000",
+        ])
+        .unwrap();
+
+        assert_eq!(arguments.config_file.as_deref(), Some("server.toml"));
+        assert_eq!(
+            arguments.overrides,
+            vec![
+                ("workers".to_string(), "8".to_string()),
+                ("storage_name".to_string(), "server".to_string()),
+                ("endpoint".to_string(), "tcp://*:6000".to_string()),
+            ]
+        );
+
+        let mut config = ServerConfig::default();
+        for (option, value) in arguments.overrides {
+            config.set(&option, &value).unwrap();
+        }
+        assert_eq!(config.workers, 8);
+        assert_eq!(config.storage_name, "server");
+        assert_eq!(config.endpoint, "tcp://*:6000");
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        assert!(matches!(
+            parse(&["--unknown", "1"]),
+            Err(ConfigError::UnknownOption(option)) if option == "--unknown"
+        ));
+        assert!(matches!(
+            parse(&["workers"]),
+            Err(ConfigError::UnknownOption(option)) if option == "workers"
+        ));
+        assert!(matches!(
+            ServerConfig::default().set("unknown", "1"),
+            Err(ConfigError::UnknownOption(option)) if option == "unknown"
+        ));
+    }
+
+    #[test]
+    fn missing_value_is_rejected() {
+        assert!(matches!(
+            parse(&["--endpoint=tcp://*:6000", "--workers"]),
+            Err(ConfigError::MissingOptionValue(option)) if option == "--workers"
+        ));
+    }
+
+    #[test]
+    fn invalid_numbers_are_rejected() {
+        let mut config = ServerConfig::default();
+        assert!(matches!(
+            config.set("workers", "four"),
+            Err(ConfigError::InvalidValue("workers", _))
+        ));
+        assert!(matches!(
+            config.set("curve_one_time_prekeys_threshold", "-1"),
+            Err(ConfigError::InvalidValue(
+                "curve_one_time_prekeys_threshold",
+                _
+            ))
+        ));
+        assert!(matches!(
+            config.set("allow_insecure_transport", "yes"),
+            Err(ConfigError::InvalidValue("allow_insecure_transport", _))
+        ));
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert!(ServerConfig::default().validate().is_ok());
+
+        let config = ServerConfig {
+            endpoint: "inproc://server".to_string(),
+            ..ServerConfig::default()
+        };
+        assert_rejected(&config, "endpoint");
+
+        let config = ServerConfig {
+            workers: 0,
+            ..ServerConfig::default()
+        };
+        assert_rejected(&config, "workers");
+
+        for storage_name in ["", "../server"] {
+            let config = ServerConfig {
+                storage_name: storage_name.to_string(),
+                ..ServerConfig::default()
+            };
+            assert_rejected(&config, "storage_name");
+        }
+
+        let config = ServerConfig {
+            storage_path: "./Cargo.toml".to_string(),
+            ..ServerConfig::default()
+        };
+        assert_rejected(&config, "storage_path");
+
+        let config = ServerConfig {
+            curve_signed_prekey_lifetime_secs: 0,
+            ..ServerConfig::default()
+        };
+        assert_rejected(&config, "curve_signed_prekey_lifetime_secs");
+
+        let config = ServerConfig {
+            pqkem_last_resort_signed_prekey_lifetime_secs: 0,
+            ..ServerConfig::default()
+        };
+        assert_rejected(&config, "pqkem_last_resort_signed_prekey_lifetime_secs");
+
+        let config = ServerConfig {
+            curve_one_time_prekeys_threshold: 0,
+            ..ServerConfig::default()
+        };
+        assert_rejected(&config, "curve_one_time_prekeys_threshold");
+
+        let config = ServerConfig {
+            pqkem_one_time_prekeys_threshold: 0,
+            ..ServerConfig::default()
+        };
+        assert_rejected(&config, "pqkem_one_time_prekeys_threshold");
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fs, io::Write, path::Path};
+
+use e2ee_rust_common::errors::{general::GeneralError, zmq::ZMQError};
+use log::{error, info};
+use zmq::CurveKeyPair;
+
+const CURVE_KEY_LENGTH: usize = 32;
+
+// Loads the long-term CurveZMQ key pair of the server, or generates and saves one on the first start
+// The key file holds the z85 encoded public and secret keys on two lines
+// The public key is also written alone to another file, to be handed to the clients that pin it
+pub fn load_or_generate_curve_key_pair(
+    key_file: &str,
+    public_key_file: &str,
+) -> Result<CurveKeyPair, GeneralError> {
+    let key_pair = if Path::new(key_file).exists() {
+        info!("Loading CurveZMQ key pair from {}", key_file);
+        load_curve_key_pair(key_file)?
+    } else {
+        info!("Generating a new CurveZMQ key pair in {}", key_file);
+        let key_pair =
+            CurveKeyPair::new().map_err(|_| GeneralError::ZMQ(ZMQError::CurveSetupError))?;
+        save_curve_key_pair(key_file, &key_pair)?;
+        key_pair
+    };
+
+    // Publish the public key for the clients
+    let public_key = encode_curve_key(&key_pair.public_key)?;
+    fs::write(public_key_file, format!("{}\n", public_key)).map_err(|e| {
+        error!("Cannot write the CurveZMQ public key: {}", e);
+        GeneralError::ZMQ(ZMQError::CurveKeyFileError)
+    })?;
+    info!("Server CurveZMQ public key: {}", public_key);
+
+    Ok(key_pair)
+}
+
+fn load_curve_key_pair(key_file: &str) -> Result<CurveKeyPair, GeneralError> {
+    let content = fs::read_to_string(key_file).map_err(|e| {
+        error!("Cannot read the CurveZMQ key file: {}", e);
+        GeneralError::ZMQ(ZMQError::CurveKeyFileError)
+    })?;
+
+    let mut lines = content.lines();
+    let (Some(public_key), Some(secret_key)) = (lines.next(), lines.next()) else {
+        error!("The CurveZMQ key file must hold the public and the secret keys");
+        return Err(GeneralError::ZMQ(ZMQError::CurveKeyFileError));
+    };
+
+    Ok(CurveKeyPair {
+        public_key: decode_curve_key(public_key.trim())?,
+        secret_key: decode_curve_key(secret_key.trim())?,
+    })
+}
+
+fn save_curve_key_pair(key_file: &str, key_pair: &CurveKeyPair) -> Result<(), GeneralError> {
+    let content = format!(
+        "{}\n{}\n",
+        encode_curve_key(&key_pair.public_key)?,
+        encode_curve_key(&key_pair.secret_key)?
+    );
+
+    // The secret key must only be readable by the server user
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options
+        .open(key_file)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| {
+            error!("Cannot write the CurveZMQ key file: {}", e);
+            GeneralError::ZMQ(ZMQError::CurveKeyFileError)
+        })
+}
+
+fn encode_curve_key(key: &[u8]) -> Result<String, GeneralError> {
+    zmq::z85_encode(key).map_err(|_| GeneralError::ZMQ(ZMQError::InvalidCurveKey))
+}
+
+fn decode_curve_key(encoded_key: &str) -> Result<[u8; CURVE_KEY_LENGTH], GeneralError> {
+    zmq::z85_decode(encoded_key)
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or(GeneralError::ZMQ(ZMQError::InvalidCurveKey))
+}
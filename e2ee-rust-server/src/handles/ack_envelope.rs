@@ -0,0 +1,37 @@
+use e2ee_rust_common::{
+    messages::{
+        client::ack_envelope::AckEnvelope,
+        server::server_message::{ServerError, ServerMessage},
+    },
+    storage::{
+        errors::StorageInterfaceError,
+        server::{errors::ServerStorageError, traits::ServerStorage},
+    },
+};
+use log::{debug, error};
+use uuid::Uuid;
+
+use crate::utils::state_send_first_messages;
+
+pub fn handle_ack_envelope(
+    client_id: Uuid,
+    ack_envelope: &AckEnvelope,
+    server_storage: &impl ServerStorage,
+) -> ServerMessage {
+    debug!("Client acknowledged envelope {}", ack_envelope.envelope_id);
+
+    // The client processed the envelope, it can be removed from its mailbox
+    match server_storage.delete_envelope(client_id, &ack_envelope.envelope_id) {
+        Ok(()) => {}
+        Err(StorageInterfaceError::ServerStorageError(ServerStorageError::EnvelopeNotFound)) => {
+            return ServerMessage::new_error(ServerError::BadResponse)
+        }
+        Err(e) => {
+            error!("Error deleting envelope: {:?}", e);
+            return ServerMessage::new_error(ServerError::UnknownError);
+        }
+    }
+
+    // Send the next envelope
+    state_send_first_messages(client_id, server_storage)
+}
@@ -0,0 +1,66 @@
+use e2ee_rust_common::{
+    crypto::curve::traits::EllipticCurveAlgorithm,
+    messages::{
+        client::authentication_response::AuthenticationResponse,
+        server::server_message::{ServerError, ServerMessage},
+    },
+    storage::server::traits::ServerStorage,
+};
+use log::{debug, warn};
+use uuid::Uuid;
+
+use crate::{authentication::Authenticator, config::ServerConfig, utils::state_check_keys};
+
+pub fn handle_authentication_response(
+    client_id: Uuid,
+    session_token: &[u8],
+    authentication_response: &AuthenticationResponse,
+    authenticator: &Authenticator,
+    config: &ServerConfig,
+    server_storage: &impl ServerStorage,
+) -> ServerMessage {
+    debug!("Handling authentication response");
+
+    // Make sure that we are waiting for an answer from this client, to the challenge sent with the session token
+    let challenge = match authenticator.take_challenge(client_id, session_token) {
+        Some(challenge) => challenge,
+        None => {
+            warn!(
+                "No pending authentication challenge for client {}",
+                client_id
+            );
+            return ServerMessage::new_error(ServerError::AuthenticationFailed);
+        }
+    };
+
+    // Make sure that the client is registered
+    let client_info = match server_storage.get_client(&client_id) {
+        Ok(client_info) => client_info,
+        Err(_) => return ServerMessage::new_error(ServerError::ClientNotRegistered),
+    };
+
+    // Verify the signature of the challenge with the registered identity key
+    let identity_key = &client_info.key_bundle.identity_key.0;
+    match identity_key.key_type.xeddsa_verify(
+        identity_key,
+        &challenge.signed_message(client_id),
+        &authentication_response.signature,
+    ) {
+        Ok(true) => {}
+        Ok(false) => {
+            warn!("Invalid authentication signature from client {}", client_id);
+            return ServerMessage::new_error(ServerError::AuthenticationFailed);
+        }
+        Err(e) => {
+            warn!(
+                "Cannot verify the authentication signature from client {}: {:?}",
+                client_id, e
+            );
+            return ServerMessage::new_error(ServerError::AuthenticationFailed);
+        }
+    }
+    authenticator.authenticate(client_id, session_token);
+
+    // Check the keys
+    state_check_keys(client_id, &client_info.key_bundle, config, server_storage)
+}
@@ -8,19 +8,30 @@ use e2ee_rust_common::{
 use log::debug;
 use uuid::Uuid;
 
-use crate::utils::state_check_keys;
+use crate::{
+    authentication::Authenticator,
+    config::ServerConfig,
+    utils::{state_authenticate, state_check_keys},
+};
 
 pub fn handle_client_hello(
     client_id: Uuid,
+    session_token: &[u8],
     _client_hello: &ClientHello,
+    authenticator: &Authenticator,
+    config: &ServerConfig,
     server_storage: &impl ServerStorage,
 ) -> ServerMessage {
     debug!("Handling client hello from client_id: {}", client_id);
 
     // Check if the client is registered
+    // The keys are only checked once the client proved that it owns the registered identity key
     if let Ok(client_info) = server_storage.get_client(&client_id) {
-        // Get the keys and check them
-        return state_check_keys(client_id, &client_info.key_bundle, server_storage);
+        // The session stays open until it expires, so the heartbeats do not sign a new challenge every time
+        if authenticator.is_authenticated(client_id, session_token) {
+            return state_check_keys(client_id, &client_info.key_bundle, config, server_storage);
+        }
+        return state_authenticate(client_id, authenticator);
     }
 
     // Client is not registered, ask for registration bundle
@@ -1,41 +1,96 @@
 use e2ee_rust_common::{
     messages::{
         client::client_message::{ClientMessage, ClientMessageType},
-        server::server_message::ServerMessage,
+        server::server_message::{ServerError, ServerMessage},
     },
     storage::server::traits::ServerStorage,
 };
+use log::warn;
+
+use crate::{authentication::Authenticator, config::ServerConfig};
 
 use super::{
+    ack_envelope::handle_ack_envelope, authentication_response::handle_authentication_response,
     client_hello::handle_client_hello, new_keys::handle_new_keys,
-    registration_bundle::handle_registration_bundle,
-    request_peer_bundle::handle_request_peer_bundle,
+    post_envelope::handle_post_envelope, registration_bundle::handle_registration_bundle,
+    request_peer_bundle::handle_request_peer_bundle, send_first_message::handle_send_first_message,
 };
 
 pub fn handle_client_message(
     client_message: &ClientMessage,
+    authenticator: &Authenticator,
+    config: &ServerConfig,
     server_storage: &mut impl ServerStorage,
 ) -> ServerMessage {
+    // Until they answer an authentication challenge, clients may only say hello and register
+    // The session is found with the token of the challenge, the ZMQ routing identity is chosen by the client and cannot be trusted
+    let session_token = &client_message.session_token;
+    match client_message.message_type {
+        ClientMessageType::ClientHello
+        | ClientMessageType::RegistrationBundle
+        | ClientMessageType::AuthenticationResponse => {}
+        _ => {
+            if !authenticator.is_authenticated(client_message.client_id, session_token) {
+                warn!(
+                    "Rejecting request from unauthenticated client {}",
+                    client_message.client_id
+                );
+                return ServerMessage::new_error(ServerError::NotAuthenticated);
+            }
+        }
+    }
+
     match client_message.message_type {
         ClientMessageType::ClientHello => {
             let client_hello = client_message.client_hello.as_ref().unwrap();
-            handle_client_hello(client_message.client_id, client_hello, server_storage)
+            handle_client_hello(
+                client_message.client_id,
+                session_token,
+                client_hello,
+                authenticator,
+                config,
+                server_storage,
+            )
         }
         ClientMessageType::RegistrationBundle => {
             let registration_bundle = client_message.registration_bundle.as_ref().unwrap();
             handle_registration_bundle(
                 client_message.client_id,
                 registration_bundle,
+                authenticator,
+                server_storage,
+            )
+        }
+        ClientMessageType::AuthenticationResponse => {
+            let authentication_response = client_message.authentication_response.as_ref().unwrap();
+            handle_authentication_response(
+                client_message.client_id,
+                session_token,
+                authentication_response,
+                authenticator,
+                config,
                 server_storage,
             )
         }
         ClientMessageType::NewKeys => {
             let new_keys = client_message.new_keys.as_ref().unwrap();
-            handle_new_keys(client_message.client_id, new_keys, server_storage)
+            handle_new_keys(client_message.client_id, new_keys, config, server_storage)
         }
         ClientMessageType::RequestPeerBundle => {
             let request_peer_bundle = client_message.request_peer_bundle.as_ref().unwrap();
             handle_request_peer_bundle(request_peer_bundle, server_storage)
         }
+        ClientMessageType::SendFirstMessage => {
+            let send_first_message = client_message.send_first_message.as_ref().unwrap();
+            handle_send_first_message(client_message.client_id, send_first_message, server_storage)
+        }
+        ClientMessageType::PostEnvelope => {
+            let post_envelope = client_message.post_envelope.as_ref().unwrap();
+            handle_post_envelope(client_message.client_id, post_envelope, server_storage)
+        }
+        ClientMessageType::AckEnvelope => {
+            let ack_envelope = client_message.ack_envelope.as_ref().unwrap();
+            handle_ack_envelope(client_message.client_id, ack_envelope, server_storage)
+        }
     }
 }
@@ -1,5 +1,9 @@
+pub mod ack_envelope;
+pub mod authentication_response;
 pub mod client_hello;
 pub mod client_message;
 pub mod new_keys;
+pub mod post_envelope;
 pub mod registration_bundle;
 pub mod request_peer_bundle;
+pub mod send_first_message;
@@ -8,11 +8,15 @@ use e2ee_rust_common::{
 use log::{debug, error};
 use uuid::Uuid;
 
-use crate::utils::state_check_keys;
+use crate::{
+    config::ServerConfig,
+    utils::{state_check_keys, validate_curve_public_keys, verify_prekey_signatures},
+};
 
 pub fn handle_new_keys(
     client_id: Uuid,
     new_keys: &NewKeys,
+    config: &ServerConfig,
     server_storage: &mut impl ServerStorage,
 ) -> ServerMessage {
     debug!("Handling new keys");
@@ -26,6 +30,33 @@ pub fn handle_new_keys(
         Err(_) => return ServerMessage::new_error(ServerError::ClientNotRegistered),
     }
 
+    // Make sure that the new curve prekeys can safely be used for Diffie-Hellman
+    let identity_key = &client_bundle.key_bundle.identity_key.0;
+    let mut curve_public_keys: Vec<_> = new_keys
+        .signed_curve_prekey
+        .iter()
+        .map(|prekey| &prekey.identified_public_key.public_key)
+        .collect();
+    if let Some(new_keys) = &new_keys.one_time_curve_prekey_set {
+        curve_public_keys.extend(new_keys.prekeys.iter().map(|prekey| &prekey.public_key));
+    }
+    if let Err(err) = validate_curve_public_keys(identity_key, &curve_public_keys) {
+        return ServerMessage::new_error(err);
+    }
+
+    // Make sure that the new signed prekeys were signed by the registered identity key
+    let signed_curve_prekeys: Vec<_> = new_keys.signed_curve_prekey.iter().collect();
+    let mut signed_pqkem_prekeys: Vec<_> =
+        new_keys.signed_last_resort_pqkem_prekey.iter().collect();
+    if let Some(new_keys) = &new_keys.signed_one_time_pqkem_prekey_set {
+        signed_pqkem_prekeys.extend(new_keys.prekeys.iter());
+    }
+    if let Err(err) =
+        verify_prekey_signatures(identity_key, &signed_curve_prekeys, &signed_pqkem_prekeys)
+    {
+        return ServerMessage::new_error(err);
+    }
+
     // Update the keys as needed
     let now = chrono::Utc::now();
     let res: Result<(), ServerError> = match new_keys.keys_type {
@@ -99,5 +130,5 @@ pub fn handle_new_keys(
     }
     debug!("Update OK");
 
-    state_check_keys(client_id, &client_bundle.key_bundle, server_storage)
+    state_check_keys(client_id, &client_bundle.key_bundle, config, server_storage)
 }
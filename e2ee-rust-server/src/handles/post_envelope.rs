@@ -0,0 +1,42 @@
+use e2ee_rust_common::{
+    messages::{
+        client::post_envelope::PostEnvelope,
+        server::{
+            server_envelope::{ServerEnvelope, ServerEnvelopeType},
+            server_message::{ServerError, ServerMessage},
+        },
+    },
+    storage::server::traits::ServerStorage,
+};
+use log::{debug, error};
+use uuid::Uuid;
+
+pub fn handle_post_envelope(
+    client_id: Uuid,
+    post_envelope: &PostEnvelope,
+    server_storage: &impl ServerStorage,
+) -> ServerMessage {
+    // Get the recipient UUID from the request
+    let recipient_uuid = post_envelope.recipient_uuid;
+    debug!("Received envelope for peer {}", recipient_uuid);
+
+    // Make sure that the recipient is registered
+    if server_storage.get_client(&recipient_uuid).is_err() {
+        return ServerMessage::new_error(ServerError::ClientNotRegistered);
+    }
+
+    // Queue the envelope in the recipient's mailbox
+    let envelope = ServerEnvelope {
+        id: Uuid::new_v4(),
+        sender_uuid: client_id,
+        envelope_type: ServerEnvelopeType::Message,
+        payload: post_envelope.payload.clone(),
+        timestamp: chrono::Utc::now(),
+    };
+    if let Err(e) = server_storage.enqueue_envelope(recipient_uuid, &envelope) {
+        error!("Error queuing envelope: {:?}", e);
+        return ServerMessage::new_error(ServerError::UnknownError);
+    }
+
+    ServerMessage::new_ok()
+}
@@ -1,26 +1,61 @@
 use e2ee_rust_common::{
     messages::server::server_message::{ServerError, ServerMessage},
     pqxdh::registration_bundle::RegistrationBundle,
-    storage::server::{
-        client_structs::{ClientInformation, ClientKeyBundle},
-        traits::ServerStorage,
+    storage::{
+        errors::StorageInterfaceError,
+        server::{
+            client_structs::{ClientInformation, ClientKeyBundle},
+            errors::ServerStorageError,
+            traits::ServerStorage,
+        },
     },
 };
 use log::{debug, error};
 use uuid::Uuid;
 
-use crate::utils::state_check_keys;
+use crate::{
+    authentication::Authenticator,
+    utils::{state_authenticate, validate_curve_public_keys, verify_prekey_signatures},
+};
 
 pub fn handle_registration_bundle(
     client_id: Uuid,
     registration_bundle: &RegistrationBundle,
+    authenticator: &Authenticator,
     server_storage: &mut impl ServerStorage,
 ) -> ServerMessage {
     debug!("Handling registration bundle");
 
-    // Make sure that the client is not already registered
-    if server_storage.get_client(&client_id).is_ok() {
-        return ServerMessage::new_error(ServerError::ClientAlreadyRegistered);
+    // Make sure that every curve key can safely be used for Diffie-Hellman
+    let mut curve_public_keys = vec![
+        &registration_bundle.identity_key,
+        &registration_bundle
+            .signed_curve_prekey
+            .identified_public_key
+            .public_key,
+    ];
+    curve_public_keys.extend(
+        registration_bundle
+            .one_time_curve_prekeys
+            .prekeys
+            .iter()
+            .map(|prekey| &prekey.public_key),
+    );
+    if let Err(err) =
+        validate_curve_public_keys(&registration_bundle.identity_key, &curve_public_keys)
+    {
+        return ServerMessage::new_error(err);
+    }
+
+    // Make sure that every signed prekey was signed by the identity key
+    let mut signed_pqkem_prekeys = vec![&registration_bundle.signed_last_resort_pqkem_prekey];
+    signed_pqkem_prekeys.extend(registration_bundle.one_time_pqkem_prekeys.prekeys.iter());
+    if let Err(err) = verify_prekey_signatures(
+        &registration_bundle.identity_key,
+        &[&registration_bundle.signed_curve_prekey],
+        &signed_pqkem_prekeys,
+    ) {
+        return ServerMessage::new_error(err);
     }
 
     // Create the client key bundle
@@ -37,16 +72,168 @@ pub fn handle_registration_bundle(
     };
 
     // Add the client to the server storage
-    if let Err(e) = server_storage.add_client(
+    // The storage only accepts the first registration of a client, even when several workers handle one at the same time
+    match server_storage.add_client(
         client_id,
         &ClientInformation {
-            key_bundle: client_key_bundle.clone(),
+            key_bundle: client_key_bundle,
         },
     ) {
-        error!("Error adding client: {:?}", e);
-        return ServerMessage::new_error(ServerError::UnknownError);
+        Ok(()) => {}
+        Err(StorageInterfaceError::ServerStorageError(ServerStorageError::ClientAlreadyExists)) => {
+            return ServerMessage::new_error(ServerError::ClientAlreadyRegistered);
+        }
+        Err(e) => {
+            error!("Error adding client: {:?}", e);
+            return ServerMessage::new_error(ServerError::UnknownError);
+        }
+    }
+
+    // The client has to authenticate before its keys are checked
+    state_authenticate(client_id, authenticator)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Barrier},
+        thread,
+    };
+
+    use e2ee_rust_common::{
+        crypto::{
+            aead::enum_aead_types::AEADType, curve::enum_elliptic_curve_type::EllipticCurveType,
+            pqkem::enum_pqkem_type::PQKEMType,
+        },
+        hash::enum_hash_types::HashType,
+        messages::server::server_message::{ServerError, ServerMessage, ServerMessageType},
+        pqxdh::{
+            parameters::PqxdhParameters, private_bundle::PrivateBundle,
+            registration_bundle::RegistrationBundle,
+        },
+        storage::{server::traits::ServerStorage, storage_interface::StorageInterface},
+    };
+    use e2ee_rust_sqlite_storage::SQLiteStorage;
+    use uuid::Uuid;
+
+    use crate::authentication::Authenticator;
+
+    use super::handle_registration_bundle;
+
+    const WORKERS: usize = 8;
+
+    // Server database in the temporary directory, removed with its WAL files when dropped
+    struct TestDatabase {
+        storage: SQLiteStorage,
+        name: String,
+    }
+
+    impl TestDatabase {
+        fn new() -> Self {
+            let name = format!("test-registration-{}", Uuid::new_v4());
+            let storage =
+                SQLiteStorage::new(&name, std::env::temp_dir().to_str().unwrap()).unwrap();
+            storage.init_server().unwrap();
+            TestDatabase { storage, name }
+        }
+    }
+
+    impl Drop for TestDatabase {
+        fn drop(&mut self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(
+                    std::env::temp_dir().join(format!("db_{}.sqlite{}", self.name, suffix)),
+                );
+            }
+        }
+    }
+
+    fn registration_bundle() -> RegistrationBundle {
+        let mut rng = rand::thread_rng();
+        let parameters = PqxdhParameters::new(
+            EllipticCurveType::CURVE25519,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap();
+        let private_bundle = PrivateBundle::new(&parameters, 1, 1, &mut rng);
+        RegistrationBundle::from_private_bundle(&private_bundle, parameters.curve_type(), &mut rng)
+            .unwrap()
     }
 
-    // Check the keys
-    state_check_keys(client_id, &client_key_bundle, server_storage)
+    // Runs the registrations at the same time, each from its own worker with its own handle on the shared storage like the server
+    fn register_concurrently(
+        storage: &SQLiteStorage,
+        registrations: Vec<(Uuid, RegistrationBundle)>,
+    ) -> Vec<ServerMessage> {
+        let authenticator = Arc::new(Authenticator::default());
+        let barrier = Arc::new(Barrier::new(registrations.len()));
+        let workers: Vec<_> = registrations
+            .into_iter()
+            .map(|(client_id, registration_bundle)| {
+                let mut server_storage = storage.clone();
+                let authenticator = Arc::clone(&authenticator);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    handle_registration_bundle(
+                        client_id,
+                        &registration_bundle,
+                        &authenticator,
+                        &mut server_storage,
+                    )
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn concurrent_registrations_of_a_client_store_it_once() {
+        let database = TestDatabase::new();
+        let client_id = Uuid::new_v4();
+        let registrations = (0..WORKERS)
+            .map(|_| (client_id, registration_bundle()))
+            .collect();
+
+        // One worker registers the client, the others are told that it is already registered
+        let answers = register_concurrently(&database.storage, registrations);
+        let registered = answers
+            .iter()
+            .filter(|answer| answer.message_type == ServerMessageType::Data)
+            .count();
+        assert_eq!(registered, 1);
+        assert!(answers.iter().all(|answer| {
+            answer.message_type == ServerMessageType::Data
+                || matches!(answer.error, Some(ServerError::ClientAlreadyRegistered))
+        }));
+        assert!(database.storage.get_client(&client_id).is_ok());
+    }
+
+    #[test]
+    fn concurrent_registrations_of_different_clients_all_succeed() {
+        let database = TestDatabase::new();
+        let registrations: Vec<_> = (0..WORKERS)
+            .map(|_| (Uuid::new_v4(), registration_bundle()))
+            .collect();
+        let client_ids: Vec<_> = registrations
+            .iter()
+            .map(|(client_id, _)| *client_id)
+            .collect();
+
+        // The workers wait for each other instead of failing on a busy database
+        let answers = register_concurrently(&database.storage, registrations);
+        assert!(answers
+            .iter()
+            .all(|answer| answer.message_type == ServerMessageType::Data));
+        for client_id in client_ids {
+            assert!(database.storage.get_client(&client_id).is_ok());
+        }
+    }
 }
@@ -56,6 +56,8 @@ pub fn handle_request_peer_bundle(
                 one_time_curve_prekey: curve_prekey,
             },
         }),
+        envelope: None,
+        authentication_challenge: None,
     };
 
     ServerMessage {
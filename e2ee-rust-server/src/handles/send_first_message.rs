@@ -0,0 +1,43 @@
+use e2ee_rust_common::{
+    messages::{
+        client::send_first_message::SendFirstMessage,
+        server::{
+            server_envelope::{ServerEnvelope, ServerEnvelopeType},
+            server_message::{ServerError, ServerMessage},
+        },
+    },
+    protobuf::utils::create_first_message,
+    storage::server::traits::ServerStorage,
+};
+use log::{debug, error};
+use uuid::Uuid;
+
+pub fn handle_send_first_message(
+    client_id: Uuid,
+    send_first_message: &SendFirstMessage,
+    server_storage: &impl ServerStorage,
+) -> ServerMessage {
+    // Get the peer UUID from the request
+    let peer_uuid = send_first_message.peer_uuid;
+    debug!("Received first message for peer {}", peer_uuid);
+
+    // Make sure that the peer is registered
+    if server_storage.get_client(&peer_uuid).is_err() {
+        return ServerMessage::new_error(ServerError::ClientNotRegistered);
+    }
+
+    // Queue the first message in the peer's mailbox
+    let envelope = ServerEnvelope {
+        id: Uuid::new_v4(),
+        sender_uuid: client_id,
+        envelope_type: ServerEnvelopeType::FirstMessage,
+        payload: create_first_message(&send_first_message.first_message),
+        timestamp: chrono::Utc::now(),
+    };
+    if let Err(e) = server_storage.enqueue_envelope(peer_uuid, &envelope) {
+        error!("Error queuing first message: {:?}", e);
+        return ServerMessage::new_error(ServerError::UnknownError);
+    }
+
+    ServerMessage::new_ok()
+}
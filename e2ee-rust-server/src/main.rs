@@ -1,47 +1,81 @@
+mod authentication;
+mod config;
+mod curve_key;
 mod handles;
 mod utils;
+mod worker;
 
+use std::sync::Arc;
+
+use authentication::Authenticator;
+use config::ServerConfig;
+use curve_key::load_or_generate_curve_key_pair;
 use e2ee_rust_common::{
     errors::{
         general::{GeneralError, ToGeneralError},
         zmq::ZMQError,
     },
-    messages::server::server_message::{ServerError, ServerMessage},
-    protobuf::utils::{create_server_message, decode_client_message},
     storage::{server::traits::ServerStorage, storage_interface::StorageInterface},
-    utils::display::print_slice,
 };
 use e2ee_rust_sqlite_storage::SQLiteStorage;
-use handles::client_message::handle_client_message;
-use log::{debug, error, info, warn};
+use log::{error, info, warn};
+use worker::run_worker;
 
-const ENDPOINT: &str = "tcp://*:5555";
 const MONITOR_ENDPOINT: &str = "inproc://monitor.rep";
+const WORKERS_ENDPOINT: &str = "inproc://workers";
+const CURVE_KEY_FILE: &str = "server-curve.key";
+const CURVE_PUBLIC_KEY_FILE: &str = "server-curve.pub";
 
-// const CURVE_SIGNED_PREKEY_LIFETIME_SECS: u64 = 60 * 60 * 24 * 7;
-const CURVE_SIGNED_PREKEY_LIFETIME_SECS: u64 = 10;
-// const PQKEM_LAST_RESORT_SIGNED_PREKEY_LIFETIME_SECS: u64 = 60 * 60 * 24 * 7;
-const PQKEM_LAST_RESORT_SIGNED_PREKEY_LIFETIME_SECS: u64 = 10;
-const CURVE_ONE_TIME_PREKEYS_THRESHOLD: usize = 5;
-const PQKEM_ONE_TIME_PREKEYS_THRESHOLD: usize = 5;
+const AUTHENTICATION_CHALLENGE_LIFETIME_SECS: u64 = 30;
+const AUTHENTICATION_SESSION_LIFETIME_SECS: u64 = 60 * 10;
 
 fn main() -> Result<(), GeneralError> {
     env_logger::init();
 
-    // Create the server storage
-    let mut server_storage = SQLiteStorage::new("test-server", "./").to_general_error()?;
+    // Load and validate the configuration
+    let config = Arc::new(ServerConfig::load().map_err(GeneralError::Config)?);
+    info!("Configuration: {:?}", config);
+
+    // Create the server storage, the workers share its connection pool
+    let server_storage =
+        SQLiteStorage::new(&config.storage_name, &config.storage_path).to_general_error()?;
     info!("Server storage created");
 
     // Initializes the server storage
     server_storage.init_server().to_general_error()?;
     info!("Server storage initialized");
 
+    // Authentication state of the connected clients, shared by all the workers
+    let authenticator = Arc::new(Authenticator::default());
+
+    // Load the long-term key pair that encrypts the connections with the clients
+    // libzmq may be built without CURVE, in which case the server only starts in cleartext when explicitly allowed
+    let curve_key_pair = if zmq::has("curve") == Some(true) {
+        Some(load_or_generate_curve_key_pair(
+            &config.storage_file(CURVE_KEY_FILE),
+            &config.storage_file(CURVE_PUBLIC_KEY_FILE),
+        )?)
+    } else if config.allow_insecure_transport {
+        warn!("libzmq was built without CURVE support, connections will not be encrypted");
+        None
+    } else {
+        error!("libzmq was built without CURVE support, set allow_insecure_transport to run the server in cleartext");
+        return Err(GeneralError::ZMQ(ZMQError::CurveNotSupported));
+    };
+
     // Prepare our context and socket
     let ctx = zmq::Context::new();
 
-    // Create the server reply socket
+    // Create the frontend socket, which the clients connect to
     info!("Creating server socket...");
     let server_socket = ctx.socket(zmq::ROUTER).unwrap();
+    if let Some(curve_key_pair) = &curve_key_pair {
+        server_socket
+            .set_curve_server(true)
+            .and_then(|_| server_socket.set_curve_secretkey(&curve_key_pair.secret_key))
+            .map_err(|_| GeneralError::ZMQ(ZMQError::CurveSetupError))?;
+        info!("Server socket uses CurveZMQ encryption");
+    }
     server_socket
         .monitor(MONITOR_ENDPOINT, zmq::SocketEvent::ALL as i32)
         .map_err(|_| GeneralError::ZMQ(ZMQError::MonitorError))?;
@@ -53,60 +87,37 @@ fn main() -> Result<(), GeneralError> {
         monitor(&ctx_clone).unwrap();
     });
 
+    // Create the backend socket, which spreads the requests over the workers
+    let workers_socket = ctx.socket(zmq::DEALER).unwrap();
+    workers_socket
+        .bind(WORKERS_ENDPOINT)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::SocketBindError))?;
+
+    // Start the workers, each in its own thread with its own handle on the storage
+    info!("Starting {} workers...", config.workers);
+    for worker_id in 0..config.workers {
+        let server_storage = server_storage.clone();
+        let ctx_clone = ctx.clone();
+        let authenticator = Arc::clone(&authenticator);
+        let config = Arc::clone(&config);
+        std::thread::spawn(move || {
+            if let Err(e) = run_worker(worker_id, &ctx_clone, authenticator, config, server_storage)
+            {
+                error!("Worker {} stopped: {}", worker_id, e);
+            }
+        });
+    }
+
     // Bind the server socket to the endpoint
     info!("Starting server...");
     server_socket
-        .bind(ENDPOINT)
+        .bind(&config.endpoint)
         .map_err(|_| GeneralError::ZMQ(ZMQError::SocketBindError))?;
 
     info!("Server started, waiting for requests...");
 
-    // Start the server loop
-    loop {
-        // Wait for next request from client
-        let identity = server_socket
-            .recv_bytes(0)
-            .map_err(|_| GeneralError::ZMQ(ZMQError::RecvError))?;
-        info!("Received: message from {}", print_slice(&identity));
-        server_socket.send(&identity, zmq::SNDMORE).unwrap();
-
-        // Wait for envelope
-        // If the envelope is an error or if it is not an empty message, the communication is not correct, so we skip it
-        let envelope = server_socket.recv_bytes(zmq::SNDMORE);
-        if envelope.is_err() || envelope.unwrap().len() != 0 {
-            warn!("Received: invalid envelope");
-            continue;
-        }
-
-        // Wait for message
-        let msg = server_socket.recv_bytes(0);
-        if msg.is_err() {
-            error!("Error receiving message");
-            continue;
-        }
-
-        // Try to convert the message to a ClientMessage
-        let server_message_res = decode_client_message(&msg.unwrap());
-        let answer: ServerMessage = match server_message_res {
-            Ok(server_message) => {
-                debug!("Decoded client message");
-                handle_client_message(&server_message, &mut server_storage)
-            }
-            Err(e) => {
-                error!("Error decoding client message: {:?}", e);
-                ServerMessage::new_error(ServerError::CannotDecodeClientMessage)
-            }
-        };
-
-        // Respond to client
-        let mut err = server_socket.send("", zmq::SNDMORE);
-        if err.is_ok() {
-            err = server_socket.send(create_server_message(&answer), 0);
-        }
-        if err.is_err() {
-            error!("Error sending message");
-        }
-    }
+    // Forward the requests to the workers and their replies to the clients
+    zmq::proxy(&server_socket, &workers_socket).map_err(|_| GeneralError::ZMQ(ZMQError::ProxyError))
 }
 
 fn monitor(ctx: &zmq::Context) -> Result<(), zmq::Error> {
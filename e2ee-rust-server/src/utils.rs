@@ -1,14 +1,15 @@
 use e2ee_rust_common::{
-    messages::server::server_message::{ServerCommand, ServerMessage},
+    crypto::curve::{keys::EllipticCurvePublicKey, traits::EllipticCurveAlgorithm},
+    messages::server::server_message::{
+        ServerCommand, ServerDataType, ServerError, ServerMessage, ServerMessageData,
+    },
+    pqxdh::{signed_curve_prekey::SignedCurvePrekey, signed_pqkem_prekey::SignedPQKEMPrekey},
     storage::server::{client_structs::ClientKeyBundle, traits::ServerStorage},
 };
-use log::debug;
+use log::{debug, error, warn};
 use uuid::Uuid;
 
-use crate::{
-    CURVE_ONE_TIME_PREKEYS_THRESHOLD, CURVE_SIGNED_PREKEY_LIFETIME_SECS,
-    PQKEM_LAST_RESORT_SIGNED_PREKEY_LIFETIME_SECS, PQKEM_ONE_TIME_PREKEYS_THRESHOLD,
-};
+use crate::{authentication::Authenticator, config::ServerConfig};
 
 enum KeysCheckResult {
     Ok,
@@ -21,9 +22,10 @@ enum KeysCheckResult {
 pub fn state_check_keys(
     client_id: Uuid,
     bundle: &ClientKeyBundle,
+    config: &ServerConfig,
     server_storage: &impl ServerStorage,
 ) -> ServerMessage {
-    let state = check_keys(bundle);
+    let state = check_keys(bundle, config);
 
     match state {
         KeysCheckResult::Ok => state_send_first_messages(client_id, server_storage),
@@ -46,16 +48,55 @@ pub fn state_check_keys(
     }
 }
 
-fn state_send_first_messages(
-    _client_id: Uuid,
-    _server_storage: &impl ServerStorage,
+// Sends the oldest envelope waiting in the client's mailbox, or OK once the mailbox is empty
+// Envelopes are only deleted when the client acknowledges them, so the next one is sent from the acknowledgement handler
+pub fn state_send_first_messages(
+    client_id: Uuid,
+    server_storage: &impl ServerStorage,
 ) -> ServerMessage {
-    // TODO: Implement this function
     debug!("Sending first messages");
-    ServerMessage::new_ok()
+
+    // Get the oldest envelope waiting for the client, if any
+    let envelope = match server_storage.get_next_envelope(client_id) {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            error!("Error fetching the client's mailbox: {:?}", e);
+            return ServerMessage::new_error(ServerError::UnknownError);
+        }
+    };
+
+    match envelope {
+        Some(envelope) => {
+            debug!("Sending envelope {} to client", envelope.id);
+            ServerMessage::new_data(ServerMessageData {
+                data_type: ServerDataType::Envelope,
+                peer_bundle: None,
+                envelope: Some(envelope),
+                authentication_challenge: None,
+            })
+        }
+        None => {
+            debug!("No more envelopes to send");
+            ServerMessage::new_ok()
+        }
+    }
 }
 
-fn check_keys(bundle: &ClientKeyBundle) -> KeysCheckResult {
+// Sends a fresh challenge that the client has to sign with its identity key before any privileged request
+pub fn state_authenticate(client_id: Uuid, authenticator: &Authenticator) -> ServerMessage {
+    debug!("Asking for authentication");
+    let mut rng = rand::thread_rng();
+    let challenge = authenticator.issue_challenge(client_id, &mut rng);
+
+    ServerMessage::new_data(ServerMessageData {
+        data_type: ServerDataType::AuthenticationChallenge,
+        peer_bundle: None,
+        envelope: None,
+        authentication_challenge: Some(challenge),
+    })
+}
+
+fn check_keys(bundle: &ClientKeyBundle, config: &ServerConfig) -> KeysCheckResult {
     debug!("Checking keys");
     let now = chrono::Utc::now();
 
@@ -63,7 +104,7 @@ fn check_keys(bundle: &ClientKeyBundle) -> KeysCheckResult {
     if now
         .signed_duration_since(bundle.signed_curve_prekey.1)
         .as_seconds_f32() as u64
-        > CURVE_SIGNED_PREKEY_LIFETIME_SECS
+        > config.curve_signed_prekey_lifetime_secs
     {
         debug!("Curve signed prekey is expired");
         return KeysCheckResult::NewSPK;
@@ -73,20 +114,21 @@ fn check_keys(bundle: &ClientKeyBundle) -> KeysCheckResult {
     if now
         .signed_duration_since(bundle.signed_last_resort_pqkem_prekey.1)
         .as_seconds_f32() as u64
-        > PQKEM_LAST_RESORT_SIGNED_PREKEY_LIFETIME_SECS
+        > config.pqkem_last_resort_signed_prekey_lifetime_secs
     {
         debug!("Last resort PQKEM signed prekey is expired");
         return KeysCheckResult::NewLRSPK;
     }
 
     // Check if we are running low on curve one time prekeys
-    if bundle.one_time_curve_prekeys.prekeys.len() < CURVE_ONE_TIME_PREKEYS_THRESHOLD {
+    if bundle.one_time_curve_prekeys.prekeys.len() < config.curve_one_time_prekeys_threshold {
         debug!("Running low on curve one time prekeys");
         return KeysCheckResult::NewCOPK;
     }
 
     // Check if we are running low on PQKEM one time prekeys
-    if bundle.signed_one_time_pqkem_prekeys.prekeys.len() < PQKEM_ONE_TIME_PREKEYS_THRESHOLD {
+    if bundle.signed_one_time_pqkem_prekeys.prekeys.len() < config.pqkem_one_time_prekeys_threshold
+    {
         debug!("Running low on PQKEM one time prekeys");
         return KeysCheckResult::NewPQOPK;
     }
@@ -95,3 +137,77 @@ fn check_keys(bundle: &ClientKeyBundle) -> KeysCheckResult {
     debug!("All keys are fine");
     return KeysCheckResult::Ok;
 }
+
+// Checks that the curve keys uploaded by a client are usable Diffie-Hellman public keys on the identity key curve
+// Rejects wrong lengths and points of small order, which would let a malicious client force a known shared secret on its peers
+pub fn validate_curve_public_keys(
+    identity_key: &EllipticCurvePublicKey,
+    curve_public_keys: &[&EllipticCurvePublicKey],
+) -> Result<(), ServerError> {
+    debug!("Validating curve public keys");
+    let curve_type = &identity_key.key_type;
+
+    for public_key in curve_public_keys {
+        if let Err(e) = curve_type.validate_public_key(public_key) {
+            warn!("Invalid curve public key: {:?}", e);
+            return Err(ServerError::InvalidPublicKey);
+        }
+    }
+
+    Ok(())
+}
+
+// Verifies the signatures of the prekeys uploaded by a client against its identity key
+// The curve algorithm is picked from the identity key type
+pub fn verify_prekey_signatures(
+    identity_key: &EllipticCurvePublicKey,
+    signed_curve_prekeys: &[&SignedCurvePrekey],
+    signed_pqkem_prekeys: &[&SignedPQKEMPrekey],
+) -> Result<(), ServerError> {
+    debug!("Verifying prekey signatures");
+    let curve_type = &identity_key.key_type;
+
+    // Verify the signed curve prekeys
+    for prekey in signed_curve_prekeys {
+        match prekey.verify(identity_key, curve_type) {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!(
+                    "Invalid signature on curve prekey {}",
+                    prekey.identified_public_key.id
+                );
+                return Err(ServerError::InvalidSignature);
+            }
+            Err(e) => {
+                warn!(
+                    "Cannot verify the signature on curve prekey {}: {:?}",
+                    prekey.identified_public_key.id, e
+                );
+                return Err(ServerError::InvalidSignature);
+            }
+        }
+    }
+
+    // Verify the signed PQKEM prekeys
+    for prekey in signed_pqkem_prekeys {
+        match prekey.verify(identity_key, curve_type) {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!(
+                    "Invalid signature on PQKEM prekey {}",
+                    prekey.identified_public_key.id
+                );
+                return Err(ServerError::InvalidSignature);
+            }
+            Err(e) => {
+                warn!(
+                    "Cannot verify the signature on PQKEM prekey {}: {:?}",
+                    prekey.identified_public_key.id, e
+                );
+                return Err(ServerError::InvalidSignature);
+            }
+        }
+    }
+
+    Ok(())
+}
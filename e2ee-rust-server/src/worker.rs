@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use e2ee_rust_common::{
+    errors::{general::GeneralError, zmq::ZMQError},
+    messages::server::server_message::{ServerError, ServerMessage},
+    protobuf::utils::{create_server_message, decode_client_message},
+    utils::display::print_slice,
+};
+use e2ee_rust_sqlite_storage::SQLiteStorage;
+use log::{debug, error, info, warn};
+
+use crate::{
+    authentication::Authenticator, config::ServerConfig,
+    handles::client_message::handle_client_message, WORKERS_ENDPOINT,
+};
+
+// Handles the requests forwarded by the frontend, one at a time, with the worker's own handle on the shared storage
+// Requests arrive as [identity, empty delimiter, message] and the reply is sent back with the same identity so the frontend can route it
+pub fn run_worker(
+    worker_id: usize,
+    ctx: &zmq::Context,
+    authenticator: Arc<Authenticator>,
+    config: Arc<ServerConfig>,
+    mut server_storage: SQLiteStorage,
+) -> Result<(), GeneralError> {
+    // Connect to the backend of the server
+    let worker_socket = ctx.socket(zmq::DEALER).unwrap();
+    worker_socket
+        .connect(WORKERS_ENDPOINT)
+        .map_err(|_| GeneralError::ZMQ(ZMQError::ConnectError))?;
+    info!("Worker {} started", worker_id);
+
+    loop {
+        // Wait for next request from a client
+        let parts = worker_socket
+            .recv_multipart(0)
+            .map_err(|_| GeneralError::ZMQ(ZMQError::RecvError))?;
+
+        // If the request does not have an identity and an empty delimiter, the communication is not correct, so we skip it
+        let [identity, delimiter, msg] = parts.as_slice() else {
+            warn!("Worker {} received: invalid envelope", worker_id);
+            continue;
+        };
+        if !delimiter.is_empty() {
+            warn!("Worker {} received: invalid envelope", worker_id);
+            continue;
+        }
+        info!(
+            "Worker {} received: message from {}",
+            worker_id,
+            print_slice(identity)
+        );
+
+        // Try to convert the message to a ClientMessage
+        let answer: ServerMessage = match decode_client_message(msg) {
+            Ok(client_message) => {
+                debug!("Decoded client message");
+                handle_client_message(
+                    &client_message,
+                    &authenticator,
+                    &config,
+                    &mut server_storage,
+                )
+            }
+            Err(e) => {
+                error!("Error decoding client message: {:?}", e);
+                ServerMessage::new_error(ServerError::CannotDecodeClientMessage)
+            }
+        };
+
+        // Respond to client
+        let reply = create_server_message(&answer);
+        if worker_socket
+            .send_multipart([identity.as_slice(), &[], reply.as_slice()], 0)
+            .is_err()
+        {
+            error!("Worker {}: error sending message", worker_id);
+        }
+    }
+}
@@ -71,3 +71,69 @@ WHERE
     otpp.client_id = ?1
 ";
 pub const REQ_INSERT_ONE_TIME_PQKEM_PREKEY: &str = "INSERT INTO one_time_pqkem_prekey (client_id, identified_pqkem_keypair_id) VALUES (?1, ?2) RETURNING id";
+
+pub const REQ_GET_CLIENT_ONE_TIME_CURVE_PREKEY_IDS: &str = "SELECT
+    otcp.id AS one_time_curve_prekey_id,
+    iec.id AS identified_elliptic_curve_keypair_id,
+    iec.elliptic_curve_keypair_id AS elliptic_curve_keypair_id
+FROM
+    one_time_curve_prekey otcp
+JOIN
+    identified_elliptic_curve_keypair iec ON otcp.identified_elliptic_curve_keypair_id = iec.id
+WHERE
+    otcp.client_id = ?1 AND iec.uuid = ?2
+";
+pub const REQ_DELETE_ONE_TIME_CURVE_PREKEY: &str =
+    "DELETE FROM one_time_curve_prekey WHERE id = ?1";
+pub const REQ_DELETE_IDENTIFIED_ELLIPTIC_CURVE_KEYPAIR: &str =
+    "DELETE FROM identified_elliptic_curve_keypair WHERE id = ?1";
+pub const REQ_DELETE_ELLIPTIC_CURVE_KEYPAIR: &str =
+    "DELETE FROM elliptic_curve_keypair WHERE id = ?1";
+
+pub const REQ_GET_CLIENT_ONE_TIME_PQKEM_PREKEY_IDS: &str = "SELECT
+    otpp.id AS one_time_pqkem_prekey_id,
+    ip.id AS identified_pqkem_keypair_id,
+    ip.pqkem_keypair_id AS pqkem_keypair_id
+FROM
+    one_time_pqkem_prekey otpp
+JOIN
+    identified_pqkem_keypair ip ON otpp.identified_pqkem_keypair_id = ip.id
+WHERE
+    otpp.client_id = ?1 AND ip.uuid = ?2
+";
+pub const REQ_DELETE_ONE_TIME_PQKEM_PREKEY: &str =
+    "DELETE FROM one_time_pqkem_prekey WHERE id = ?1";
+pub const REQ_DELETE_IDENTIFIED_PQKEM_KEYPAIR: &str =
+    "DELETE FROM identified_pqkem_keypair WHERE id = ?1";
+pub const REQ_DELETE_PQKEM_KEYPAIR: &str = "DELETE FROM pqkem_keypair WHERE id = ?1";
+
+pub const REQ_GET_RATCHET_SESSION: &str = "SELECT
+    id,
+    associated_data,
+    root_key,
+    sending_ratchet_key_type,
+    sending_ratchet_public_key,
+    sending_ratchet_private_key,
+    receiving_ratchet_key_type,
+    receiving_ratchet_public_key,
+    sending_chain_key,
+    receiving_chain_key,
+    sending_message_number,
+    receiving_message_number,
+    previous_sending_chain_length,
+    peer_identity_key_type,
+    peer_identity_public_key
+FROM
+    ratchet_session
+WHERE
+    client_id = ?1 AND peer_uuid = ?2
+";
+pub const REQ_GET_RATCHET_SESSION_ID: &str =
+    "SELECT id FROM ratchet_session WHERE client_id = ?1 AND peer_uuid = ?2";
+pub const REQ_INSERT_RATCHET_SESSION: &str = "INSERT INTO ratchet_session (client_id, peer_uuid, associated_data, root_key, sending_ratchet_key_type, sending_ratchet_public_key, sending_ratchet_private_key, receiving_ratchet_key_type, receiving_ratchet_public_key, sending_chain_key, receiving_chain_key, sending_message_number, receiving_message_number, previous_sending_chain_length, peer_identity_key_type, peer_identity_public_key) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16) RETURNING id";
+pub const REQ_DELETE_RATCHET_SESSION: &str = "DELETE FROM ratchet_session WHERE id = ?1";
+
+pub const REQ_GET_SKIPPED_MESSAGE_KEYS: &str = "SELECT ratchet_public_key, message_number, message_key FROM skipped_message_key WHERE ratchet_session_id = ?1 ORDER BY id";
+pub const REQ_INSERT_SKIPPED_MESSAGE_KEY: &str = "INSERT INTO skipped_message_key (ratchet_session_id, ratchet_public_key, message_number, message_key) VALUES (?1, ?2, ?3, ?4) RETURNING id";
+pub const REQ_DELETE_SKIPPED_MESSAGE_KEYS: &str =
+    "DELETE FROM skipped_message_key WHERE ratchet_session_id = ?1";
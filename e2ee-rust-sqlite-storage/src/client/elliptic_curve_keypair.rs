@@ -16,7 +16,7 @@ pub fn insert_elliptic_curve_keypair(
         params![
             key.key_type.id(),
             key.public_key.bytes,
-            key.private_key.bytes
+            key.private_key.bytes.expose_secret()
         ],
         "elliptic_curve_keypair",
         connection,
@@ -1,6 +1,7 @@
 use e2ee_rust_common::{
     crypto::{curve::keys::IdentifiedEllipticCurveKeyPair, pqkem::keys::IdentifiedPQKEMKeyPair},
     pqxdh::private_bundle::PrivateBundle,
+    ratchet::session::RatchetSession,
     storage::{
         client::{errors::ClientStorageError, traits::ClientStorage},
         errors::StorageInterfaceError,
@@ -8,7 +9,7 @@ use e2ee_rust_common::{
 };
 use uuid::Uuid;
 
-use crate::{SQLiteStorage, ToStorageInterfaceError, CLIENT_SCHEMA_VERSION};
+use crate::{SQLiteStorage, ToStorageInterfaceError, CLIENT_MIGRATIONS, CLIENT_SCHEMA_VERSION};
 
 use super::{
     client::{
@@ -18,13 +19,22 @@ use super::{
     elliptic_curve_keypair::insert_elliptic_curve_keypair,
     identified_elliptic_curve_keypair::insert_identified_elliptic_curve_keypair,
     identified_pqkem_keypair::insert_identified_pqkem_keypair,
-    one_time_curve_prekey::insert_one_time_curve_prekey_set,
-    one_time_pqkem_prekey::insert_one_time_pqkem_prekey_set,
+    one_time_curve_prekey::{
+        delete_client_one_time_curve_prekey, insert_one_time_curve_prekey_set,
+    },
+    one_time_pqkem_prekey::{
+        delete_client_one_time_pqkem_prekey, insert_one_time_pqkem_prekey_set,
+    },
+    ratchet_session::{delete_ratchet_session, get_ratchet_session, replace_ratchet_session},
 };
 
 impl ClientStorage for SQLiteStorage {
     fn init_client(&self) -> Result<(), StorageInterfaceError> {
-        self.init(include_str!("schema_client.sql"), CLIENT_SCHEMA_VERSION)
+        self.init(
+            include_str!("schema_client.sql"),
+            CLIENT_SCHEMA_VERSION,
+            &CLIENT_MIGRATIONS,
+        )
     }
 
     fn contains_client(&self) -> Result<Option<i32>, StorageInterfaceError> {
@@ -196,4 +206,302 @@ impl ClientStorage for SQLiteStorage {
         insert_one_time_pqkem_prekey_set(client_db_id, new_signed_pqkem_prekeys, &conn)?;
         Ok(())
     }
+
+    fn remove_curve_one_time_prekey(&self, prekey_id: &Uuid) -> Result<(), StorageInterfaceError> {
+        self.remove_one_time_prekeys(Some(prekey_id), None)
+    }
+
+    fn remove_pqkem_one_time_prekey(&self, prekey_id: &Uuid) -> Result<(), StorageInterfaceError> {
+        self.remove_one_time_prekeys(None, Some(prekey_id))
+    }
+
+    fn remove_one_time_prekeys(
+        &self,
+        curve_prekey_id: Option<&Uuid>,
+        pqkem_prekey_id: Option<&Uuid>,
+    ) -> Result<(), StorageInterfaceError> {
+        // Get the connection
+        let mut conn = self.pool.get().unwrap();
+
+        // Get the client database id
+        let client_db_id =
+            self.contains_client()?
+                .ok_or(StorageInterfaceError::ClientStorageError(
+                    ClientStorageError::ClientNotFound,
+                ))?;
+
+        // Delete the prekeys and the keypairs they reference in a single transaction, which is rolled back if dropped before the commit
+        let transaction = conn.transaction().to_storage_interface_error()?;
+        if let Some(prekey_id) = curve_prekey_id {
+            delete_client_one_time_curve_prekey(client_db_id, prekey_id, &transaction)?;
+        }
+        if let Some(prekey_id) = pqkem_prekey_id {
+            delete_client_one_time_pqkem_prekey(client_db_id, prekey_id, &transaction)?;
+        }
+        transaction.commit().to_storage_interface_error()
+    }
+
+    fn load_session(
+        &self,
+        peer_id: &Uuid,
+    ) -> Result<Option<RatchetSession>, StorageInterfaceError> {
+        // Get the connection
+        let conn = self.pool.get().unwrap();
+
+        // Get the client database id
+        let client_db_id =
+            self.contains_client()?
+                .ok_or(StorageInterfaceError::ClientStorageError(
+                    ClientStorageError::ClientNotFound,
+                ))?;
+
+        // Get the ratchet session
+        get_ratchet_session(client_db_id, peer_id, &conn)
+    }
+
+    fn store_session(
+        &self,
+        peer_id: &Uuid,
+        session: &RatchetSession,
+    ) -> Result<(), StorageInterfaceError> {
+        // Get the connection
+        let mut conn = self.pool.get().unwrap();
+
+        // Get the client database id
+        let client_db_id =
+            self.contains_client()?
+                .ok_or(StorageInterfaceError::ClientStorageError(
+                    ClientStorageError::ClientNotFound,
+                ))?;
+
+        // Replace the ratchet session in a single transaction, which is rolled back if dropped before the commit
+        let transaction = conn.transaction().to_storage_interface_error()?;
+        replace_ratchet_session(client_db_id, peer_id, session, &transaction)?;
+        transaction.commit().to_storage_interface_error()
+    }
+
+    fn delete_session(&self, peer_id: &Uuid) -> Result<(), StorageInterfaceError> {
+        // Get the connection
+        let mut conn = self.pool.get().unwrap();
+
+        // Get the client database id
+        let client_db_id =
+            self.contains_client()?
+                .ok_or(StorageInterfaceError::ClientStorageError(
+                    ClientStorageError::ClientNotFound,
+                ))?;
+
+        // Delete the ratchet session and its skipped message keys in a single transaction
+        let transaction = conn.transaction().to_storage_interface_error()?;
+        delete_ratchet_session(client_db_id, peer_id, &transaction)?;
+        transaction.commit().to_storage_interface_error()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use e2ee_rust_common::{
+        crypto::{
+            aead::enum_aead_types::AEADType,
+            curve::{
+                curve25519::Curve25519, enum_elliptic_curve_type::EllipticCurveType,
+                traits::EllipticCurveAlgorithm,
+            },
+            pqkem::enum_pqkem_type::PQKEMType,
+        },
+        hash::enum_hash_types::HashType,
+        pqxdh::{parameters::PqxdhParameters, private_bundle::PrivateBundle},
+        ratchet::session::{RatchetSession, SkippedMessageKey},
+        storage::{client::traits::ClientStorage, storage_interface::StorageInterface},
+    };
+    use uuid::Uuid;
+
+    use crate::{
+        client::ratchet_session::{get_ratchet_session, replace_ratchet_session},
+        SQLiteStorage, CLIENT_SCHEMA_VERSION,
+    };
+
+    // Client database in the temporary directory, removed when dropped
+    struct TestDatabase {
+        storage: SQLiteStorage,
+        name: String,
+    }
+
+    impl TestDatabase {
+        fn new() -> Self {
+            let name = format!("test-client-{}", Uuid::new_v4());
+            let storage =
+                SQLiteStorage::new(&name, std::env::temp_dir().to_str().unwrap()).unwrap();
+            storage.init_client().unwrap();
+            TestDatabase { storage, name }
+        }
+    }
+
+    impl Drop for TestDatabase {
+        fn drop(&mut self) {
+            // Also remove the WAL files
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(
+                    std::env::temp_dir().join(format!("db_{}.sqlite{}", self.name, suffix)),
+                );
+            }
+        }
+    }
+
+    // Creates a client with a freshly generated key bundle
+    fn create_client(storage: &SQLiteStorage) {
+        let mut rng = rand::thread_rng();
+        let parameters = PqxdhParameters::new(
+            EllipticCurveType::CURVE25519,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap();
+        let private_bundle = PrivateBundle::new(&parameters, 1, 1, &mut rng);
+        storage
+            .create_client(&Uuid::new_v4(), &private_bundle)
+            .unwrap();
+    }
+
+    // Creates an initiator session which already skipped a message key
+    fn session() -> RatchetSession {
+        let mut rng = rand::thread_rng();
+        let curve = Curve25519 {};
+        let mut session = RatchetSession::new_initiator(
+            &[7u8; 32],
+            b"associated data",
+            &curve.generate_key_pair(&mut rng).public_key,
+            &curve.generate_key_pair(&mut rng).public_key,
+            &curve,
+            &HashType::SHA256,
+            &mut rng,
+        )
+        .unwrap();
+        session.skipped_message_keys.push(SkippedMessageKey {
+            ratchet_key: vec![1u8; 32],
+            message_number: 3,
+            message_key: [2u8; 32],
+        });
+        session
+    }
+
+    fn assert_same_session(session: &RatchetSession, other: &RatchetSession) {
+        assert_eq!(session.root_key, other.root_key);
+        assert_eq!(session.associated_data, other.associated_data);
+        assert_eq!(
+            session.peer_identity_key.encode_ec(),
+            other.peer_identity_key.encode_ec()
+        );
+        assert_eq!(session.sending_chain_key, other.sending_chain_key);
+        assert_eq!(
+            session.receiving_ratchet_key.as_ref().map(|k| &k.bytes),
+            other.receiving_ratchet_key.as_ref().map(|k| &k.bytes)
+        );
+        assert_eq!(
+            session.skipped_message_keys.len(),
+            other.skipped_message_keys.len()
+        );
+    }
+
+    #[test]
+    fn session_is_stored_and_replaced() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        create_client(storage);
+        let peer_uuid = Uuid::new_v4();
+
+        assert!(storage.load_session(&peer_uuid).unwrap().is_none());
+        let first_session = session();
+        storage.store_session(&peer_uuid, &first_session).unwrap();
+        assert_same_session(
+            &storage.load_session(&peer_uuid).unwrap().unwrap(),
+            &first_session,
+        );
+
+        let second_session = session();
+        storage.store_session(&peer_uuid, &second_session).unwrap();
+        assert_same_session(
+            &storage.load_session(&peer_uuid).unwrap().unwrap(),
+            &second_session,
+        );
+
+        storage.delete_session(&peer_uuid).unwrap();
+        assert!(storage.load_session(&peer_uuid).unwrap().is_none());
+    }
+
+    #[test]
+    fn clients_have_their_own_session_with_a_peer() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        create_client(storage);
+        create_client(storage);
+        let peer_uuid = Uuid::new_v4();
+
+        // Both clients of the database store a session with the same peer
+        let conn = storage.pool.get().unwrap();
+        let client_db_ids: Vec<i32> = conn
+            .prepare("SELECT id FROM client ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let [client_db_id, other_client_db_id] = client_db_ids[..] else {
+            panic!("expected two clients, found {:?}", client_db_ids);
+        };
+        let first_session = session();
+        let second_session = session();
+        replace_ratchet_session(client_db_id, &peer_uuid, &first_session, &conn).unwrap();
+        replace_ratchet_session(other_client_db_id, &peer_uuid, &second_session, &conn).unwrap();
+
+        // Each client loads its own session
+        let first_loaded = get_ratchet_session(client_db_id, &peer_uuid, &conn)
+            .unwrap()
+            .unwrap();
+        let second_loaded = get_ratchet_session(other_client_db_id, &peer_uuid, &conn)
+            .unwrap()
+            .unwrap();
+        assert_same_session(&first_loaded, &first_session);
+        assert_same_session(&second_loaded, &second_session);
+        assert_ne!(first_loaded.root_key, second_loaded.root_key);
+
+        // The storage loads the session of the client it uses
+        let loaded_session = storage.load_session(&peer_uuid).unwrap().unwrap();
+        if storage.contains_client().unwrap() == Some(client_db_id) {
+            assert_same_session(&loaded_session, &first_session);
+        } else {
+            assert_same_session(&loaded_session, &second_session);
+        }
+    }
+
+    #[test]
+    fn version_1_database_is_upgraded() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        create_client(storage);
+
+        // Bring the database back to version 1, before the sessions were stored
+        let conn = storage.pool.get().unwrap();
+        conn.execute_batch(
+            "DROP TABLE skipped_message_key; DROP TABLE ratchet_session; PRAGMA user_version = 1;",
+        )
+        .unwrap();
+
+        storage.init_client().unwrap();
+        let version: i32 = conn
+            .query_row("PRAGMA user_version;", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, CLIENT_SCHEMA_VERSION);
+
+        // The client is kept and the sessions are stored
+        let peer_uuid = Uuid::new_v4();
+        let session = session();
+        storage.store_session(&peer_uuid, &session).unwrap();
+        assert_same_session(
+            &storage.load_session(&peer_uuid).unwrap().unwrap(),
+            &session,
+        );
+    }
 }
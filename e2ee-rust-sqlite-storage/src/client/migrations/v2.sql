@@ -0,0 +1,35 @@
+-- Upgrades a version 1 client database to version 2
+PRAGMA user_version = 2;
+
+-- Create the Ratchet Session table
+CREATE TABLE IF NOT EXISTS ratchet_session (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    client_id INTEGER NOT NULL,
+    peer_uuid BLOB NOT NULL,
+    associated_data BLOB NOT NULL,
+    root_key BLOB NOT NULL,
+    sending_ratchet_key_type INTEGER NOT NULL,
+    sending_ratchet_public_key BLOB NOT NULL,
+    sending_ratchet_private_key BLOB NOT NULL,
+    receiving_ratchet_key_type INTEGER,
+    receiving_ratchet_public_key BLOB,
+    sending_chain_key BLOB,
+    receiving_chain_key BLOB,
+    sending_message_number INTEGER NOT NULL,
+    receiving_message_number INTEGER NOT NULL,
+    previous_sending_chain_length INTEGER NOT NULL,
+    peer_identity_key_type INTEGER NOT NULL,
+    peer_identity_public_key BLOB NOT NULL,
+    UNIQUE (client_id, peer_uuid),
+    FOREIGN KEY (client_id) REFERENCES client(id)
+);
+
+-- Create the Skipped Message Key table
+CREATE TABLE IF NOT EXISTS skipped_message_key (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ratchet_session_id INTEGER NOT NULL,
+    ratchet_public_key BLOB NOT NULL,
+    message_number INTEGER NOT NULL,
+    message_key BLOB NOT NULL,
+    FOREIGN KEY (ratchet_session_id) REFERENCES ratchet_session(id)
+);
\ No newline at end of file
@@ -7,3 +7,4 @@ pub mod implementation;
 pub mod one_time_curve_prekey;
 pub mod one_time_pqkem_prekey;
 pub mod pqkem_keypair;
+pub mod ratchet_session;
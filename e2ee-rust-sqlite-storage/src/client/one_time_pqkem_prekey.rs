@@ -1,16 +1,21 @@
 use e2ee_rust_common::{
     crypto::pqkem::keys::{IdentifiedPQKEMKeyPair, PQKEMKeyPair},
-    storage::errors::StorageInterfaceError,
+    storage::{client::errors::ClientStorageError, errors::StorageInterfaceError},
 };
 use rusqlite::{params, Connection};
+use uuid::Uuid;
 
 use crate::{
-    utils::{insert_returning_id, uuid_from_bytes},
+    utils::{insert_returning_id, perform_delete, uuid_from_bytes},
     ToStorageInterfaceError,
 };
 
 use super::{
-    consts::{REQ_GET_CLIENT_ONE_TIME_PQKEM_PREKEY, REQ_INSERT_ONE_TIME_PQKEM_PREKEY},
+    consts::{
+        REQ_DELETE_IDENTIFIED_PQKEM_KEYPAIR, REQ_DELETE_ONE_TIME_PQKEM_PREKEY,
+        REQ_DELETE_PQKEM_KEYPAIR, REQ_GET_CLIENT_ONE_TIME_PQKEM_PREKEY,
+        REQ_GET_CLIENT_ONE_TIME_PQKEM_PREKEY_IDS, REQ_INSERT_ONE_TIME_PQKEM_PREKEY,
+    },
     identified_pqkem_keypair::insert_identified_pqkem_keypair,
 };
 
@@ -83,3 +88,39 @@ pub fn get_client_one_time_pqkem_prekey_set(
     // Return the one time PQKEM prekeys
     Ok(one_time_pqkem_prekeys)
 }
+
+pub fn delete_client_one_time_pqkem_prekey(
+    client_db_id: i32,
+    prekey_uuid: &Uuid,
+    connection: &Connection,
+) -> Result<(), StorageInterfaceError> {
+    // Prepare the statement
+    let mut stmt = connection
+        .prepare_cached(REQ_GET_CLIENT_ONE_TIME_PQKEM_PREKEY_IDS)
+        .to_storage_interface_error()?;
+
+    // Get the database ids of the prekey rows
+    let (one_time_pqkem_prekey_id, identified_keypair_id, keypair_id): (i32, i32, i32) = stmt
+        .query_row(params![client_db_id, prekey_uuid.as_bytes()], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                StorageInterfaceError::ClientStorageError(ClientStorageError::PrekeyNotFound)
+            }
+            e => StorageInterfaceError::CustomError(format!("SQLite error: {}", e)),
+        })?;
+
+    // Delete the one-time PQKEM prekey, then the keypair it references
+    perform_delete(
+        REQ_DELETE_ONE_TIME_PQKEM_PREKEY,
+        params![one_time_pqkem_prekey_id],
+        connection,
+    )?;
+    perform_delete(
+        REQ_DELETE_IDENTIFIED_PQKEM_KEYPAIR,
+        params![identified_keypair_id],
+        connection,
+    )?;
+    perform_delete(REQ_DELETE_PQKEM_KEYPAIR, params![keypair_id], connection)
+}
@@ -14,7 +14,7 @@ pub fn insert_pqkem_keypair(
         params![
             key.key_type.id(),
             key.public_key.bytes,
-            key.private_key.bytes
+            key.private_key.bytes.expose_secret()
         ],
         "pqkem_keypair",
         connection,
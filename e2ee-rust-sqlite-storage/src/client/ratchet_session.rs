@@ -0,0 +1,237 @@
+use e2ee_rust_common::{
+    crypto::curve::keys::{EllipticCurveKeyPair, EllipticCurvePublicKey},
+    ratchet::session::{RatchetSession, SkippedMessageKey},
+    storage::{client::errors::ClientStorageError, errors::StorageInterfaceError},
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use crate::{
+    utils::{insert_returning_id, perform_delete},
+    ToStorageInterfaceError,
+};
+
+use super::consts::{
+    REQ_DELETE_RATCHET_SESSION, REQ_DELETE_SKIPPED_MESSAGE_KEYS, REQ_GET_RATCHET_SESSION,
+    REQ_GET_RATCHET_SESSION_ID, REQ_GET_SKIPPED_MESSAGE_KEYS, REQ_INSERT_RATCHET_SESSION,
+    REQ_INSERT_SKIPPED_MESSAGE_KEY,
+};
+
+// Converts a stored symmetric key to a fixed size array
+fn symmetric_key_from_bytes(bytes: &[u8]) -> Result<[u8; 32], StorageInterfaceError> {
+    bytes
+        .try_into()
+        .map_err(|_| StorageInterfaceError::BadKeySize)
+}
+
+pub fn get_ratchet_session_id(
+    client_db_id: i32,
+    peer_uuid: &Uuid,
+    connection: &Connection,
+) -> Result<Option<i32>, StorageInterfaceError> {
+    // Prepare the statement
+    let mut stmt = connection
+        .prepare_cached(REQ_GET_RATCHET_SESSION_ID)
+        .to_storage_interface_error()?;
+
+    // Execute the statement
+    stmt.query_row(params![client_db_id, peer_uuid.as_bytes()], |row| {
+        row.get(0)
+    })
+    .optional()
+    .to_storage_interface_error()
+}
+
+pub fn get_skipped_message_keys(
+    ratchet_session_id: i32,
+    connection: &Connection,
+) -> Result<Vec<SkippedMessageKey>, StorageInterfaceError> {
+    // Prepare the statement
+    let mut stmt = connection
+        .prepare_cached(REQ_GET_SKIPPED_MESSAGE_KEYS)
+        .to_storage_interface_error()?;
+
+    // Execute the statement
+    let mut rows = stmt
+        .query(params![ratchet_session_id])
+        .to_storage_interface_error()?;
+
+    // Loop through the rows, which are ordered from the oldest to the newest key
+    let mut skipped_message_keys = Vec::new();
+    while let Some(row) = rows.next().to_storage_interface_error()? {
+        let ratchet_key: Vec<u8> = row.get(0).to_storage_interface_error()?;
+        let message_number: u32 = row.get(1).to_storage_interface_error()?;
+        let message_key: Vec<u8> = row.get(2).to_storage_interface_error()?;
+
+        skipped_message_keys.push(SkippedMessageKey {
+            ratchet_key,
+            message_number,
+            message_key: symmetric_key_from_bytes(&message_key)?,
+        });
+    }
+
+    Ok(skipped_message_keys)
+}
+
+pub fn get_ratchet_session(
+    client_db_id: i32,
+    peer_uuid: &Uuid,
+    connection: &Connection,
+) -> Result<Option<RatchetSession>, StorageInterfaceError> {
+    // Prepare the statement
+    let mut stmt = connection
+        .prepare_cached(REQ_GET_RATCHET_SESSION)
+        .to_storage_interface_error()?;
+
+    // Execute the statement
+    let mut rows = stmt
+        .query(params![client_db_id, peer_uuid.as_bytes()])
+        .to_storage_interface_error()?;
+
+    // Return None if there is no session with this peer
+    let row = match rows.next().to_storage_interface_error()? {
+        Some(row) => row,
+        None => return Ok(None),
+    };
+
+    // Get the values
+    let ratchet_session_id: i32 = row.get(0).to_storage_interface_error()?;
+    let associated_data: Vec<u8> = row.get(1).to_storage_interface_error()?;
+    let root_key: Vec<u8> = row.get(2).to_storage_interface_error()?;
+    let sending_ratchet_key_type: i32 = row.get(3).to_storage_interface_error()?;
+    let sending_ratchet_public_key: Vec<u8> = row.get(4).to_storage_interface_error()?;
+    let sending_ratchet_private_key: Vec<u8> = row.get(5).to_storage_interface_error()?;
+    let receiving_ratchet_key_type: Option<i32> = row.get(6).to_storage_interface_error()?;
+    let receiving_ratchet_public_key: Option<Vec<u8>> = row.get(7).to_storage_interface_error()?;
+    let sending_chain_key: Option<Vec<u8>> = row.get(8).to_storage_interface_error()?;
+    let receiving_chain_key: Option<Vec<u8>> = row.get(9).to_storage_interface_error()?;
+    let sending_message_number: u32 = row.get(10).to_storage_interface_error()?;
+    let receiving_message_number: u32 = row.get(11).to_storage_interface_error()?;
+    let previous_sending_chain_length: u32 = row.get(12).to_storage_interface_error()?;
+    let peer_identity_key_type: i32 = row.get(13).to_storage_interface_error()?;
+    let peer_identity_public_key: Vec<u8> = row.get(14).to_storage_interface_error()?;
+
+    // Rebuild the peer ratchet key, which is only known once a message was received
+    let receiving_ratchet_key = match (receiving_ratchet_key_type, receiving_ratchet_public_key) {
+        (Some(key_type), Some(public_key)) => Some(EllipticCurvePublicKey::from_bytes(
+            key_type as u8,
+            public_key,
+        )?),
+        _ => None,
+    };
+
+    // Create the session
+    Ok(Some(RatchetSession {
+        associated_data,
+        peer_identity_key: EllipticCurvePublicKey::from_bytes(
+            peer_identity_key_type as u8,
+            peer_identity_public_key,
+        )?,
+        root_key: symmetric_key_from_bytes(&root_key)?,
+        sending_ratchet_key: EllipticCurveKeyPair::from_bytes(
+            sending_ratchet_key_type as u8,
+            sending_ratchet_public_key,
+            sending_ratchet_private_key,
+        )?,
+        receiving_ratchet_key,
+        sending_chain_key: sending_chain_key
+            .map(|k| symmetric_key_from_bytes(&k))
+            .transpose()?,
+        receiving_chain_key: receiving_chain_key
+            .map(|k| symmetric_key_from_bytes(&k))
+            .transpose()?,
+        sending_message_number,
+        receiving_message_number,
+        previous_sending_chain_length,
+        skipped_message_keys: get_skipped_message_keys(ratchet_session_id, connection)?,
+    }))
+}
+
+// Replaces the session with a peer and its skipped message keys
+// This must run inside a transaction so that the session is never partially written
+pub fn replace_ratchet_session(
+    client_db_id: i32,
+    peer_uuid: &Uuid,
+    session: &RatchetSession,
+    connection: &Connection,
+) -> Result<(), StorageInterfaceError> {
+    // Delete the previous session, if any
+    if let Some(ratchet_session_id) = get_ratchet_session_id(client_db_id, peer_uuid, connection)? {
+        delete_ratchet_session_by_id(ratchet_session_id, connection)?;
+    }
+
+    // Insert the session
+    let receiving_ratchet_key = session.receiving_ratchet_key.as_ref();
+    let ratchet_session_id = insert_returning_id(
+        REQ_INSERT_RATCHET_SESSION,
+        params![
+            client_db_id,
+            peer_uuid.as_bytes(),
+            session.associated_data,
+            session.root_key,
+            session.sending_ratchet_key.key_type.id(),
+            session.sending_ratchet_key.public_key.bytes,
+            session
+                .sending_ratchet_key
+                .private_key
+                .bytes
+                .expose_secret(),
+            receiving_ratchet_key.map(|k| k.key_type.id()),
+            receiving_ratchet_key.map(|k| &k.bytes),
+            session.sending_chain_key,
+            session.receiving_chain_key,
+            session.sending_message_number,
+            session.receiving_message_number,
+            session.previous_sending_chain_length,
+            session.peer_identity_key.key_type.id(),
+            session.peer_identity_key.bytes
+        ],
+        "ratchet_session",
+        connection,
+    )?;
+
+    // Insert the skipped message keys
+    for skipped_message_key in &session.skipped_message_keys {
+        insert_returning_id(
+            REQ_INSERT_SKIPPED_MESSAGE_KEY,
+            params![
+                ratchet_session_id,
+                skipped_message_key.ratchet_key,
+                skipped_message_key.message_number,
+                skipped_message_key.message_key
+            ],
+            "skipped_message_key",
+            connection,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn delete_ratchet_session_by_id(
+    ratchet_session_id: i32,
+    connection: &Connection,
+) -> Result<(), StorageInterfaceError> {
+    // Delete the skipped message keys, then the session they reference
+    perform_delete(
+        REQ_DELETE_SKIPPED_MESSAGE_KEYS,
+        params![ratchet_session_id],
+        connection,
+    )?;
+    perform_delete(
+        REQ_DELETE_RATCHET_SESSION,
+        params![ratchet_session_id],
+        connection,
+    )
+}
+
+pub fn delete_ratchet_session(
+    client_db_id: i32,
+    peer_uuid: &Uuid,
+    connection: &Connection,
+) -> Result<(), StorageInterfaceError> {
+    let ratchet_session_id = get_ratchet_session_id(client_db_id, peer_uuid, connection)?.ok_or(
+        StorageInterfaceError::ClientStorageError(ClientStorageError::SessionNotFound),
+    )?;
+    delete_ratchet_session_by_id(ratchet_session_id, connection)
+}
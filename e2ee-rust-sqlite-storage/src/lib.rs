@@ -2,6 +2,8 @@ mod client;
 mod server;
 mod utils;
 
+use std::time::Duration;
+
 use e2ee_rust_common::storage::{
     errors::{InitializationError, StorageInterfaceError},
     storage_interface::StorageInterface,
@@ -10,9 +12,18 @@ use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use server::consts::REQ_FIND_TABLES;
 
-const SERVER_SCHEMA_VERSION: i32 = 1;
-const CLIENT_SCHEMA_VERSION: i32 = 1;
+const SERVER_SCHEMA_VERSION: i32 = 2;
+const CLIENT_SCHEMA_VERSION: i32 = 2;
+
+// How long a connection waits for the write lock held by another connection
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
+// Upgrade scripts, the script at index i upgrades a database from version i + 1 to version i + 2
+const SERVER_MIGRATIONS: [&str; 1] = [include_str!("server/migrations/v2.sql")];
+const CLIENT_MIGRATIONS: [&str; 1] = [include_str!("client/migrations/v2.sql")];
+
+// Cloning the storage shares its connection pool, so that every thread of a process goes through the same pool
+#[derive(Clone)]
 pub struct SQLiteStorage {
     pool: Pool<SqliteConnectionManager>,
 }
@@ -22,9 +33,10 @@ impl SQLiteStorage {
         &self,
         schema: &'static str,
         expected_version: i32,
+        migrations: &[&'static str],
     ) -> Result<(), StorageInterfaceError> {
         // Get the connection
-        let conn = self.pool.get().unwrap();
+        let mut conn = self.pool.get().unwrap();
 
         // Check if the database exists
         let table_exists = conn
@@ -43,14 +55,33 @@ impl SQLiteStorage {
                 .map_err(|_| {
                     StorageInterfaceError::InitializationError(InitializationError::NoSchemaVersion)
                 })?;
-            if schema_version != expected_version {
+            if schema_version > expected_version || schema_version < 1 {
                 return Err(StorageInterfaceError::InitializationError(
                     InitializationError::IncompatibleSchemaVersion(
                         schema_version,
-                        SERVER_SCHEMA_VERSION,
+                        expected_version,
                     ),
                 ));
             }
+
+            // Upgrade older databases one version at a time, each script setting the version it upgrades to
+            for version in schema_version..expected_version {
+                let migration = migrations.get(version as usize - 1).ok_or(
+                    StorageInterfaceError::InitializationError(
+                        InitializationError::IncompatibleSchemaVersion(
+                            schema_version,
+                            expected_version,
+                        ),
+                    ),
+                )?;
+                let transaction = conn.transaction().to_storage_interface_error()?;
+                transaction.execute_batch(migration).map_err(|_| {
+                    StorageInterfaceError::InitializationError(
+                        InitializationError::CannotMigrateSchema(version + 1),
+                    )
+                })?;
+                transaction.commit().to_storage_interface_error()?;
+            }
         }
 
         // Return Ok if the database schema is initialized successfully
@@ -61,7 +92,11 @@ impl SQLiteStorage {
 impl StorageInterface for SQLiteStorage {
     fn new(application_name: &str, root_path: &str) -> Result<Self, StorageInterfaceError> {
         let db_path = format!("{}/db_{}.sqlite", root_path, application_name);
-        let manager = SqliteConnectionManager::file(db_path);
+        // WAL lets readers run while another connection writes, and a connection waits for a busy database instead of failing
+        let manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
+            conn.busy_timeout(BUSY_TIMEOUT)?;
+            conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))
+        });
         let pool = r2d2::Pool::new(manager).map_err(|_| {
             StorageInterfaceError::InitializationError(InitializationError::CannotCreateConnection)
         })?;
@@ -1,12 +1,16 @@
 use e2ee_rust_common::storage::{
-    errors::StorageInterfaceError, server::client_structs::ClientKeyBundle,
+    errors::StorageInterfaceError,
+    server::{client_structs::ClientKeyBundle, errors::ServerStorageError},
 };
-use rusqlite::{params, Connection};
+use rusqlite::{ffi, params, Connection};
 use uuid::Uuid;
 
-use crate::utils::insert_returning_id;
+use crate::ToStorageInterfaceError;
 
-use super::{consts::REQ_INSERT_CLIENT, key_bundle::insert_key_bundle};
+use super::{
+    consts::{REQ_GET_CLIENT_ID, REQ_INSERT_CLIENT},
+    key_bundle::insert_key_bundle,
+};
 
 pub fn insert_client(
     client_uuid: Uuid,
@@ -17,10 +21,41 @@ pub fn insert_client(
     let key_bundle_id = insert_key_bundle(key_bundle, connection)?;
 
     // Insert the client and return the new ID
-    Ok(insert_returning_id(
-        REQ_INSERT_CLIENT,
-        params![client_uuid.as_bytes(), key_bundle_id],
-        "client",
-        connection,
-    )?)
+    // The client UUID is UNIQUE, so of concurrent registrations of the same client only the first one is stored
+    connection
+        .query_row(
+            REQ_INSERT_CLIENT,
+            params![client_uuid.as_bytes(), key_bundle_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(error, _)
+                if error.extended_code == ffi::SQLITE_CONSTRAINT_UNIQUE =>
+            {
+                StorageInterfaceError::ServerStorageError(ServerStorageError::ClientAlreadyExists)
+            }
+            e => StorageInterfaceError::CustomError(format!("SQLite error: {}", e)),
+        })
+}
+
+pub fn get_client_id(
+    client_uuid: Uuid,
+    connection: &Connection,
+) -> Result<i32, StorageInterfaceError> {
+    // Create the statement
+    let mut stmt = connection
+        .prepare_cached(REQ_GET_CLIENT_ID)
+        .to_storage_interface_error()?;
+
+    // Execute the statement and get the result
+    let mut rows = stmt
+        .query([client_uuid.as_bytes()])
+        .to_storage_interface_error()?;
+    if let Some(row) = rows.next().to_storage_interface_error()? {
+        Ok(row.get(0).to_storage_interface_error()?)
+    } else {
+        Err(StorageInterfaceError::ServerStorageError(
+            ServerStorageError::ClientNotFound,
+        ))
+    }
 }
@@ -3,6 +3,8 @@ pub const REQ_FIND_TABLES: &str = "SELECT name FROM sqlite_master WHERE type='ta
 pub const REQ_INSERT_CLIENT: &str =
     "INSERT INTO clients (client_uuid, client_key_bundle_id) VALUES (?1, ?2) RETURNING id";
 
+pub const REQ_GET_CLIENT_ID: &str = "SELECT id FROM clients WHERE client_uuid = ?1";
+
 pub const REQ_GET_KEY_BUNDLE_ID: &str =
     "SELECT client_key_bundle_id FROM clients WHERE client_uuid = ?1";
 pub const REQ_QUERY_KEY_BUNDLE: &str = "SELECT identity_key_id, identity_key_timestamp, signed_curve_prekey_id, signed_curve_prekey_timestamp, signed_last_resort_pqkem_prekey_id, signed_last_resort_pqkem_prekey_timestamp FROM key_bundle WHERE id = ?1";
@@ -58,3 +60,9 @@ pub const REQ_QUERY_SIGNED_ONE_TIME_PQKEM_PREKEY_SET: &str =
 pub const REQ_INSERT_SIGNED_ONE_TIME_PQKEM_PREKEY: &str = "INSERT INTO signed_one_time_pqkem_prekey (prekey_id, key_bundle_id) VALUES (?1, ?2) RETURNING id";
 pub const REQ_DELETE_SIGNED_ONE_TIME_PQKEM_PREKEY: &str =
     "DELETE FROM signed_one_time_pqkem_prekey WHERE id = ?1";
+
+pub const REQ_QUERY_MAILBOX_ENVELOPES: &str = "SELECT uuid, sender_uuid, envelope_type, payload, timestamp FROM mailbox_envelope WHERE recipient_client_id = ?1 ORDER BY id";
+pub const REQ_QUERY_NEXT_MAILBOX_ENVELOPE: &str = "SELECT uuid, sender_uuid, envelope_type, payload, timestamp FROM mailbox_envelope WHERE recipient_client_id = ?1 ORDER BY id LIMIT 1";
+pub const REQ_INSERT_MAILBOX_ENVELOPE: &str = "INSERT INTO mailbox_envelope (uuid, recipient_client_id, sender_uuid, envelope_type, payload, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id";
+pub const REQ_DELETE_MAILBOX_ENVELOPE: &str =
+    "DELETE FROM mailbox_envelope WHERE recipient_client_id = ?1 AND uuid = ?2";
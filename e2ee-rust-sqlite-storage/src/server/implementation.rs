@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use e2ee_rust_common::{
     crypto::curve::keys::IdentifiedEllipticCurvePublicKey,
+    messages::server::server_envelope::ServerEnvelope,
     pqxdh::{
         one_time_curve_prekey_set::OneTimeCurvePrekeySet, signed_curve_prekey::SignedCurvePrekey,
         signed_one_time_pqkem_prekey_set::SignedOneTimePqkemPrekeySet,
@@ -11,6 +12,7 @@ use e2ee_rust_common::{
         server::{client_structs::ClientInformation, traits::ServerStorage},
     },
 };
+use rusqlite::TransactionBehavior;
 use uuid::Uuid;
 
 use crate::{
@@ -21,15 +23,19 @@ use crate::{
         signed_one_time_pqkem_prekey::pop_signed_one_time_pqkem_prekey_from_set,
         signed_pqkem_prekey::delete_signed_pqkem_public_key,
     },
-    SQLiteStorage, SERVER_SCHEMA_VERSION,
+    SQLiteStorage, ToStorageInterfaceError, SERVER_MIGRATIONS, SERVER_SCHEMA_VERSION,
 };
 
 use super::{
-    clients::insert_client,
+    clients::{get_client_id, insert_client},
     key_bundle::{
         get_client_key_bundle_id, get_key_bundle_from_id, update_key_bundle_signed_curve_prekey,
         update_key_bundle_signed_last_resort_pqkem_prekey,
     },
+    mailbox_envelope::{
+        delete_mailbox_envelope, get_mailbox_envelopes, get_next_mailbox_envelope,
+        insert_mailbox_envelope,
+    },
     one_time_curve_prekey::insert_one_time_curve_prekey_set,
     signed_curve_prekey::insert_signed_curve_prekey,
     signed_one_time_pqkem_prekey::insert_signed_one_time_pqkem_prekey_set,
@@ -38,7 +44,11 @@ use super::{
 
 impl ServerStorage for SQLiteStorage {
     fn init_server(&self) -> Result<(), StorageInterfaceError> {
-        self.init(include_str!("schema_server.sql"), SERVER_SCHEMA_VERSION)
+        self.init(
+            include_str!("schema_server.sql"),
+            SERVER_SCHEMA_VERSION,
+            &SERVER_MIGRATIONS,
+        )
     }
 
     fn get_client(&self, client_id: &Uuid) -> Result<ClientInformation, StorageInterfaceError> {
@@ -60,9 +70,14 @@ impl ServerStorage for SQLiteStorage {
         client: &ClientInformation,
     ) -> Result<(), StorageInterfaceError> {
         // Get the connection
-        let conn = self.pool.get().unwrap();
-
-        insert_client(client_id, &client.key_bundle, &conn)?;
+        let mut conn = self.pool.get().unwrap();
+
+        // Insert the key bundle and the client together, so that a rejected registration leaves no key bundle behind
+        let transaction = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .to_storage_interface_error()?;
+        insert_client(client_id, &client.key_bundle, &transaction)?;
+        transaction.commit().to_storage_interface_error()?;
         Ok(())
     }
 
@@ -152,13 +167,21 @@ impl ServerStorage for SQLiteStorage {
         client_id: Uuid,
     ) -> Result<Option<IdentifiedEllipticCurvePublicKey>, StorageInterfaceError> {
         // Get the connection
-        let conn = self.pool.get().unwrap();
+        let mut conn = self.pool.get().unwrap();
+
+        // Read and delete the prekey in a single write transaction, so that concurrent requests never hand out the same one time prekey
+        let transaction = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .to_storage_interface_error()?;
 
         // Get the client's key bundle ID
-        let key_bundle_id = get_client_key_bundle_id(client_id, &conn)?;
+        let key_bundle_id = get_client_key_bundle_id(client_id, &transaction)?;
 
         // Pop the prekey from the database
-        pop_one_time_curve_prekey_from_set(key_bundle_id, &conn)
+        let prekey = pop_one_time_curve_prekey_from_set(key_bundle_id, &transaction)?;
+        transaction.commit().to_storage_interface_error()?;
+
+        Ok(prekey)
     }
 
     fn add_signed_one_time_pqkem_prekeys(
@@ -183,12 +206,338 @@ impl ServerStorage for SQLiteStorage {
         client_id: Uuid,
     ) -> Result<Option<SignedPQKEMPrekey>, StorageInterfaceError> {
         // Get the connection
-        let conn = self.pool.get().unwrap();
+        let mut conn = self.pool.get().unwrap();
+
+        // Read and delete the prekey in a single write transaction, so that concurrent requests never hand out the same one time prekey
+        let transaction = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .to_storage_interface_error()?;
 
         // Get the client's key bundle ID
-        let key_bundle_id = get_client_key_bundle_id(client_id, &conn)?;
+        let key_bundle_id = get_client_key_bundle_id(client_id, &transaction)?;
 
         // Pop the prekey from the database
-        pop_signed_one_time_pqkem_prekey_from_set(key_bundle_id, &conn)
+        let prekey = pop_signed_one_time_pqkem_prekey_from_set(key_bundle_id, &transaction)?;
+        transaction.commit().to_storage_interface_error()?;
+
+        Ok(prekey)
+    }
+
+    fn enqueue_envelope(
+        &self,
+        recipient_id: Uuid,
+        envelope: &ServerEnvelope,
+    ) -> Result<(), StorageInterfaceError> {
+        // Get the connection
+        let conn = self.pool.get().unwrap();
+
+        // Get the recipient's database ID
+        let recipient_client_id = get_client_id(recipient_id, &conn)?;
+
+        // Add the envelope to the mailbox
+        insert_mailbox_envelope(envelope, recipient_client_id, &conn)?;
+
+        Ok(())
+    }
+
+    fn get_envelopes(
+        &self,
+        recipient_id: Uuid,
+    ) -> Result<Vec<ServerEnvelope>, StorageInterfaceError> {
+        // Get the connection
+        let conn = self.pool.get().unwrap();
+
+        // Get the recipient's database ID
+        let recipient_client_id = get_client_id(recipient_id, &conn)?;
+
+        // Get the envelopes from the mailbox
+        get_mailbox_envelopes(recipient_client_id, &conn)
+    }
+
+    fn get_next_envelope(
+        &self,
+        recipient_id: Uuid,
+    ) -> Result<Option<ServerEnvelope>, StorageInterfaceError> {
+        // Get the connection
+        let conn = self.pool.get().unwrap();
+
+        // Get the recipient's database ID
+        let recipient_client_id = get_client_id(recipient_id, &conn)?;
+
+        // Get the oldest envelope from the mailbox
+        get_next_mailbox_envelope(recipient_client_id, &conn)
+    }
+
+    fn delete_envelope(
+        &self,
+        recipient_id: Uuid,
+        envelope_id: &Uuid,
+    ) -> Result<(), StorageInterfaceError> {
+        // Get the connection
+        let conn = self.pool.get().unwrap();
+
+        // Get the recipient's database ID
+        let recipient_client_id = get_client_id(recipient_id, &conn)?;
+
+        // Delete the envelope from the mailbox
+        delete_mailbox_envelope(recipient_client_id, envelope_id, &conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use e2ee_rust_common::{
+        crypto::{
+            aead::enum_aead_types::AEADType, curve::enum_elliptic_curve_type::EllipticCurveType,
+            pqkem::enum_pqkem_type::PQKEMType,
+        },
+        hash::enum_hash_types::HashType,
+        messages::server::server_envelope::{ServerEnvelope, ServerEnvelopeType},
+        pqxdh::{
+            parameters::PqxdhParameters, private_bundle::PrivateBundle,
+            registration_bundle::RegistrationBundle,
+        },
+        storage::{
+            errors::StorageInterfaceError,
+            server::{
+                client_structs::{ClientInformation, ClientKeyBundle},
+                errors::ServerStorageError,
+                traits::ServerStorage,
+            },
+            storage_interface::StorageInterface,
+        },
+    };
+    use uuid::Uuid;
+
+    use crate::{SQLiteStorage, SERVER_SCHEMA_VERSION};
+
+    // Server database in the temporary directory, removed when dropped
+    struct TestDatabase {
+        storage: SQLiteStorage,
+        name: String,
+    }
+
+    impl TestDatabase {
+        fn new() -> Self {
+            let name = format!("test-server-{}", Uuid::new_v4());
+            let storage =
+                SQLiteStorage::new(&name, std::env::temp_dir().to_str().unwrap()).unwrap();
+            storage.init_server().unwrap();
+            TestDatabase { storage, name }
+        }
+    }
+
+    impl Drop for TestDatabase {
+        fn drop(&mut self) {
+            // Also remove the WAL files
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(
+                    std::env::temp_dir().join(format!("db_{}.sqlite{}", self.name, suffix)),
+                );
+            }
+        }
+    }
+
+    // Freshly generated key bundle of a client
+    fn client_information() -> ClientInformation {
+        let mut rng = rand::thread_rng();
+        let parameters = PqxdhParameters::new(
+            EllipticCurveType::CURVE25519,
+            HashType::SHA256,
+            "PQXDHTestApplication",
+            PQKEMType::KYBER512,
+            AEADType::AES256GCM,
+        )
+        .unwrap();
+        let private_bundle = PrivateBundle::new(&parameters, 1, 1, &mut rng);
+        let registration_bundle = RegistrationBundle::from_private_bundle(
+            &private_bundle,
+            parameters.curve_type(),
+            &mut rng,
+        )
+        .unwrap();
+
+        let now = Utc::now();
+        ClientInformation {
+            key_bundle: ClientKeyBundle {
+                identity_key: (registration_bundle.identity_key, now),
+                signed_curve_prekey: (registration_bundle.signed_curve_prekey, now),
+                signed_last_resort_pqkem_prekey: (
+                    registration_bundle.signed_last_resort_pqkem_prekey,
+                    now,
+                ),
+                one_time_curve_prekeys: registration_bundle.one_time_curve_prekeys,
+                signed_one_time_pqkem_prekeys: registration_bundle.one_time_pqkem_prekeys,
+            },
+        }
+    }
+
+    // Registers a client with a freshly generated key bundle
+    fn add_client(storage: &SQLiteStorage) -> Uuid {
+        let client_id = Uuid::new_v4();
+        storage
+            .add_client(client_id, &client_information())
+            .unwrap();
+        client_id
+    }
+
+    fn count_key_bundles(storage: &SQLiteStorage) -> i64 {
+        storage
+            .pool
+            .get()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM key_bundle;", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn client_is_only_added_once() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        let client_id = add_client(storage);
+        let key_bundles = count_key_bundles(storage);
+
+        // The second registration is rejected by the UNIQUE constraint and leaves no key bundle behind
+        assert!(matches!(
+            storage.add_client(client_id, &client_information()),
+            Err(StorageInterfaceError::ServerStorageError(
+                ServerStorageError::ClientAlreadyExists
+            ))
+        ));
+        assert_eq!(count_key_bundles(storage), key_bundles);
+    }
+
+    fn envelope(sender_uuid: Uuid, payload: &[u8]) -> ServerEnvelope {
+        ServerEnvelope {
+            id: Uuid::new_v4(),
+            sender_uuid,
+            envelope_type: ServerEnvelopeType::Message,
+            payload: payload.to_vec(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn envelope_ids(envelopes: &[ServerEnvelope]) -> Vec<Uuid> {
+        envelopes.iter().map(|envelope| envelope.id).collect()
+    }
+
+    #[test]
+    fn envelopes_are_queued_in_order() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        let alice = add_client(storage);
+        let bob = add_client(storage);
+
+        let first = envelope(alice, b"first");
+        let second = envelope(alice, b"second");
+        storage.enqueue_envelope(bob, &first).unwrap();
+        storage.enqueue_envelope(bob, &second).unwrap();
+
+        let envelopes = storage.get_envelopes(bob).unwrap();
+        assert_eq!(envelope_ids(&envelopes), vec![first.id, second.id]);
+        assert_eq!(envelopes[0].sender_uuid, alice);
+        assert_eq!(envelopes[0].envelope_type, ServerEnvelopeType::Message);
+        assert_eq!(envelopes[0].payload, b"first");
+
+        assert_eq!(
+            storage
+                .get_next_envelope(bob)
+                .unwrap()
+                .map(|envelope| envelope.id),
+            Some(first.id)
+        );
+
+        // Mailboxes are not shared
+        assert!(storage.get_envelopes(alice).unwrap().is_empty());
+        assert!(storage.get_next_envelope(alice).unwrap().is_none());
+    }
+
+    #[test]
+    fn enqueue_envelope_rejects_unknown_recipient() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        let alice = add_client(storage);
+
+        assert!(storage
+            .enqueue_envelope(Uuid::new_v4(), &envelope(alice, b"hello"))
+            .is_err());
+    }
+
+    #[test]
+    fn delete_envelope_only_removes_own_envelopes() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        let alice = add_client(storage);
+        let bob = add_client(storage);
+
+        let first = envelope(alice, b"first");
+        let second = envelope(alice, b"second");
+        storage.enqueue_envelope(bob, &first).unwrap();
+        storage.enqueue_envelope(bob, &second).unwrap();
+
+        // Another client cannot delete an envelope from the mailbox
+        assert!(matches!(
+            storage.delete_envelope(alice, &first.id),
+            Err(StorageInterfaceError::ServerStorageError(
+                ServerStorageError::EnvelopeNotFound
+            ))
+        ));
+
+        storage.delete_envelope(bob, &first.id).unwrap();
+        assert_eq!(
+            envelope_ids(&storage.get_envelopes(bob).unwrap()),
+            vec![second.id]
+        );
+
+        // An envelope can only be acknowledged once
+        assert!(matches!(
+            storage.delete_envelope(bob, &first.id),
+            Err(StorageInterfaceError::ServerStorageError(
+                ServerStorageError::EnvelopeNotFound
+            ))
+        ));
+    }
+
+    #[test]
+    fn version_1_database_is_upgraded() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+        let alice = add_client(storage);
+        let bob = add_client(storage);
+
+        // Bring the database back to version 1, before the mailbox existed
+        let conn = storage.pool.get().unwrap();
+        conn.execute_batch("DROP TABLE mailbox_envelope; PRAGMA user_version = 1;")
+            .unwrap();
+
+        storage.init_server().unwrap();
+        let version: i32 = conn
+            .query_row("PRAGMA user_version;", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, SERVER_SCHEMA_VERSION);
+
+        // The clients are kept and the mailbox works
+        let hello = envelope(alice, b"hello");
+        storage.enqueue_envelope(bob, &hello).unwrap();
+        assert_eq!(
+            envelope_ids(&storage.get_envelopes(bob).unwrap()),
+            vec![hello.id]
+        );
+    }
+
+    #[test]
+    fn newer_database_is_rejected() {
+        let database = TestDatabase::new();
+        let storage = &database.storage;
+
+        let conn = storage.pool.get().unwrap();
+        conn.execute_batch(&format!(
+            "PRAGMA user_version = {};",
+            SERVER_SCHEMA_VERSION + 1
+        ))
+        .unwrap();
+
+        assert!(storage.init_server().is_err());
     }
 }
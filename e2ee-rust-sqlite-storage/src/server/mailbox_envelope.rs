@@ -0,0 +1,124 @@
+use e2ee_rust_common::{
+    messages::server::server_envelope::{ServerEnvelope, ServerEnvelopeType},
+    storage::{errors::StorageInterfaceError, server::errors::ServerStorageError},
+};
+use rusqlite::{params, Connection, Row};
+use uuid::Uuid;
+
+use crate::{
+    utils::{
+        datetime_to_timestamp, insert_returning_id, perform_update, timestamp_to_datetime,
+        uuid_from_bytes,
+    },
+    ToStorageInterfaceError,
+};
+
+use super::consts::{
+    REQ_DELETE_MAILBOX_ENVELOPE, REQ_INSERT_MAILBOX_ENVELOPE, REQ_QUERY_MAILBOX_ENVELOPES,
+    REQ_QUERY_NEXT_MAILBOX_ENVELOPE,
+};
+
+pub fn insert_mailbox_envelope(
+    envelope: &ServerEnvelope,
+    recipient_client_id: i32,
+    connection: &Connection,
+) -> Result<i32, StorageInterfaceError> {
+    // Insert the envelope and return the new ID
+    insert_returning_id(
+        REQ_INSERT_MAILBOX_ENVELOPE,
+        params![
+            envelope.id.as_bytes(),
+            recipient_client_id,
+            envelope.sender_uuid.as_bytes(),
+            envelope.envelope_type.id(),
+            envelope.payload,
+            datetime_to_timestamp(&envelope.timestamp)
+        ],
+        "mailbox_envelope",
+        connection,
+    )
+}
+
+pub fn get_mailbox_envelopes(
+    recipient_client_id: i32,
+    connection: &Connection,
+) -> Result<Vec<ServerEnvelope>, StorageInterfaceError> {
+    // Result
+    let mut res: Vec<ServerEnvelope> = vec![];
+
+    // Create the statement
+    let mut statement = connection
+        .prepare_cached(REQ_QUERY_MAILBOX_ENVELOPES)
+        .to_storage_interface_error()?;
+
+    // Execute the statement
+    let mut rows = statement
+        .query([recipient_client_id])
+        .to_storage_interface_error()?;
+
+    // Loop through the rows
+    while let Some(row) = rows.next().to_storage_interface_error()? {
+        res.push(envelope_from_row(row)?);
+    }
+
+    Ok(res)
+}
+
+pub fn get_next_mailbox_envelope(
+    recipient_client_id: i32,
+    connection: &Connection,
+) -> Result<Option<ServerEnvelope>, StorageInterfaceError> {
+    // Create the statement
+    let mut statement = connection
+        .prepare_cached(REQ_QUERY_NEXT_MAILBOX_ENVELOPE)
+        .to_storage_interface_error()?;
+
+    // Execute the statement, only the oldest envelope is read
+    let mut rows = statement
+        .query([recipient_client_id])
+        .to_storage_interface_error()?;
+
+    match rows.next().to_storage_interface_error()? {
+        Some(row) => Ok(Some(envelope_from_row(row)?)),
+        None => Ok(None),
+    }
+}
+
+fn envelope_from_row(row: &Row) -> Result<ServerEnvelope, StorageInterfaceError> {
+    let uuid: Vec<u8> = row.get(0).to_storage_interface_error()?;
+    let sender_uuid: Vec<u8> = row.get(1).to_storage_interface_error()?;
+    let envelope_type: u8 = row.get(2).to_storage_interface_error()?;
+    let payload: Vec<u8> = row.get(3).to_storage_interface_error()?;
+    let timestamp: i64 = row.get(4).to_storage_interface_error()?;
+
+    Ok(ServerEnvelope {
+        id: uuid_from_bytes(&uuid)?,
+        sender_uuid: uuid_from_bytes(&sender_uuid)?,
+        envelope_type: ServerEnvelopeType::from_id(envelope_type).ok_or(
+            StorageInterfaceError::CustomError(format!("Invalid envelope type: {}", envelope_type)),
+        )?,
+        payload,
+        timestamp: timestamp_to_datetime(timestamp)?,
+    })
+}
+
+pub fn delete_mailbox_envelope(
+    recipient_client_id: i32,
+    envelope_id: &Uuid,
+    connection: &Connection,
+) -> Result<(), StorageInterfaceError> {
+    // Delete the envelope, making sure it belongs to the recipient
+    let deleted = perform_update(
+        REQ_DELETE_MAILBOX_ENVELOPE,
+        params![recipient_client_id, envelope_id.as_bytes()],
+        connection,
+    )?;
+
+    if deleted == 0 {
+        return Err(StorageInterfaceError::ServerStorageError(
+            ServerStorageError::EnvelopeNotFound,
+        ));
+    }
+
+    Ok(())
+}
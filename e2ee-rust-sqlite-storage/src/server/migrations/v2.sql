@@ -0,0 +1,17 @@
+-- Upgrades a version 1 server database to version 2
+PRAGMA user_version = 2;
+
+-- Create the mailbox envelopes table
+CREATE TABLE IF NOT EXISTS mailbox_envelope (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    uuid BLOB NOT NULL UNIQUE,
+    recipient_client_id INTEGER NOT NULL,
+    sender_uuid BLOB NOT NULL,
+    envelope_type INTEGER NOT NULL,
+    payload BLOB NOT NULL,
+    timestamp INTEGER NOT NULL,
+    FOREIGN KEY (recipient_client_id) REFERENCES clients(id)
+);
+
+-- Index the mailboxes, so that the oldest envelope of a client is found without a scan
+CREATE INDEX IF NOT EXISTS mailbox_envelope_recipient ON mailbox_envelope (recipient_client_id, id);
\ No newline at end of file
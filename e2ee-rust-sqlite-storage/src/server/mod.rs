@@ -5,6 +5,7 @@ pub mod identified_elliptic_curve_public_key;
 pub mod identified_pqkem_public_key;
 pub mod implementation;
 pub mod key_bundle;
+pub mod mailbox_envelope;
 pub mod one_time_curve_prekey;
 pub mod pqkem_public_key;
 pub mod signed_curve_prekey;
@@ -47,9 +47,7 @@ pub fn get_signed_curve_prekey(
 
         Ok(SignedCurvePrekey {
             identified_public_key,
-            signature: signature
-                .try_into()
-                .map_err(|_| StorageInterfaceError::BadSignature)?,
+            signature,
         })
     } else {
         return Err(StorageInterfaceError::ServerStorageError(
@@ -46,9 +46,7 @@ pub fn get_signed_pqkem_prekey(
 
         Ok(SignedPQKEMPrekey {
             identified_public_key,
-            signature: signature
-                .try_into()
-                .map_err(|_| StorageInterfaceError::BadSignature)?,
+            signature,
         })
     } else {
         return Err(StorageInterfaceError::ServerStorageError(
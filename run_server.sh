@@ -1,3 +1,3 @@
 #!/bin/bash
 
-clear && RUST_LOG=debug cargo run -p e2ee-rust-server --release
\ No newline at end of file
+clear && RUST_LOG=debug cargo run -p e2ee-rust-server --release -- "$@"
\ No newline at end of file